@@ -56,6 +56,27 @@ impl GUIApplication {
 		self.platform.borrow_mut()
 	}
 
+	/// Returns the platform context of the enabled platform backend with its concrete type.
+	///
+	/// Useful to access backend specific functionality like the bitmaps of the headless platform.
+	pub fn platform(&self) -> Ref<Context> {
+		self.platform.borrow()
+	}
+
+	/// Mutable version of [GUIApplication::platform()].
+	///
+	/// Must not be held while events get dispatched,
+	/// as widgets access the platform context themselves, e.g. to capture the cursor.
+	pub fn platform_mut(&self) -> RefMut<Context> {
+		self.platform.borrow_mut()
+	}
+
+	/// Dispatches simulated user input through the headless platform.
+	#[cfg(feature = "headless")]
+	pub fn inject_input(&self, input: crate::platform::HeadlessInput) {
+		Context::inject_input(&self.platform, &self.event, input);
+	}
+
 	pub fn run(&self) {
 		PlatformContext::run(&self.platform,&self.event);
 	}
@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use crate::{events::input::Modifiers, util::WidgetRef, widgets::Widget};
+use winit::event::VirtualKeyCode;
+
+/// Names of the commands the built-in widgets handle.
+pub mod standard {
+	pub const COPY: &str = "copy";
+	pub const CUT: &str = "cut";
+	pub const PASTE: &str = "paste";
+	pub const UNDO: &str = "undo";
+	pub const REDO: &str = "redo";
+	pub const SELECT_ALL: &str = "select_all";
+}
+
+/// A key pressed together with modifier keys, like Ctrl+Shift+S.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+	pub modifiers: Modifiers,
+	pub key: VirtualKeyCode,
+}
+
+impl KeyChord {
+	pub fn new(modifiers: Modifiers, key: VirtualKeyCode) -> Self {
+		Self { modifiers, key }
+	}
+}
+
+/// Parses chords like `Ctrl+Shift+S`, names are case insensitive.
+/// The plus key is written as `Plus` or as `+` at the end, like `Ctrl++`.
+impl FromStr for KeyChord {
+	type Err = ParseBindingError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let chord = s.trim();
+		// a trailing plus right after a separator (or on its own) is the plus key
+		let (parts, plus) = match chord.strip_suffix('+').map(str::trim_end) {
+			Some("") => ("", true),
+			Some(rest) if rest.ends_with('+') => (&rest[..rest.len() - 1], true),
+			_ => (chord, false),
+		};
+		let mut modifiers = Modifiers::empty();
+		let mut key = plus.then_some(VirtualKeyCode::Plus);
+		for part in parts.split('+').map(str::trim).filter(|_| !parts.is_empty()) {
+			match part.to_ascii_lowercase().as_str() {
+				"shift" => modifiers |= Modifiers::SHIFT,
+				"ctrl" | "control" => modifiers |= Modifiers::CTRL,
+				"alt" => modifiers |= Modifiers::ALT,
+				"super" | "cmd" | "win" | "logo" => modifiers |= Modifiers::SUPER,
+				name if key.is_none() => {
+					key = Some(key_from_name(name).ok_or_else(|| ParseBindingError::UnknownKey(part.to_string()))?);
+				}
+				_ => return Err(ParseBindingError::InvalidChord(s.to_string())),
+			}
+		}
+		match key {
+			Some(key) => Ok(KeyChord { modifiers, key }),
+			None => Err(ParseBindingError::InvalidChord(s.to_string())),
+		}
+	}
+}
+
+impl Display for KeyChord {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for (modifier, name) in [
+			(Modifiers::CTRL, "Ctrl"),
+			(Modifiers::ALT, "Alt"),
+			(Modifiers::SHIFT, "Shift"),
+			(Modifiers::SUPER, "Super"),
+		] {
+			if self.modifiers.contains(modifier) {
+				write!(f, "{}+", name)?;
+			}
+		}
+		match KEY_NAMES.iter().find(|(_, key)| *key == self.key) {
+			Some((name, _)) => write!(f, "{}", name),
+			None => write!(f, "{:?}", self.key),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum ParseBindingError {
+	UnknownKey(String),
+	InvalidChord(String),
+	/// The line (starting at 1) of a bindings config is not of the form `command = chord`.
+	InvalidLine(usize),
+	Io(std::io::Error),
+}
+
+impl Display for ParseBindingError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ParseBindingError::UnknownKey(key) => write!(f, "unknown key '{}'", key),
+			ParseBindingError::InvalidChord(chord) => write!(f, "invalid key chord '{}'", chord),
+			ParseBindingError::InvalidLine(line) => write!(f, "invalid binding in line {}", line),
+			ParseBindingError::Io(err) => write!(f, "{}", err),
+		}
+	}
+}
+
+impl std::error::Error for ParseBindingError {}
+
+impl From<std::io::Error> for ParseBindingError {
+	fn from(err: std::io::Error) -> Self {
+		ParseBindingError::Io(err)
+	}
+}
+
+/// Where a key binding is active.
+#[derive(Clone, PartialEq)]
+pub enum BindingScope {
+	Application,
+	/// Active while the focus is within the given window.
+	Window(WidgetRef<dyn Widget>),
+	/// Active while the given widget or one of its descendants has the focus.
+	Widget(WidgetRef<dyn Widget>),
+}
+
+#[derive(Clone)]
+pub struct KeyBinding {
+	pub chord: KeyChord,
+	pub command: String,
+	pub scope: BindingScope,
+}
+
+impl KeyBinding {
+	pub fn new(chord: KeyChord, command: impl Into<String>, scope: BindingScope) -> Self {
+		Self {
+			chord,
+			command: command.into(),
+			scope,
+		}
+	}
+}
+
+/// Maps key chords to named commands.
+///
+/// When a key gets pressed, the bindings of its chord get resolved before the key event gets dispatched.
+/// The command gets sent as [WidgetEvent::OnCommand](crate::events::WidgetEvent::OnCommand)
+/// to the focused widget and bubbles up to its scope.
+/// If no widget handles it, the handler registered with [CommandRegistry::register()] gets called.
+///
+/// Bindings of widget scopes closer to the focused widget win over bindings of outer scopes,
+/// window bindings win over application bindings.
+pub struct CommandRegistry {
+	bindings: Vec<KeyBinding>,
+	handlers: HashMap<String, Box<dyn Fn(usize)>>,
+}
+
+impl Default for CommandRegistry {
+	/// Creates a registry with the standard editing commands bound to their common chords.
+	fn default() -> Self {
+		let mut registry = Self {
+			bindings: Vec::new(),
+			handlers: HashMap::new(),
+		};
+		for (key, command) in [
+			(VirtualKeyCode::C, standard::COPY),
+			(VirtualKeyCode::X, standard::CUT),
+			(VirtualKeyCode::V, standard::PASTE),
+			(VirtualKeyCode::Z, standard::UNDO),
+			(VirtualKeyCode::Y, standard::REDO),
+			(VirtualKeyCode::A, standard::SELECT_ALL),
+		] {
+			registry.bind(KeyBinding::new(
+				KeyChord::new(Modifiers::CTRL, key),
+				command,
+				BindingScope::Application,
+			));
+		}
+		registry.bind(KeyBinding::new(
+			KeyChord::new(Modifiers::CTRL | Modifiers::SHIFT, VirtualKeyCode::Z),
+			standard::REDO,
+			BindingScope::Application,
+		));
+		registry
+	}
+}
+
+impl CommandRegistry {
+	/// Registers a handler called with the keyboard index if no widget handled the command.
+	pub fn register(&mut self, command: impl Into<String>, handler: impl Fn(usize) + 'static) {
+		self.handlers.insert(command.into(), Box::new(handler));
+	}
+
+	pub fn unregister(&mut self, command: &str) {
+		self.handlers.remove(command);
+	}
+
+	/// Adds a binding, replacing any binding of the same chord in the same scope.
+	pub fn bind(&mut self, binding: KeyBinding) {
+		self.bindings
+			.retain(|other| other.chord != binding.chord || other.scope != binding.scope);
+		self.bindings.push(binding);
+	}
+
+	pub fn unbind(&mut self, chord: KeyChord, scope: &BindingScope) {
+		self.bindings
+			.retain(|binding| binding.chord != chord || &binding.scope != scope);
+	}
+
+	/// Removes all bindings of the given scope, e.g. when its widget gets removed.
+	pub fn unbind_scope(&mut self, scope: &BindingScope) {
+		self.bindings.retain(|binding| &binding.scope != scope);
+	}
+
+	pub fn bindings(&self) -> &[KeyBinding] {
+		&self.bindings
+	}
+
+	/// Returns the chords bound to the given command.
+	pub fn chords_of(&self, command: &str) -> Vec<KeyChord> {
+		self.bindings
+			.iter()
+			.filter(|binding| binding.command == command)
+			.map(|binding| binding.chord)
+			.collect()
+	}
+
+	/// Remaps commands to the chords given in a config.
+	///
+	/// Every non-empty line not starting with `#` has the form `command = Chord, Chord, ...`.
+	/// The listed chords replace the previous chords of the command in all scopes the command was bound in,
+	/// or in the application scope if it was not bound yet. An empty chord list unbinds the command.
+	/// Nothing gets changed if the config has an error.
+	pub fn load_bindings(&mut self, config: &str) -> Result<(), ParseBindingError> {
+		let mut remaps = Vec::new();
+		for (index, line) in config.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let (command, chords) = line
+				.split_once('=')
+				.ok_or(ParseBindingError::InvalidLine(index + 1))?;
+			let command = command.trim();
+			if command.is_empty() {
+				return Err(ParseBindingError::InvalidLine(index + 1));
+			}
+			let chords = chords
+				.split(',')
+				.map(str::trim)
+				.filter(|chord| !chord.is_empty())
+				.map(KeyChord::from_str)
+				.collect::<Result<Vec<_>, _>>()?;
+			remaps.push((command.to_string(), chords));
+		}
+
+		for (command, chords) in remaps {
+			let mut scopes: Vec<BindingScope> = Vec::new();
+			for binding in self.bindings.iter().filter(|binding| binding.command == command) {
+				if !scopes.contains(&binding.scope) {
+					scopes.push(binding.scope.clone());
+				}
+			}
+			if scopes.is_empty() {
+				scopes.push(BindingScope::Application);
+			}
+			self.bindings.retain(|binding| binding.command != command);
+			for scope in scopes {
+				for chord in &chords {
+					self.bind(KeyBinding::new(*chord, command.clone(), scope.clone()));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	pub fn load_bindings_file(&mut self, path: impl AsRef<Path>) -> Result<(), ParseBindingError> {
+		let config = std::fs::read_to_string(path)?;
+		self.load_bindings(&config)
+	}
+
+	/// Returns the bindings of the chord active for the focused widget, the most specific scope first.
+	pub fn resolve(
+		&self,
+		chord: KeyChord,
+		window: &WidgetRef<dyn Widget>,
+		focused: Option<&WidgetRef<dyn Widget>>,
+	) -> Vec<&KeyBinding> {
+		// the focused widget and its ancestors, the closest first
+		let mut ancestors = Vec::new();
+		let mut widget = focused.cloned();
+		while let Some(current) = widget {
+			widget = current.get().get_parent();
+			ancestors.push(current);
+		}
+
+		let mut matches: Vec<(usize, &KeyBinding)> = self
+			.bindings
+			.iter()
+			.filter(|binding| binding.chord == chord)
+			.filter_map(|binding| {
+				let priority = match &binding.scope {
+					BindingScope::Widget(scope) => ancestors.iter().position(|ancestor| ancestor == scope)?,
+					BindingScope::Window(scope) if scope == window => usize::MAX - 1,
+					BindingScope::Window(_) => return None,
+					BindingScope::Application => usize::MAX,
+				};
+				Some((priority, binding))
+			})
+			.collect();
+		matches.sort_by_key(|(priority, _)| *priority);
+		matches.into_iter().map(|(_, binding)| binding).collect()
+	}
+
+	/// Calls the handler of the command, returns false if there is none.
+	pub fn execute(&self, command: &str, keyboard_index: usize) -> bool {
+		match self.handlers.get(command) {
+			Some(handler) => {
+				handler(keyboard_index);
+				true
+			}
+			None => false,
+		}
+	}
+}
+
+/// Returns true if the key is a modifier key, which never triggers a binding on its own.
+pub fn is_modifier_key(key: VirtualKeyCode) -> bool {
+	matches!(
+		key,
+		VirtualKeyCode::LShift
+			| VirtualKeyCode::RShift
+			| VirtualKeyCode::LControl
+			| VirtualKeyCode::RControl
+			| VirtualKeyCode::LAlt
+			| VirtualKeyCode::RAlt
+			| VirtualKeyCode::LWin
+			| VirtualKeyCode::RWin
+	)
+}
+
+fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+	KEY_NAMES
+		.iter()
+		.find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+		.or_else(|| KEY_ALIASES.iter().find(|(alias, _)| alias.eq_ignore_ascii_case(name)))
+		.map(|(_, key)| *key)
+}
+
+/// Names used for keys in chords, the first name of a key is used to display it.
+const KEY_NAMES: &[(&str, VirtualKeyCode)] = &[
+	("A", VirtualKeyCode::A),
+	("B", VirtualKeyCode::B),
+	("C", VirtualKeyCode::C),
+	("D", VirtualKeyCode::D),
+	("E", VirtualKeyCode::E),
+	("F", VirtualKeyCode::F),
+	("G", VirtualKeyCode::G),
+	("H", VirtualKeyCode::H),
+	("I", VirtualKeyCode::I),
+	("J", VirtualKeyCode::J),
+	("K", VirtualKeyCode::K),
+	("L", VirtualKeyCode::L),
+	("M", VirtualKeyCode::M),
+	("N", VirtualKeyCode::N),
+	("O", VirtualKeyCode::O),
+	("P", VirtualKeyCode::P),
+	("Q", VirtualKeyCode::Q),
+	("R", VirtualKeyCode::R),
+	("S", VirtualKeyCode::S),
+	("T", VirtualKeyCode::T),
+	("U", VirtualKeyCode::U),
+	("V", VirtualKeyCode::V),
+	("W", VirtualKeyCode::W),
+	("X", VirtualKeyCode::X),
+	("Y", VirtualKeyCode::Y),
+	("Z", VirtualKeyCode::Z),
+	("0", VirtualKeyCode::Key0),
+	("1", VirtualKeyCode::Key1),
+	("2", VirtualKeyCode::Key2),
+	("3", VirtualKeyCode::Key3),
+	("4", VirtualKeyCode::Key4),
+	("5", VirtualKeyCode::Key5),
+	("6", VirtualKeyCode::Key6),
+	("7", VirtualKeyCode::Key7),
+	("8", VirtualKeyCode::Key8),
+	("9", VirtualKeyCode::Key9),
+	("F1", VirtualKeyCode::F1),
+	("F2", VirtualKeyCode::F2),
+	("F3", VirtualKeyCode::F3),
+	("F4", VirtualKeyCode::F4),
+	("F5", VirtualKeyCode::F5),
+	("F6", VirtualKeyCode::F6),
+	("F7", VirtualKeyCode::F7),
+	("F8", VirtualKeyCode::F8),
+	("F9", VirtualKeyCode::F9),
+	("F10", VirtualKeyCode::F10),
+	("F11", VirtualKeyCode::F11),
+	("F12", VirtualKeyCode::F12),
+	("Escape", VirtualKeyCode::Escape),
+	("Tab", VirtualKeyCode::Tab),
+	("Space", VirtualKeyCode::Space),
+	("Enter", VirtualKeyCode::Return),
+	("Backspace", VirtualKeyCode::Back),
+	("Delete", VirtualKeyCode::Delete),
+	("Insert", VirtualKeyCode::Insert),
+	("Home", VirtualKeyCode::Home),
+	("End", VirtualKeyCode::End),
+	("PageUp", VirtualKeyCode::PageUp),
+	("PageDown", VirtualKeyCode::PageDown),
+	("Left", VirtualKeyCode::Left),
+	("Right", VirtualKeyCode::Right),
+	("Up", VirtualKeyCode::Up),
+	("Down", VirtualKeyCode::Down),
+	("Plus", VirtualKeyCode::Plus),
+	("Minus", VirtualKeyCode::Minus),
+	("Equals", VirtualKeyCode::Equals),
+	("Comma", VirtualKeyCode::Comma),
+	("Period", VirtualKeyCode::Period),
+	("Slash", VirtualKeyCode::Slash),
+	("Backslash", VirtualKeyCode::Backslash),
+	("Semicolon", VirtualKeyCode::Semicolon),
+	("Apostrophe", VirtualKeyCode::Apostrophe),
+	("Grave", VirtualKeyCode::Grave),
+	("LBracket", VirtualKeyCode::LBracket),
+	("RBracket", VirtualKeyCode::RBracket),
+];
+
+const KEY_ALIASES: &[(&str, VirtualKeyCode)] = &[
+	("Esc", VirtualKeyCode::Escape),
+	("Return", VirtualKeyCode::Return),
+	("Back", VirtualKeyCode::Back),
+	("Del", VirtualKeyCode::Delete),
+	("Ins", VirtualKeyCode::Insert),
+	("PgUp", VirtualKeyCode::PageUp),
+	("PgDown", VirtualKeyCode::PageDown),
+];
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		events::input::Modifiers,
+		util::WidgetRef,
+		widgets::{ButtonWidget, Widget},
+	};
+	use super::{standard, BindingScope, CommandRegistry, KeyBinding, KeyChord, ParseBindingError, KEY_NAMES};
+	use winit::event::VirtualKeyCode;
+
+	fn chord(s: &str) -> KeyChord {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn chords_round_trip_through_display() {
+		let modifier_sets = [
+			Modifiers::empty(),
+			Modifiers::CTRL,
+			Modifiers::CTRL | Modifiers::SHIFT,
+			Modifiers::ALT | Modifiers::SUPER,
+			Modifiers::all(),
+		];
+		for (_, key) in KEY_NAMES {
+			for modifiers in modifier_sets {
+				let chord = KeyChord::new(modifiers, *key);
+				assert_eq!(chord.to_string().parse::<KeyChord>().unwrap(), chord, "{}", chord);
+			}
+		}
+	}
+
+	#[test]
+	fn chords_parse_case_insensitive_names_and_aliases() {
+		assert_eq!(
+			chord(" ctrl + SHIFT + esc "),
+			KeyChord::new(Modifiers::CTRL | Modifiers::SHIFT, VirtualKeyCode::Escape)
+		);
+		assert_eq!(chord("Cmd+PgDown"), KeyChord::new(Modifiers::SUPER, VirtualKeyCode::PageDown));
+	}
+
+	#[test]
+	fn plus_key_gets_written_as_plus_sign() {
+		let ctrl_plus = KeyChord::new(Modifiers::CTRL, VirtualKeyCode::Plus);
+		assert_eq!(chord("Ctrl++"), ctrl_plus);
+		assert_eq!(chord("Ctrl + +"), ctrl_plus);
+		assert_eq!(chord("Ctrl+Plus"), ctrl_plus);
+		assert_eq!(chord("+"), KeyChord::new(Modifiers::empty(), VirtualKeyCode::Plus));
+		assert!(matches!("A++".parse::<KeyChord>(), Err(ParseBindingError::InvalidChord(_))));
+	}
+
+	#[test]
+	fn invalid_chords_fail() {
+		assert!(matches!("Ctrl+Foo".parse::<KeyChord>(), Err(ParseBindingError::UnknownKey(key)) if key == "Foo"));
+		assert!(matches!("Ctrl+Shift".parse::<KeyChord>(), Err(ParseBindingError::InvalidChord(_))));
+		assert!(matches!("A+B".parse::<KeyChord>(), Err(ParseBindingError::InvalidChord(_))));
+	}
+
+	#[test]
+	fn load_bindings_skips_comments_and_blank_lines() {
+		let mut registry = CommandRegistry::default();
+
+		registry
+			.load_bindings("# editing\n\n   \n  copy = Ctrl+Insert, Ctrl+C  \n# paste = Ctrl+P\n")
+			.unwrap();
+
+		assert_eq!(registry.chords_of(standard::COPY), vec![chord("Ctrl+Insert"), chord("Ctrl+C")]);
+		assert_eq!(registry.chords_of(standard::PASTE), vec![chord("Ctrl+V")]);
+	}
+
+	#[test]
+	fn load_bindings_lets_later_lines_win() {
+		let mut registry = CommandRegistry::default();
+
+		// the same command twice and the same chord for two commands
+		registry
+			.load_bindings("copy = Ctrl+K\ncopy = Ctrl+J\npaste = Ctrl+J")
+			.unwrap();
+
+		assert!(registry.chords_of(standard::COPY).is_empty());
+		assert_eq!(registry.chords_of(standard::PASTE), vec![chord("Ctrl+J")]);
+	}
+
+	#[test]
+	fn load_bindings_keeps_scope_of_remapped_command() {
+		let mut registry = CommandRegistry::default();
+		let window: WidgetRef<dyn Widget> = ButtonWidget::new("").build();
+		let scope = BindingScope::Window(window);
+		registry.bind(KeyBinding::new(chord("Ctrl+F"), "find", scope.clone()));
+
+		registry.load_bindings("find = F3, Ctrl+F\nclose = Ctrl+W").unwrap();
+
+		let find: Vec<_> = registry.bindings().iter().filter(|binding| binding.command == "find").collect();
+		assert_eq!(find.len(), 2);
+		assert!(find.iter().all(|binding| binding.scope == scope));
+		let close: Vec<_> = registry.bindings().iter().filter(|binding| binding.command == "close").collect();
+		assert!(close.len() == 1 && close[0].scope == BindingScope::Application);
+	}
+
+	#[test]
+	fn load_bindings_changes_nothing_on_error() {
+		let mut registry = CommandRegistry::default();
+
+		let result = registry.load_bindings("copy = Ctrl+K\npaste Ctrl+V");
+
+		assert!(matches!(result, Err(ParseBindingError::InvalidLine(2))));
+		assert_eq!(registry.chords_of(standard::COPY), vec![chord("Ctrl+C")]);
+		assert!(matches!(
+			registry.load_bindings("copy = Ctrl+Foo"),
+			Err(ParseBindingError::UnknownKey(_))
+		));
+	}
+}
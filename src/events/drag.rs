@@ -0,0 +1,85 @@
+use std::any::Any;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::rc::Rc;
+use crate::{util::WidgetRef, widgets::Widget};
+use cgmath::Vector2;
+use skia_safe::scalar;
+
+/// The data carried by a drag, can be of any type.
+///
+/// Cloning is cheap, all clones share the same data.
+#[derive(Clone)]
+pub struct DragPayload(Rc<dyn Any>);
+
+impl DragPayload {
+	pub fn new<T: 'static>(data: T) -> Self {
+		Self(Rc::new(data))
+	}
+
+	/// Returns the data if it is of the given type.
+	pub fn get<T: 'static>(&self) -> Option<&T> {
+		self.0.downcast_ref()
+	}
+
+	pub fn is<T: 'static>(&self) -> bool {
+		self.0.is::<T>()
+	}
+}
+
+/// Payload of files dragged in from the operating system.
+pub struct DroppedFiles(pub Vec<PathBuf>);
+
+/// A drag and drop operation of a cursor in progress.
+pub(crate) struct DragState {
+	/// The widget that started the drag, None if it came from outside, like files from the OS.
+	pub source: Option<WidgetRef<dyn Widget>>,
+	pub payload: DragPayload,
+	/// Widget painted at the cursor while dragging.
+	pub visual: Option<WidgetRef<dyn Widget>>,
+	/// The root of the widget tree the drag is over, normally the window, and the position within it.
+	pub root: Option<WidgetRef<dyn Widget>>,
+	pub pos: Vector2<scalar>,
+	pub over_widgets: HashSet<WidgetRef<dyn Widget>>,
+	/// The widget that accepted the drop at the current position.
+	pub target: Option<WidgetRef<dyn Widget>>,
+}
+
+impl DragState {
+	pub fn new(
+		source: Option<WidgetRef<dyn Widget>>,
+		payload: DragPayload,
+		visual: Option<WidgetRef<dyn Widget>>,
+	) -> Self {
+		Self {
+			source,
+			payload,
+			visual,
+			root: None,
+			pos: Vector2::new(0.0, 0.0),
+			over_widgets: HashSet::new(),
+			target: None,
+		}
+	}
+
+	/// Moves the drag to the given position of the window, the visual follows as overlay of the window.
+	pub fn move_to(&mut self, root: WidgetRef<dyn Widget>, pos: Vector2<scalar>) {
+		if self.root.as_ref() != Some(&root) {
+			self.hide_visual();
+		}
+		if let (Some(visual), Some(window)) = (&self.visual, root.get().as_window()) {
+			window.show_overlay(visual.clone(), pos);
+		}
+		self.root = Some(root);
+		self.pos = pos;
+	}
+
+	/// Removes the visual from the window the drag is over.
+	pub fn hide_visual(&self) {
+		if let (Some(visual), Some(root)) = (&self.visual, &self.root) {
+			if let Some(window) = root.get().as_window() {
+				window.hide_overlay(visual);
+			}
+		}
+	}
+}
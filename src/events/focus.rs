@@ -0,0 +1,130 @@
+use crate::{util::WidgetRef, widgets::Widget};
+
+/// Collects all focusable widgets of the given widget tree in focus navigation order.
+///
+/// Widgets with a lower tab index come first,
+/// widgets with the same tab index are ordered as they appear in the tree (depth first).
+pub fn collect_focusable(scope: &WidgetRef<dyn Widget>) -> Vec<WidgetRef<dyn Widget>> {
+	let mut widgets = Vec::new();
+	collect_focusable_recursive(scope, &mut widgets);
+	// stable sort to keep the tree order for equal tab indices
+	widgets.sort_by_key(|widget| widget.get().tab_index());
+	widgets
+}
+
+fn collect_focusable_recursive(widget: &WidgetRef<dyn Widget>, widgets: &mut Vec<WidgetRef<dyn Widget>>) {
+	if widget.get().is_focusable() {
+		widgets.push(widget.clone());
+	}
+	for child in widget.get().get_children() {
+		collect_focusable_recursive(&child, widgets);
+	}
+}
+
+/// Returns the widget that should get the focus next when navigating from the currently
+/// focused widget within the given focus scope.
+///
+/// If the currently focused widget is not part of the scope, navigation starts at the first
+/// (or last if reversed) focusable widget. Navigation wraps around at both ends.
+pub fn next_focus(
+	scope: &WidgetRef<dyn Widget>,
+	current: Option<&WidgetRef<dyn Widget>>,
+	reverse: bool,
+) -> Option<WidgetRef<dyn Widget>> {
+	let widgets = collect_focusable(scope);
+	if widgets.is_empty() {
+		return None;
+	}
+	let index = current.and_then(|current| widgets.iter().position(|widget| widget == current));
+	let next = match (index, reverse) {
+		(None, false) => 0,
+		(None, true) => widgets.len() - 1,
+		(Some(index), false) => (index + 1) % widgets.len(),
+		(Some(index), true) => (index + widgets.len() - 1) % widgets.len(),
+	};
+	Some(widgets[next].clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		events::EventContext,
+		util::WidgetRef,
+		widgets::{ButtonWidget, Growth, LinearPanel, LinearPanelDirection, TextBlockWidget, Widget},
+	};
+	use super::{collect_focusable, next_focus};
+
+	fn button(tab_index: i32) -> WidgetRef<dyn Widget> {
+		ButtonWidget::new("").tab_index(tab_index).build()
+	}
+
+	/// Returns the tree and its buttons in tree order, with the tab indices 1, 0, 1, 0 and a nested panel.
+	fn tree() -> (WidgetRef<dyn Widget>, Vec<WidgetRef<dyn Widget>>) {
+		let buttons: Vec<_> = [1, 0, 1, 0].into_iter().map(button).collect();
+		let nested = LinearPanel::new(LinearPanelDirection::Vertical)
+			.slot(buttons[1].clone(), Growth::Fit)
+			.slot(TextBlockWidget::new().build(), Growth::Fit)
+			.slot(buttons[2].clone(), Growth::Fit)
+			.build();
+		let root: WidgetRef<dyn Widget> = LinearPanel::new(LinearPanelDirection::Horizontal)
+			.slot(buttons[0].clone(), Growth::Fit)
+			.slot(nested, Growth::Fit)
+			.slot(buttons[3].clone(), Growth::Fit)
+			.build();
+		(root, buttons)
+	}
+
+	#[test]
+	fn collect_orders_by_tab_index_then_tree_order() {
+		let (root, buttons) = tree();
+
+		let focusable = collect_focusable(&root);
+
+		let expected = vec![buttons[1].clone(), buttons[3].clone(), buttons[0].clone(), buttons[2].clone()];
+		assert!(focusable == expected);
+	}
+
+	#[test]
+	fn next_focus_wraps_around() {
+		let (root, buttons) = tree();
+
+		assert!(next_focus(&root, None, false) == Some(buttons[1].clone()));
+		assert!(next_focus(&root, Some(&buttons[1]), false) == Some(buttons[3].clone()));
+		assert!(next_focus(&root, Some(&buttons[2]), false) == Some(buttons[1].clone()));
+	}
+
+	#[test]
+	fn reverse_next_focus_wraps_around() {
+		let (root, buttons) = tree();
+
+		assert!(next_focus(&root, None, true) == Some(buttons[2].clone()));
+		assert!(next_focus(&root, Some(&buttons[3]), true) == Some(buttons[1].clone()));
+		assert!(next_focus(&root, Some(&buttons[1]), true) == Some(buttons[2].clone()));
+	}
+
+	#[test]
+	fn next_focus_starts_over_outside_of_scope() {
+		let (root, buttons) = tree();
+		let outside = button(0);
+
+		assert!(next_focus(&root, Some(&outside), false) == Some(buttons[1].clone()));
+		assert!(next_focus(&outside, Some(&buttons[0]), true) == Some(outside.clone()));
+		let empty: WidgetRef<dyn Widget> = TextBlockWidget::new().build();
+		assert!(next_focus(&empty, None, false).is_none());
+	}
+
+	#[test]
+	fn tab_and_shift_tab_move_keyboard_focus() {
+		let (root, buttons) = tree();
+		let mut event_context = EventContext::new();
+		let keyboard = event_context.get_keyboard_context(0);
+
+		keyboard.move_focus(&root, false);
+		keyboard.move_focus(&root, false);
+		assert!(keyboard.focused_widget() == Some(&buttons[3]));
+
+		keyboard.move_focus(&root, true);
+		keyboard.move_focus(&root, true);
+		assert!(keyboard.focused_widget() == Some(&buttons[2]));
+	}
+}
@@ -0,0 +1,526 @@
+use std::collections::HashSet;
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+use crate::{util::WidgetRef, widgets::Widget};
+use cgmath::{InnerSpace, Vector2};
+use skia_safe::scalar;
+
+/// Distance in pixels a touch may move and still count as tap or long press.
+pub const TAP_SLOP: scalar = 10.0;
+/// Maximum time a touch may rest for a tap.
+pub const TAP_TIMEOUT: Duration = Duration::from_millis(300);
+/// Time a touch has to rest for a long press.
+pub const LONG_PRESS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The kinds of gestures a widget can subscribe to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GestureKind {
+	Tap,
+	LongPress,
+	Pan,
+	Pinch,
+	Rotate,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GesturePhase {
+	Started,
+	Changed,
+	Ended,
+	Cancelled,
+}
+
+/// A recognized gesture, all positions are in window coordinates.
+#[derive(Debug, Copy, Clone)]
+pub enum Gesture {
+	Tap {
+		pos: Vector2<scalar>,
+	},
+	LongPress {
+		pos: Vector2<scalar>,
+	},
+	/// A single finger moving.
+	Pan {
+		phase: GesturePhase,
+		pos: Vector2<scalar>,
+		/// Movement since the last pan event.
+		delta: Vector2<scalar>,
+		/// Movement since the pan started.
+		translation: Vector2<scalar>,
+	},
+	/// Two fingers moving towards or away from each other.
+	Pinch {
+		phase: GesturePhase,
+		center: Vector2<scalar>,
+		/// Distance of the fingers relative to their distance when the pinch started.
+		scale: scalar,
+	},
+	/// Two fingers rotating around each other.
+	Rotate {
+		phase: GesturePhase,
+		center: Vector2<scalar>,
+		/// Rotation in radians since the rotation started, clockwise is positive.
+		angle: scalar,
+	},
+}
+
+impl Gesture {
+	pub fn kind(&self) -> GestureKind {
+		match self {
+			Gesture::Tap { .. } => GestureKind::Tap,
+			Gesture::LongPress { .. } => GestureKind::LongPress,
+			Gesture::Pan { .. } => GestureKind::Pan,
+			Gesture::Pinch { .. } => GestureKind::Pinch,
+			Gesture::Rotate { .. } => GestureKind::Rotate,
+		}
+	}
+}
+
+struct TouchPoint {
+	finger: u64,
+	start: Vector2<scalar>,
+	pos: Vector2<scalar>,
+}
+
+enum RecognizerState {
+	/// A single touch that could still become any gesture.
+	Possible,
+	LongPressed,
+	Panning,
+	/// Two fingers pinching and rotating.
+	Transforming {
+		start_distance: scalar,
+		start_angle: scalar,
+	},
+	/// The gesture ended, the remaining touches get ignored until all got lifted.
+	Done,
+}
+
+/// Turns the touches on a widget into gestures.
+///
+/// A recognizer gets created when the first finger touches a widget subscribed to gestures
+/// and lives until all fingers got lifted.
+///
+/// A single finger taps, long presses or pans, a second finger turns the gesture into a pinch and rotation.
+/// If a finger of a pinch gets lifted, the remaining finger continues as a new pan,
+/// or the remaining fingers start a new pinch. Lifting the finger of a tap, long press or pan ends
+/// the gesture and any other fingers get ignored until they got lifted.
+pub(crate) struct GestureRecognizer {
+	pub target: WidgetRef<dyn Widget>,
+	kinds: HashSet<GestureKind>,
+	touches: Vec<TouchPoint>,
+	down_time: Instant,
+	state: RecognizerState,
+}
+
+impl GestureRecognizer {
+	pub fn new(target: WidgetRef<dyn Widget>, kinds: HashSet<GestureKind>, now: Instant) -> Self {
+		Self {
+			target,
+			kinds,
+			touches: Vec::new(),
+			down_time: now,
+			state: RecognizerState::Possible,
+		}
+	}
+
+	pub fn has_finger(&self, finger: u64) -> bool {
+		self.touches.iter().any(|touch| touch.finger == finger)
+	}
+
+	/// All fingers got lifted, so the recognizer can be dropped.
+	pub fn is_finished(&self) -> bool {
+		self.touches.is_empty()
+	}
+
+	/// Returns when a long press would be recognized, if one is possible.
+	pub fn deadline(&self) -> Option<Instant> {
+		match self.state {
+			RecognizerState::Possible if self.kinds.contains(&GestureKind::LongPress) => {
+				Some(self.down_time + LONG_PRESS_TIMEOUT)
+			}
+			_ => None,
+		}
+	}
+
+	pub fn touch_down(&mut self, finger: u64, pos: Vector2<scalar>, now: Instant) -> Vec<Gesture> {
+		self.touches.push(TouchPoint {
+			finger,
+			start: pos,
+			pos,
+		});
+		let mut gestures = Vec::new();
+		match self.state {
+			RecognizerState::Possible | RecognizerState::Panning if self.touches.len() == 2 => {
+				if let RecognizerState::Panning = self.state {
+					gestures.push(self.pan(GesturePhase::Ended, Vector2::new(0.0, 0.0)));
+				}
+				gestures.extend(self.start_transform());
+			}
+			RecognizerState::Possible if self.touches.len() == 1 => self.down_time = now,
+			_ => (),
+		}
+		self.filter(gestures)
+	}
+
+	pub fn touch_move(&mut self, finger: u64, pos: Vector2<scalar>) -> Vec<Gesture> {
+		let touch = match self.touches.iter_mut().find(|touch| touch.finger == finger) {
+			Some(touch) => touch,
+			None => return Vec::new(),
+		};
+		let delta = pos - touch.pos;
+		// e.g. a finger getting lifted where it was last moved to
+		if delta == Vector2::new(0.0, 0.0) {
+			return Vec::new();
+		}
+		touch.pos = pos;
+		let moved = (pos - touch.start).magnitude();
+
+		let mut gestures = Vec::new();
+		match self.state {
+			RecognizerState::Possible if moved > TAP_SLOP => {
+				self.state = RecognizerState::Panning;
+				gestures.push(self.pan(GesturePhase::Started, delta));
+			}
+			RecognizerState::Panning => gestures.push(self.pan(GesturePhase::Changed, delta)),
+			RecognizerState::Transforming { .. } => gestures.extend(self.transform_gestures(GesturePhase::Changed)),
+			_ => (),
+		}
+		self.filter(gestures)
+	}
+
+	pub fn touch_up(&mut self, finger: u64, pos: Vector2<scalar>, now: Instant) -> Vec<Gesture> {
+		if !self.has_finger(finger) {
+			return Vec::new();
+		}
+		let mut gestures = self.touch_move(finger, pos);
+		match self.state {
+			RecognizerState::Possible if now.saturating_duration_since(self.down_time) <= TAP_TIMEOUT => {
+				gestures.push(Gesture::Tap { pos });
+			}
+			RecognizerState::Panning => gestures.push(self.pan(GesturePhase::Ended, Vector2::new(0.0, 0.0))),
+			RecognizerState::Transforming { .. } => gestures.extend(self.transform_gestures(GesturePhase::Ended)),
+			_ => (),
+		}
+		let transforming = matches!(self.state, RecognizerState::Transforming { .. });
+		self.state = RecognizerState::Done;
+		self.touches.retain(|touch| touch.finger != finger);
+		// the remaining fingers of a pinch go on, so lifting a finger does not stop a zoom or a scroll
+		if transforming && self.touches.len() >= 2 {
+			gestures.extend(self.start_transform());
+		} else if transforming && self.touches.len() == 1 {
+			let touch = &mut self.touches[0];
+			touch.start = touch.pos;
+			self.state = RecognizerState::Panning;
+			gestures.push(self.pan(GesturePhase::Started, Vector2::new(0.0, 0.0)));
+		}
+		self.filter(gestures)
+	}
+
+	pub fn touch_cancel(&mut self, finger: u64) -> Vec<Gesture> {
+		if !self.has_finger(finger) {
+			return Vec::new();
+		}
+		let mut gestures = Vec::new();
+		match self.state {
+			RecognizerState::Panning => gestures.push(self.pan(GesturePhase::Cancelled, Vector2::new(0.0, 0.0))),
+			RecognizerState::Transforming { .. } => gestures.extend(self.transform_gestures(GesturePhase::Cancelled)),
+			_ => (),
+		}
+		self.state = RecognizerState::Done;
+		self.touches.retain(|touch| touch.finger != finger);
+		self.filter(gestures)
+	}
+
+	pub fn tick(&mut self, now: Instant) -> Vec<Gesture> {
+		match self.deadline() {
+			Some(deadline) if deadline <= now => {
+				self.state = RecognizerState::LongPressed;
+				let pos = self.touches[0].pos;
+				self.filter(vec![Gesture::LongPress { pos }])
+			}
+			_ => Vec::new(),
+		}
+	}
+
+	/// Starts pinching and rotating with the first two touches.
+	fn start_transform(&mut self) -> Vec<Gesture> {
+		let (center, distance, angle) = self.transform();
+		self.state = RecognizerState::Transforming {
+			start_distance: distance,
+			start_angle: angle,
+		};
+		vec![
+			Gesture::Pinch {
+				phase: GesturePhase::Started,
+				center,
+				scale: 1.0,
+			},
+			Gesture::Rotate {
+				phase: GesturePhase::Started,
+				center,
+				angle: 0.0,
+			},
+		]
+	}
+
+	fn pan(&self, phase: GesturePhase, delta: Vector2<scalar>) -> Gesture {
+		let touch = &self.touches[0];
+		Gesture::Pan {
+			phase,
+			pos: touch.pos,
+			delta,
+			translation: touch.pos - touch.start,
+		}
+	}
+
+	/// Returns center, distance and angle of the first two touches.
+	fn transform(&self) -> (Vector2<scalar>, scalar, scalar) {
+		let a = self.touches[0].pos;
+		let b = self.touches[1].pos;
+		let diff = b - a;
+		((a + b) / 2.0, diff.magnitude(), diff.y.atan2(diff.x))
+	}
+
+	fn transform_gestures(&self, phase: GesturePhase) -> Vec<Gesture> {
+		let (start_distance, start_angle) = match self.state {
+			RecognizerState::Transforming {
+				start_distance,
+				start_angle,
+			} => (start_distance, start_angle),
+			_ => return Vec::new(),
+		};
+		let (center, distance, angle) = self.transform();
+		let scale = if start_distance > 0.0 { distance / start_distance } else { 1.0 };
+		// keep the angle within -PI..PI
+		let mut angle = angle - start_angle;
+		if angle > PI {
+			angle -= 2.0 * PI;
+		} else if angle < -PI {
+			angle += 2.0 * PI;
+		}
+		vec![
+			Gesture::Pinch { phase, center, scale },
+			Gesture::Rotate { phase, center, angle },
+		]
+	}
+
+	/// Only keeps the gestures the target subscribed to.
+	fn filter(&self, gestures: Vec<Gesture>) -> Vec<Gesture> {
+		gestures
+			.into_iter()
+			.filter(|gesture| self.kinds.contains(&gesture.kind()))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+	use std::f32::consts::PI;
+	use std::time::{Duration, Instant};
+	use crate::{util::WidgetRef, widgets::{ButtonWidget, Widget}};
+	use super::{Gesture, GestureKind, GesturePhase, GestureRecognizer, LONG_PRESS_TIMEOUT, TAP_TIMEOUT};
+	use cgmath::Vector2;
+
+	fn recognizer(kinds: &[GestureKind], now: Instant) -> GestureRecognizer {
+		let target: WidgetRef<dyn Widget> = ButtonWidget::new("").build();
+		GestureRecognizer::new(target, kinds.iter().cloned().collect::<HashSet<_>>(), now)
+	}
+
+	fn all_kinds(now: Instant) -> GestureRecognizer {
+		recognizer(
+			&[GestureKind::Tap, GestureKind::LongPress, GestureKind::Pan, GestureKind::Pinch, GestureKind::Rotate],
+			now,
+		)
+	}
+
+	/// Kind and phase of each gesture, to compare them without their positions.
+	fn summary(gestures: &[Gesture]) -> Vec<(GestureKind, Option<GesturePhase>)> {
+		gestures
+			.iter()
+			.map(|gesture| {
+				let phase = match gesture {
+					Gesture::Tap { .. } | Gesture::LongPress { .. } => None,
+					Gesture::Pan { phase, .. } | Gesture::Pinch { phase, .. } | Gesture::Rotate { phase, .. } => Some(*phase),
+				};
+				(gesture.kind(), phase)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn quick_release_taps() {
+		let now = Instant::now();
+		let mut recognizer = all_kinds(now);
+
+		assert!(recognizer.touch_down(0, Vector2::new(10.0, 10.0), now).is_empty());
+		assert!(recognizer.touch_move(0, Vector2::new(15.0, 12.0)).is_empty());
+		let gestures = recognizer.touch_up(0, Vector2::new(15.0, 12.0), now + TAP_TIMEOUT);
+
+		assert_eq!(summary(&gestures), vec![(GestureKind::Tap, None)]);
+		assert!(recognizer.is_finished());
+	}
+
+	#[test]
+	fn slow_release_does_not_tap() {
+		let now = Instant::now();
+		let mut recognizer = recognizer(&[GestureKind::Tap], now);
+
+		recognizer.touch_down(0, Vector2::new(10.0, 10.0), now);
+
+		assert!(recognizer.touch_up(0, Vector2::new(10.0, 10.0), now + TAP_TIMEOUT * 2).is_empty());
+	}
+
+	#[test]
+	fn resting_touch_long_presses() {
+		let now = Instant::now();
+		let mut recognizer = all_kinds(now);
+		recognizer.touch_down(0, Vector2::new(10.0, 10.0), now);
+
+		assert_eq!(recognizer.deadline(), Some(now + LONG_PRESS_TIMEOUT));
+		assert!(recognizer.tick(now + LONG_PRESS_TIMEOUT - Duration::from_millis(1)).is_empty());
+		let gestures = recognizer.tick(now + LONG_PRESS_TIMEOUT);
+
+		assert_eq!(summary(&gestures), vec![(GestureKind::LongPress, None)]);
+		assert_eq!(recognizer.deadline(), None);
+		// neither a tap nor a pan afterwards
+		assert!(recognizer.touch_move(0, Vector2::new(50.0, 10.0)).is_empty());
+		assert!(recognizer.touch_up(0, Vector2::new(50.0, 10.0), now + LONG_PRESS_TIMEOUT).is_empty());
+	}
+
+	#[test]
+	fn moving_beyond_slop_pans() {
+		let now = Instant::now();
+		let mut recognizer = all_kinds(now);
+		recognizer.touch_down(0, Vector2::new(0.0, 0.0), now);
+
+		let started = recognizer.touch_move(0, Vector2::new(20.0, 0.0));
+		let changed = recognizer.touch_move(0, Vector2::new(30.0, 5.0));
+		let ended = recognizer.touch_up(0, Vector2::new(30.0, 5.0), now);
+
+		assert_eq!(summary(&started), vec![(GestureKind::Pan, Some(GesturePhase::Started))]);
+		match changed[..] {
+			[Gesture::Pan {
+				phase: GesturePhase::Changed,
+				delta,
+				translation,
+				..
+			}] => {
+				assert_eq!(delta, Vector2::new(10.0, 5.0));
+				assert_eq!(translation, Vector2::new(30.0, 5.0));
+			}
+			_ => panic!("expected a pan, got {:?}", changed),
+		}
+		assert_eq!(summary(&ended), vec![(GestureKind::Pan, Some(GesturePhase::Ended))]);
+		assert_eq!(recognizer.deadline(), None);
+	}
+
+	#[test]
+	fn two_fingers_pinch_and_rotate() {
+		let now = Instant::now();
+		let mut recognizer = all_kinds(now);
+		recognizer.touch_down(0, Vector2::new(0.0, 0.0), now);
+
+		let started = recognizer.touch_down(1, Vector2::new(10.0, 0.0), now);
+		let changed = recognizer.touch_move(1, Vector2::new(0.0, 20.0));
+
+		assert_eq!(
+			summary(&started),
+			vec![
+				(GestureKind::Pinch, Some(GesturePhase::Started)),
+				(GestureKind::Rotate, Some(GesturePhase::Started)),
+			]
+		);
+		match changed[..] {
+			[Gesture::Pinch { center, scale, .. }, Gesture::Rotate { angle, .. }] => {
+				assert_eq!(center, Vector2::new(0.0, 10.0));
+				assert!((scale - 2.0).abs() < 1e-5);
+				assert!((angle - PI / 2.0).abs() < 1e-5);
+			}
+			_ => panic!("expected a pinch and a rotation, got {:?}", changed),
+		}
+	}
+
+	#[test]
+	fn second_finger_ends_pan() {
+		let now = Instant::now();
+		let mut recognizer = recognizer(&[GestureKind::Pan, GestureKind::Pinch], now);
+		recognizer.touch_down(0, Vector2::new(0.0, 0.0), now);
+		recognizer.touch_move(0, Vector2::new(20.0, 0.0));
+
+		let gestures = recognizer.touch_down(1, Vector2::new(40.0, 0.0), now);
+
+		assert_eq!(
+			summary(&gestures),
+			vec![
+				(GestureKind::Pan, Some(GesturePhase::Ended)),
+				(GestureKind::Pinch, Some(GesturePhase::Started)),
+			]
+		);
+	}
+
+	#[test]
+	fn remaining_finger_of_pinch_continues_as_pan() {
+		let now = Instant::now();
+		let mut recognizer = all_kinds(now);
+		recognizer.touch_down(0, Vector2::new(0.0, 0.0), now);
+		recognizer.touch_down(1, Vector2::new(10.0, 0.0), now);
+
+		let lifted = recognizer.touch_up(1, Vector2::new(10.0, 0.0), now);
+		let moved = recognizer.touch_move(0, Vector2::new(0.0, 5.0));
+		let ended = recognizer.touch_up(0, Vector2::new(0.0, 5.0), now);
+
+		assert_eq!(
+			summary(&lifted),
+			vec![
+				(GestureKind::Pinch, Some(GesturePhase::Ended)),
+				(GestureKind::Rotate, Some(GesturePhase::Ended)),
+				(GestureKind::Pan, Some(GesturePhase::Started)),
+			]
+		);
+		// the pan starts where the finger was when the pinch ended, so it does not jump
+		match moved[..] {
+			[Gesture::Pan {
+				phase: GesturePhase::Changed,
+				translation,
+				..
+			}] => assert_eq!(translation, Vector2::new(0.0, 5.0)),
+			_ => panic!("expected a pan, got {:?}", moved),
+		}
+		assert_eq!(summary(&ended), vec![(GestureKind::Pan, Some(GesturePhase::Ended))]);
+		assert!(recognizer.is_finished());
+	}
+
+	#[test]
+	fn remaining_fingers_of_pinch_start_new_pinch() {
+		let now = Instant::now();
+		let mut recognizer = recognizer(&[GestureKind::Pinch], now);
+		recognizer.touch_down(0, Vector2::new(0.0, 0.0), now);
+		recognizer.touch_down(1, Vector2::new(10.0, 0.0), now);
+		recognizer.touch_down(2, Vector2::new(0.0, 10.0), now);
+
+		let lifted = recognizer.touch_up(0, Vector2::new(0.0, 0.0), now);
+
+		assert_eq!(
+			summary(&lifted),
+			vec![
+				(GestureKind::Pinch, Some(GesturePhase::Ended)),
+				(GestureKind::Pinch, Some(GesturePhase::Started)),
+			]
+		);
+	}
+
+	#[test]
+	fn cancel_ends_gesture() {
+		let now = Instant::now();
+		let mut recognizer = all_kinds(now);
+		recognizer.touch_down(0, Vector2::new(0.0, 0.0), now);
+		recognizer.touch_move(0, Vector2::new(20.0, 0.0));
+
+		let cancelled = recognizer.touch_cancel(0);
+
+		assert_eq!(summary(&cancelled), vec![(GestureKind::Pan, Some(GesturePhase::Cancelled))]);
+		assert!(recognizer.is_finished());
+	}
+}
@@ -1,7 +1,134 @@
-#[derive(Debug, Copy, Clone)]
+use cgmath::Vector2;
+use skia_safe::scalar;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum MouseButton {
 	Left,
 	Middle,
 	Right,
 	Other(u16),
 }
+
+/// Event of an input method editor (IME) used to compose text, e.g. for Chinese, Japanese or Korean.
+#[derive(Debug, Clone)]
+pub enum ImeEvent {
+	/// The IME got enabled, preedit and commit events may follow.
+	Enabled,
+	/// The text currently being composed changed.
+	/// The cursor is the byte range within the text that should be highlighted,
+	/// or None if the cursor should be hidden.
+	/// An empty text means the composition got cleared.
+	Preedit(String, Option<(usize, usize)>),
+	/// The composed text got committed and should be inserted.
+	Commit(String),
+	/// The IME got disabled, any composition is cleared.
+	Disabled,
+}
+
+/// Amount to scroll, positive values scroll towards the start (up and left).
+#[derive(Debug, Copy, Clone)]
+pub enum ScrollDelta {
+	/// Scrolled by lines, e.g. by the notches of a mouse wheel.
+	Lines(Vector2<scalar>),
+	/// Scrolled by exact pixels, e.g. by a touchpad.
+	Pixels(Vector2<scalar>),
+}
+
+/// Phase of a continuous scroll gesture, like scrolling on a touchpad.
+/// Mouse wheels only send [ScrollPhase::Moved].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScrollPhase {
+	Started,
+	Moved,
+	/// The fingers got lifted, a fling may continue kinetically.
+	Ended,
+	Cancelled,
+}
+
+/// Pressure of a touch or pen, if the device reports it.
+#[derive(Debug, Copy, Clone)]
+pub struct TouchForce {
+	/// Pressure normalized so that 1.0 is the force of an average touch.
+	pub pressure: scalar,
+	/// Tilt of a pen in radians, 0 means parallel to the surface and PI/2 perpendicular to it.
+	pub altitude_angle: Option<scalar>,
+}
+
+bitflags::bitflags! {
+	/// Modifier keys held down on a keyboard.
+	#[derive(Default)]
+	pub struct Modifiers: u8 {
+		const SHIFT = 0b0001;
+		const CTRL = 0b0010;
+		const ALT = 0b0100;
+		/// The Windows key on PC keyboards and the Command key on Apple keyboards.
+		const SUPER = 0b1000;
+	}
+}
+
+impl Modifiers {
+	pub fn shift(&self) -> bool {
+		self.contains(Modifiers::SHIFT)
+	}
+
+	pub fn ctrl(&self) -> bool {
+		self.contains(Modifiers::CTRL)
+	}
+
+	pub fn alt(&self) -> bool {
+		self.contains(Modifiers::ALT)
+	}
+
+	pub fn logo(&self) -> bool {
+		self.contains(Modifiers::SUPER)
+	}
+}
+
+/// Shape of the mouse cursor, see [crate::widgets::Widget::cursor_icon()].
+#[derive(Debug, Clone)]
+pub enum CursorIcon {
+	Default,
+	/// I-beam for selecting text.
+	Text,
+	/// Pointing hand, e.g. for links.
+	Hand,
+	Grab,
+	Grabbing,
+	Move,
+	Crosshair,
+	Wait,
+	NotAllowed,
+	ResizeHorizontal,
+	ResizeVertical,
+	/// Resizing towards the top left and bottom right.
+	ResizeDiagonal,
+	/// Resizing towards the top right and bottom left.
+	ResizeAntiDiagonal,
+	/// An image with its hot spot, the point within the image that is at the cursor position.
+	/// The system cursor gets hidden and the window paints the image instead.
+	Custom {
+		image: skia_safe::Image,
+		hotspot: Vector2<scalar>,
+	},
+}
+
+impl Default for CursorIcon {
+	fn default() -> Self {
+		CursorIcon::Default
+	}
+}
+
+impl PartialEq for CursorIcon {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(
+				CursorIcon::Custom { image, hotspot },
+				CursorIcon::Custom {
+					image: other_image,
+					hotspot: other_hotspot,
+				},
+			) => image.unique_id() == other_image.unique_id() && hotspot == other_hotspot,
+			_ => std::mem::discriminant(self) == std::mem::discriminant(other),
+		}
+	}
+}
@@ -1,16 +1,27 @@
+mod commands;
+mod drag;
 mod events;
+mod focus;
+mod gesture;
+mod tooltip;
 pub mod input;
 
 use crate::{
-	events::input::MouseButton,
+	events::input::{CursorIcon, ImeEvent, Modifiers, MouseButton, ScrollDelta, ScrollPhase, TouchForce},
 	platform::common::PlatformContext,
 	util::{Geometry, WidgetRef},
-	widgets::Widget,
+	widgets::{ImageWidget, Widget},
 };
-use cgmath::Vector2;
+use cgmath::{InnerSpace, Vector2};
+pub use commands::*;
+pub use drag::*;
 pub use events::*;
-use skia_safe::scalar;
+pub use focus::*;
+pub use gesture::*;
+pub use tooltip::*;
+use skia_safe::{scalar, Rect};
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 use winit::event::VirtualKeyCode;
 use crate::application::{GUIApplication};
 
@@ -25,6 +36,10 @@ pub struct Reply {
 	free_focus: Option<WidgetFocusChange>,
 	capture_cursor: Option<usize>,
 	release_cursor: Option<usize>,
+	ime_cursor_area: Option<Rect>,
+	request_tick: Option<Duration>,
+	start_drag: Option<(usize, DragPayload, Option<WidgetRef<dyn Widget>>)>,
+	reject_drop: bool,
 }
 
 impl Reply {
@@ -35,6 +50,10 @@ impl Reply {
 			free_focus: None,
 			capture_cursor: None,
 			release_cursor: None,
+			ime_cursor_area: None,
+			request_tick: None,
+			start_drag: None,
+			reject_drop: false,
 		}
 	}
 
@@ -45,6 +64,10 @@ impl Reply {
 			free_focus: None,
 			capture_cursor: None,
 			release_cursor: None,
+			ime_cursor_area: None,
+			request_tick: None,
+			start_drag: None,
+			reject_drop: false,
 		}
 	}
 
@@ -67,8 +90,45 @@ impl Reply {
 		self.release_cursor = Some(cursor);
 		self
 	}
+
+	/// Tells the platform where the text cursor is (in window coordinates),
+	/// so the input method editor can place its candidate window next to it.
+	pub fn ime_cursor_area(mut self, area: Rect) -> Self {
+		self.ime_cursor_area = Some(area);
+		self
+	}
+
+	/// Requests a [WidgetEvent::OnTick] after the given delay, e.g. to drive animations.
+	/// The request gets dropped after the tick, so animations have to request the next tick
+	/// when handling the current one.
+	pub fn request_tick(mut self, delay: Duration) -> Self {
+		self.request_tick = Some(delay);
+		self
+	}
+
+	/// Starts dragging the payload with the given cursor, the replying widget becomes the source of the drag.
+	/// The visual gets painted at the cursor until the drag ends.
+	/// A capture of the cursor gets released, as the drag has to move over other widgets.
+	pub fn start_drag(mut self, cursor: usize, payload: DragPayload, visual: Option<WidgetRef<dyn Widget>>) -> Self {
+		self.start_drag = Some((cursor, payload, visual));
+		self
+	}
+
+	/// Replying handled to [WidgetEvent::OnDragOver] accepts the drop,
+	/// unless the drop gets rejected with this, which still stops the bubbling.
+	pub fn reject_drop(mut self) -> Self {
+		self.reject_drop = true;
+		self
+	}
+
+	pub fn is_handled(&self) -> bool {
+		self.handled
+	}
 }
 
+/// Events sent to widgets.
+///
+/// Key and pointer events carry the [Modifiers] held on the keyboard with the same index as the device.
 pub enum WidgetEvent {
 	OnCursorEnter {
 		cursor: usize,
@@ -76,6 +136,7 @@ pub enum WidgetEvent {
 	OnCursorMove {
 		cursor: usize,
 		pos: Vector2<scalar>,
+		modifiers: Modifiers,
 	},
 	OnCursorLeave {
 		cursor: usize,
@@ -84,31 +145,144 @@ pub enum WidgetEvent {
 		mouse: usize,
 		button: MouseButton,
 		pos: Vector2<scalar>,
+		modifiers: Modifiers,
 	},
 	OnMouseButtonDown {
 		mouse: usize,
 		button: MouseButton,
 		pos: Vector2<scalar>,
+		modifiers: Modifiers,
 	},
 	OnMouseButtonUp {
 		mouse: usize,
 		button: MouseButton,
 		pos: Vector2<scalar>,
+		modifiers: Modifiers,
+	},
+	/// The button got pressed a second time within the [ClickSettings] thresholds.
+	/// Sent right after the [WidgetEvent::OnMouseButtonDown] of the second press,
+	/// so the widget can e.g. start selecting words while dragging.
+	OnDoubleClick {
+		mouse: usize,
+		button: MouseButton,
+		pos: Vector2<scalar>,
+		modifiers: Modifiers,
+	},
+	/// Like [WidgetEvent::OnDoubleClick], but for the third press.
+	/// A fourth press counts as first press again.
+	OnTripleClick {
+		mouse: usize,
+		button: MouseButton,
+		pos: Vector2<scalar>,
+		modifiers: Modifiers,
+	},
+	OnScroll {
+		cursor: usize,
+		delta: ScrollDelta,
+		phase: ScrollPhase,
+		pos: Vector2<scalar>,
+		modifiers: Modifiers,
+	},
+	/// A finger or pen touched the widget.
+	/// Touches get routed like cursors, but every finger is tracked by its own id.
+	OnTouchDown {
+		finger: u64,
+		pos: Vector2<scalar>,
+		force: Option<TouchForce>,
+	},
+	/// A touch moved, only sent to the widget that handled the touch down.
+	OnTouchMove {
+		finger: u64,
+		pos: Vector2<scalar>,
+		force: Option<TouchForce>,
+	},
+	OnTouchUp {
+		finger: u64,
+		pos: Vector2<scalar>,
+	},
+	/// The touch got cancelled by the system, e.g. because the window lost focus.
+	OnTouchCancel {
+		finger: u64,
+	},
+	/// A gesture the widget subscribed to got recognized, see [Widget::gestures()].
+	OnGesture {
+		gesture: Gesture,
+	},
+	/// A drag entered the widget, sent to every widget under the dragging cursor.
+	OnDragEnter {
+		cursor: usize,
+		payload: DragPayload,
+	},
+	/// A drag moved over the widget. Routed along the widget path under the cursor,
+	/// the widget replying handled accepts the drop, see [Reply::reject_drop()].
+	OnDragOver {
+		cursor: usize,
+		payload: DragPayload,
+		pos: Vector2<scalar>,
+	},
+	/// A drag left the widget, also sent when the drag ends while over the widget.
+	OnDragLeave {
+		cursor: usize,
+	},
+	/// The payload got dropped on the widget that accepted the last [WidgetEvent::OnDragOver].
+	/// Replying handled completes the drop.
+	OnDrop {
+		cursor: usize,
+		payload: DragPayload,
+		pos: Vector2<scalar>,
+	},
+	/// The drag this widget started ended, dropped tells if a target handled the drop.
+	OnDragEnd {
+		cursor: usize,
+		dropped: bool,
+	},
+	/// A tick requested with [Reply::request_tick()] is due.
+	OnTick {
+		time: Instant,
 	},
 
 	OnKeyDown {
 		keyboard: usize,
 		key_physical: usize,
 		key: Option<VirtualKeyCode>,
+		modifiers: Modifiers,
 	},
 	OnKeyUp {
 		keyboard: usize,
 		key_physical: usize,
 		key: Option<VirtualKeyCode>,
+		modifiers: Modifiers,
 	},
 	OnText {
 		keyboard: usize,
 		character: char,
+		modifiers: Modifiers,
+	},
+	/// A key binding of the command got pressed, see [CommandRegistry].
+	/// Bubbles from the focused widget up to the scope of the binding.
+	OnCommand {
+		keyboard: usize,
+		command: String,
+	},
+	/// The input method editor of the focused keyboard got enabled.
+	OnImeEnabled {
+		keyboard: usize,
+	},
+	/// The text currently composed with the input method editor changed.
+	/// The cursor is a byte range within the preedit text, if it should be shown.
+	/// An empty text means the composition got cleared.
+	OnImePreedit {
+		keyboard: usize,
+		text: String,
+		cursor: Option<(usize, usize)>,
+	},
+	/// The text composed with the input method editor should be inserted.
+	OnImeCommit {
+		keyboard: usize,
+		text: String,
+	},
+	OnImeDisabled {
+		keyboard: usize,
 	},
 	OnFocus {
 		keyboard: usize,
@@ -124,6 +298,12 @@ pub struct WidgetPath {
 }
 
 impl WidgetPath {
+	/// Iterates the widgets of the path from the root down to the deepest widget,
+	/// the reverse order of [WidgetPath::bubble()].
+	pub fn tunnel(&self) -> WidgetPathIteratorTunnel {
+		WidgetPathIteratorTunnel { stack: vec![self] }
+	}
+
 	pub fn bubble(&self) -> WidgetPathIteratorBubble {
 		WidgetPathIteratorBubble {
 			iter: Box::new(WidgetPathIteratorBubbleInternal {
@@ -135,6 +315,20 @@ impl WidgetPath {
 	}
 }
 
+pub struct WidgetPathIteratorTunnel<'a> {
+	stack: Vec<&'a WidgetPath>,
+}
+
+impl<'a> Iterator for WidgetPathIteratorTunnel<'a> {
+	type Item = &'a WidgetRef<dyn Widget>;
+	fn next(&mut self) -> Option<&'a WidgetRef<dyn Widget>> {
+		let path = self.stack.pop()?;
+		// bubbling passes the children in order, so tunneling passes them reversed
+		self.stack.extend(path.children.iter());
+		Some(&path.widget)
+	}
+}
+
 pub struct WidgetPathIteratorBubble<'a> {
 	iter: Box<WidgetPathIteratorBubbleInternal<'a>>,
 }
@@ -200,7 +394,7 @@ pub fn get_widget_path_under_position(
 		children: Vec::new(),
 	};
 	let widget = widget.get();
-	let arranged_children = widget.get_arranged_children();
+	let arranged_children = widget.get_hit_test_children(pos);
 	for child_arrangement in arranged_children.iter().rev() {
 		if !child_arrangement.geometry.contains_absolute_pos(pos) {
 			continue;
@@ -214,7 +408,14 @@ pub fn get_widget_path_under_position(
 	path
 }
 
+/// Sends the event as preview down the path and then bubbles it up until a widget handles it.
 pub fn bubble_event(path: &WidgetPath, event: &WidgetEvent) -> Reply {
+	for widget in path.tunnel() {
+		let reply = widget.get().on_preview_event(event);
+		if reply.handled {
+			return reply;
+		}
+	}
 	for widget in path.bubble() {
 		let reply = widget.get().on_event(event);
 		if reply.handled {
@@ -224,23 +425,139 @@ pub fn bubble_event(path: &WidgetPath, event: &WidgetEvent) -> Reply {
 	Reply::unhandled()
 }
 
+/// Thresholds deciding whether consecutive presses of a mouse button count as double or triple click.
+#[derive(Debug, Copy, Clone)]
+pub struct ClickSettings {
+	/// Maximum time between two presses.
+	pub max_interval: Duration,
+	/// Maximum distance in pixels between two presses.
+	pub max_distance: scalar,
+}
+
+impl Default for ClickSettings {
+	fn default() -> Self {
+		Self {
+			max_interval: Duration::from_millis(500),
+			max_distance: 4.0,
+		}
+	}
+}
+
+/// The last press of a cursor, used to count consecutive clicks.
+struct ButtonPress {
+	button: MouseButton,
+	pos: Vector2<scalar>,
+	time: Instant,
+	count: u32,
+}
+
 pub struct EventContext {
+	/// The state of each cursor by its index.
+	/// On Linux the winit backend reports every pointer as cursor `0`.
 	cursors: HashMap<usize, CursorEventContext>,
+	/// The state of each keyboard by its index.
+	/// On Linux the winit backend reports every keyboard as keyboard `0`.
 	keyboards: HashMap<usize, KeyboardEventContext>,
+	/// Widgets that requested a tick and when it is due.
+	tick_requests: Vec<(WidgetRef<dyn Widget>, Instant)>,
+	/// The widget that handled the touch down of each finger currently touching.
+	touches: HashMap<u64, Option<WidgetRef<dyn Widget>>>,
+	gesture_recognizers: Vec<GestureRecognizer>,
+	click_settings: ClickSettings,
+	commands: CommandRegistry,
+	tooltip: Option<TooltipState>,
+	tooltip_delay: Duration,
 }
 
 pub struct CursorEventContext {
 	last_over_widgets: HashSet<WidgetRef<dyn Widget>>,
 	captured_by_widget: Option<WidgetRef<dyn Widget>>,
-	about_to_be_clicked: HashMap<usize, HashSet<WidgetRef<dyn Widget>>>,
+	/// The root of the widget tree the capturing widget was part of when it captured the cursor.
+	/// If the root changes, the widget got removed from the tree and the capture ends.
+	capture_root: Option<WidgetRef<dyn Widget>>,
+	pressed_buttons: HashSet<MouseButton>,
+	/// The position of the last pointer event.
+	pos: Vector2<scalar>,
+	/// The root of the widget tree the cursor is over, normally the window.
+	root: Option<WidgetRef<dyn Widget>>,
+	icon: CursorIcon,
+	/// The widgets a button got pressed on, they receive the click if the button gets released on them.
+	about_to_be_clicked: HashMap<MouseButton, HashSet<WidgetRef<dyn Widget>>>,
+	last_press: Option<ButtonPress>,
+	drag: Option<DragState>,
+	/// The widget showing a custom cursor image and the window it is an overlay of.
+	icon_overlay: Option<(WidgetRef<dyn Widget>, WidgetRef<ImageWidget>)>,
+}
+
+impl CursorEventContext {
+	/// Shows a custom cursor image as overlay of the window the cursor is over at the cursor position,
+	/// or removes it if the cursor left the window or shows a system cursor.
+	fn update_custom_icon(&mut self) {
+		let (image, pos, root) = match (&self.icon, &self.root) {
+			(CursorIcon::Custom { image, hotspot }, Some(root)) => (image.clone(), self.pos - *hotspot, root.clone()),
+			_ => {
+				self.hide_custom_icon();
+				return;
+			}
+		};
+		if self.icon_overlay.as_ref().is_some_and(|(shown_root, _)| *shown_root != root) {
+			self.hide_custom_icon();
+		}
+		let widget = match self.icon_overlay.take() {
+			Some((_, widget)) => {
+				widget.get().set_image(image);
+				widget
+			}
+			None => ImageWidget::new(image).build(),
+		};
+		if let Some(window) = root.get().as_window() {
+			window.show_overlay(widget.clone(), pos);
+		}
+		self.icon_overlay = Some((root, widget));
+	}
+
+	fn hide_custom_icon(&mut self) {
+		if let Some((root, widget)) = self.icon_overlay.take() {
+			let widget: WidgetRef<dyn Widget> = widget;
+			if let Some(window) = root.get().as_window() {
+				window.hide_overlay(&widget);
+			}
+		}
+	}
 }
 
 pub struct KeyboardEventContext {
 	keyboard_index: usize,
 	focused_widget: Option<WidgetRef<dyn Widget>>,
+	/// Root of the focused widget when it got focused, the focus gets dropped if the widget leaves this tree.
+	focus_root: Option<WidgetRef<dyn Widget>>,
+	pressed_keys: HashSet<VirtualKeyCode>,
+	modifiers: Modifiers,
 }
 
 impl KeyboardEventContext {
+	pub fn focused_widget(&self) -> Option<&WidgetRef<dyn Widget>> {
+		self.focused_widget.as_ref()
+	}
+
+	/// Returns the modifier keys currently held down on this keyboard.
+	pub fn modifiers(&self) -> Modifiers {
+		self.modifiers
+	}
+
+	/// Returns true if the given key of this keyboard is currently held down.
+	pub fn is_key_pressed(&self, key: VirtualKeyCode) -> bool {
+		self.pressed_keys.contains(&key)
+	}
+
+	/// Moves the focus to the next (or previous if reversed) focusable widget of the given scope.
+	pub fn move_focus(&mut self, scope: &WidgetRef<dyn Widget>, reverse: bool) {
+		let next = next_focus(scope, self.focused_widget.as_ref(), reverse);
+		if next.is_some() {
+			self.change_focus(next);
+		}
+	}
+
 	pub fn change_focus(&mut self, widget: Option<WidgetRef<dyn Widget>>) {
 		if self.focused_widget == widget {
 			return;
@@ -256,8 +573,15 @@ impl KeyboardEventContext {
 			let focus_event = WidgetEvent::OnFocus {
 				keyboard: self.keyboard_index,
 			};
-			widget.get().on_event(&focus_event);
+			let reply = widget.get().on_event(&focus_event);
+			// focus changes (e.g. by Tab) don't go through process_reply(),
+			// but the input method editor has to know where the focused widget edits text
+			if let Some(area) = reply.ime_cursor_area.filter(|_| reply.handled) {
+				let root = widget.get().get_root();
+				GUIApplication::get().platform_context_mut().set_ime_cursor_area(&root, area);
+			}
 		}
+		self.focus_root = widget.as_ref().map(|widget| widget.get().get_root());
 		self.focused_widget = widget;
 	}
 }
@@ -267,16 +591,55 @@ impl EventContext {
 		EventContext {
 			cursors: HashMap::new(),
 			keyboards: HashMap::new(),
+			tick_requests: Vec::new(),
+			touches: HashMap::new(),
+			gesture_recognizers: Vec::new(),
+			click_settings: ClickSettings::default(),
+			commands: CommandRegistry::default(),
+			tooltip: None,
+			tooltip_delay: TOOLTIP_DELAY,
 		}
 	}
 
+	pub fn commands(&self) -> &CommandRegistry {
+		&self.commands
+	}
+
+	pub fn commands_mut(&mut self) -> &mut CommandRegistry {
+		&mut self.commands
+	}
+
+	pub fn click_settings(&self) -> ClickSettings {
+		self.click_settings
+	}
+
+	pub fn set_click_settings(&mut self, settings: ClickSettings) {
+		self.click_settings = settings;
+	}
+
+	pub fn tooltip_delay(&self) -> Duration {
+		self.tooltip_delay
+	}
+
+	pub fn set_tooltip_delay(&mut self, delay: Duration) {
+		self.tooltip_delay = delay;
+	}
+
 	pub fn get_cursor_context(&mut self, cursor_index: usize) -> &mut CursorEventContext {
 		self.cursors
 			.entry(cursor_index)
 			.or_insert(CursorEventContext {
 				last_over_widgets: HashSet::default(),
 				captured_by_widget: None,
+				capture_root: None,
+				pressed_buttons: HashSet::default(),
+				pos: Vector2::new(0.0, 0.0),
+				root: None,
+				icon: CursorIcon::Default,
 				about_to_be_clicked: HashMap::default(),
+				last_press: None,
+				drag: None,
+				icon_overlay: None,
 			})
 	}
 
@@ -293,6 +656,9 @@ impl EventContext {
 			.or_insert(KeyboardEventContext {
 				keyboard_index,
 				focused_widget: None,
+				focus_root: None,
+				pressed_keys: HashSet::default(),
+				modifiers: Modifiers::empty(),
 			})
 	}
 
@@ -303,6 +669,59 @@ impl EventContext {
 		self.keyboards.get_mut(&keyboard_index)
 	}
 
+	/// Registers a newly connected pointing device.
+	pub fn add_cursor(&mut self, cursor_index: usize) {
+		self.get_cursor_context(cursor_index);
+	}
+
+	/// Removes a disconnected pointing device.
+	/// The widgets it was over get left and a capture gets released.
+	pub fn remove_cursor(&mut self, cursor_index: usize) {
+		let mut cursor_ctx = match self.cursors.remove(&cursor_index) {
+			Some(cursor_ctx) => cursor_ctx,
+			None => return,
+		};
+		cursor_ctx.hide_custom_icon();
+		let leave_event = WidgetEvent::OnCursorLeave {
+			cursor: cursor_index,
+		};
+		for widget in &cursor_ctx.last_over_widgets {
+			widget.get().on_event(&leave_event);
+		}
+		if cursor_ctx.captured_by_widget.is_some() {
+			GUIApplication::get().platform_context_mut().set_capture_cursor(cursor_index, false);
+		}
+		if let Some(drag) = cursor_ctx.drag {
+			self.end_drag(cursor_index, drag, false);
+		}
+		self.hide_tooltip_of(cursor_index);
+	}
+
+	/// Returns the modifier keys held down on the given keyboard.
+	/// Pointers use the modifiers of the keyboard with the same index.
+	pub fn modifiers(&self, keyboard_index: usize) -> Modifiers {
+		self.keyboards
+			.get(&keyboard_index)
+			.map(|keyboard_ctx| keyboard_ctx.modifiers)
+			.unwrap_or_default()
+	}
+
+	pub fn handle_modifiers_changed(&mut self, keyboard_index: usize, modifiers: Modifiers) {
+		self.get_keyboard_context(keyboard_index).modifiers = modifiers;
+	}
+
+	/// Registers a newly connected keyboard.
+	pub fn add_keyboard(&mut self, keyboard_index: usize) {
+		self.get_keyboard_context(keyboard_index);
+	}
+
+	/// Removes a disconnected keyboard, the widget it focused gets unfocused.
+	pub fn remove_keyboard(&mut self, keyboard_index: usize) {
+		if let Some(mut keyboard_ctx) = self.keyboards.remove(&keyboard_index) {
+			keyboard_ctx.change_focus(None);
+		}
+	}
+
 	pub fn change_focus(&mut self, keyboard: usize, widget: Option<WidgetRef<dyn Widget>>) {
 		let keyboard = self.get_keyboard_context(keyboard);
 		keyboard.change_focus(widget);
@@ -322,7 +741,6 @@ impl EventContext {
 						}
 					}
 					WidgetFocusChange::AllKeyboards => {
-						// TODO: User list of available keyboards, instead of currently registered ones
 						for (_, ctx) in self.keyboards.iter_mut() {
 							ctx.change_focus(Some(widget.clone()));
 						}
@@ -354,30 +772,123 @@ impl EventContext {
 			}
 			if let Some(cursor) = reply.capture_cursor {
 				let cursor_ctx = self.get_cursor_context(cursor);
-				let capture = &mut cursor_ctx.captured_by_widget;
-				if capture.is_none() {
-					*capture = Some(widget.clone());
+				if cursor_ctx.captured_by_widget.is_none() {
+					cursor_ctx.captured_by_widget = Some(widget.clone());
+					cursor_ctx.capture_root = Some(widget.get().get_root());
 					GUIApplication::get().platform_context_mut().set_capture_cursor(cursor, true);
 				}
 			}
+			if let Some(delay) = reply.request_tick {
+				let time = Instant::now() + delay;
+				self.tick_requests.retain(|(requester, _)| requester != widget);
+				self.tick_requests.push((widget.clone(), time));
+			}
+			if let Some((cursor, payload, visual)) = &reply.start_drag {
+				self.start_drag(*cursor, Some(widget.clone()), payload.clone(), visual.clone());
+			}
+			if let Some(area) = reply.ime_cursor_area {
+				let root = widget.get().get_root();
+				GUIApplication::get().platform_context_mut().set_ime_cursor_area(&root, area);
+			}
 			if let Some(cursor) = reply.release_cursor {
-				let capture = &mut self.get_cursor_context(cursor).captured_by_widget;
-				if let Some(captured_widget) = capture {
-					if captured_widget == widget {
-						*capture = None;
-						GUIApplication::get().platform_context_mut().set_capture_cursor(cursor, false);
-					}
+				let captured = self.get_cursor_context(cursor).captured_by_widget.as_ref() == Some(widget);
+				if captured {
+					self.release_capture(cursor);
 				}
 			}
 		}
 	}
 
+	fn release_capture(&mut self, cursor_index: usize) {
+		let cursor_ctx = match self.try_get_cursor_context(cursor_index) {
+			Some(cursor_ctx) => cursor_ctx,
+			None => return,
+		};
+		cursor_ctx.capture_root = None;
+		if cursor_ctx.captured_by_widget.take().is_some() {
+			GUIApplication::get().platform_context_mut().set_capture_cursor(cursor_index, false);
+		}
+	}
+
+	/// Ends the capture of the cursor if the capturing widget got removed from the widget tree.
+	fn validate_capture(&mut self, cursor_index: usize) {
+		let removed = self.try_get_cursor_context(cursor_index).is_some_and(|cursor_ctx| {
+			match &cursor_ctx.captured_by_widget {
+				Some(widget) => Some(widget.get().get_root()) != cursor_ctx.capture_root,
+				None => false,
+			}
+		});
+		if removed {
+			self.release_capture(cursor_index);
+		}
+	}
+
+	/// Drops the focus of every keyboard whose focused widget got removed from the widget tree,
+	/// e.g. because a [crate::widgets::ListView] recycled it to show another item.
+	fn validate_focus(&mut self) {
+		for keyboard_ctx in self.keyboards.values_mut() {
+			let removed = keyboard_ctx
+				.focused_widget
+				.as_ref()
+				.is_some_and(|focused| Some(focused.get().get_root()) != keyboard_ctx.focus_root);
+			if removed {
+				keyboard_ctx.change_focus(None);
+			}
+		}
+	}
+
+	/// Ends the capture of the cursor because the platform lost the pointer,
+	/// e.g. the window lost the focus while a button was held.
+	///
+	/// The capturing widget receives a button up for every button still held,
+	/// so it does not wait for a release that never arrives. A drag of the cursor gets cancelled.
+	pub fn handle_capture_lost(&mut self, cursor_index: usize) {
+		self.validate_capture(cursor_index);
+		let modifiers = self.modifiers(cursor_index);
+		let (captured, buttons, pos) = match self.try_get_cursor_context(cursor_index) {
+			Some(cursor_ctx) => {
+				cursor_ctx.about_to_be_clicked.clear();
+				(
+					cursor_ctx.captured_by_widget.clone(),
+					std::mem::take(&mut cursor_ctx.pressed_buttons),
+					cursor_ctx.pos,
+				)
+			}
+			None => return,
+		};
+		if let Some(captured) = captured {
+			for button in buttons {
+				let up_event = WidgetEvent::OnMouseButtonUp {
+					mouse: cursor_index,
+					button,
+					pos,
+					modifiers,
+				};
+				let reply = captured.get().on_event(&up_event);
+				self.process_reply(&captured, &reply);
+			}
+		}
+		self.release_capture(cursor_index);
+		self.cancel_drag(cursor_index);
+	}
+
 	pub fn handle_mouse_move(
 		&mut self,
 		widget_path: &WidgetPath,
 		cursor_index: usize,
 		pos: &Vector2<scalar>,
 	) {
+		self.validate_capture(cursor_index);
+		self.validate_focus();
+		let cursor_ctx = self.get_cursor_context(cursor_index);
+		cursor_ctx.pos = *pos;
+		cursor_ctx.update_custom_icon();
+		if self.is_dragging(cursor_index) {
+			self.handle_drag_move(widget_path, cursor_index, pos);
+			return;
+		}
+
+		let modifiers = self.modifiers(cursor_index);
 		let cursor_ctx = self.get_cursor_context(cursor_index);
 
 		let enter_event = WidgetEvent::OnCursorEnter {
@@ -386,27 +897,81 @@ impl EventContext {
 		let move_event = WidgetEvent::OnCursorMove {
 			cursor: cursor_index,
 			pos: *pos,
+			modifiers,
 		};
 		let leave_event = WidgetEvent::OnCursorLeave {
 			cursor: cursor_index,
 		};
 
-		if let Some(captured_cursor) = &cursor_ctx.captured_by_widget {
-			captured_cursor.get().on_event(&move_event);
+		if let Some(captured_cursor) = cursor_ctx.captured_by_widget.clone() {
+			let reply = captured_cursor.get().on_event(&move_event);
+			self.process_reply(&captured_cursor, &reply);
 		} else {
+			// a parent intercepting the move hides it from all widgets, but they still get entered and left
+			let intercepted = self.tunnel_preview(widget_path, &move_event).is_some();
+			let mut last_over_widgets = std::mem::take(&mut self.get_cursor_context(cursor_index).last_over_widgets);
 			let mut over_widgets: HashSet<WidgetRef<dyn Widget>> = HashSet::new();
 			for widget in widget_path.bubble() {
 				over_widgets.insert(widget.clone());
-				if !cursor_ctx.last_over_widgets.remove(widget) {
+				if !last_over_widgets.remove(widget) {
 					widget.get().on_event(&enter_event);
 				}
-				widget.get().on_event(&move_event);
+				if !intercepted {
+					let reply = widget.get().on_event(&move_event);
+					self.process_reply(widget, &reply);
+				}
 			}
 
-			for widget in &cursor_ctx.last_over_widgets {
+			for widget in &last_over_widgets {
 				widget.get().on_event(&leave_event);
 			}
-			cursor_ctx.last_over_widgets = over_widgets;
+			self.get_cursor_context(cursor_index).last_over_widgets = over_widgets;
+		}
+		self.update_cursor_icon(widget_path, cursor_index);
+		self.update_tooltip(widget_path, cursor_index);
+	}
+
+	/// Starts the delay for the tooltip of the deepest widget under the cursor with a tooltip.
+	/// A tooltip pending or shown for another widget gets hidden.
+	fn update_tooltip(&mut self, widget_path: &WidgetPath, cursor_index: usize) {
+		let cursor_ctx = self.get_cursor_context(cursor_index);
+		// tooltips only appear while hovering, not while pressing a button or capturing
+		let owner = if cursor_ctx.captured_by_widget.is_none() && cursor_ctx.pressed_buttons.is_empty() {
+			widget_path
+				.bubble()
+				.find_map(|widget| widget.get().tooltip().map(|tooltip| (widget.clone(), tooltip)))
+		} else {
+			None
+		};
+		let keep = match &self.tooltip {
+			Some(tooltip) if tooltip.cursor == cursor_index => {
+				owner.as_ref().is_some_and(|(widget, _)| *widget == tooltip.owner)
+			}
+			// other cursors only replace the tooltip if they rest on another one
+			Some(_) => owner.is_none(),
+			None => false,
+		};
+		if keep {
+			return;
+		}
+		self.hide_tooltip();
+		if let Some((owner, content)) = owner {
+			let due = Instant::now() + self.tooltip_delay;
+			let root = widget_path.widget.clone();
+			self.tooltip = Some(TooltipState::new(owner, content, cursor_index, root, due));
+		}
+	}
+
+	/// Hides the tooltip, or stops its delay if it is not shown yet.
+	pub fn hide_tooltip(&mut self) {
+		if let Some(tooltip) = self.tooltip.take() {
+			tooltip.hide();
+		}
+	}
+
+	fn hide_tooltip_of(&mut self, cursor_index: usize) {
+		if self.tooltip.as_ref().is_some_and(|tooltip| tooltip.cursor == cursor_index) {
+			self.hide_tooltip();
 		}
 	}
 
@@ -417,17 +982,56 @@ impl EventContext {
 		button: MouseButton,
 		pos: &Vector2<scalar>,
 	) {
+		self.validate_capture(mouse_index);
+		self.validate_focus();
+		self.hide_tooltip();
+		// a press outside of the popups on top closes them and gets consumed
+		let dismissed = widget_path
+			.widget
+			.get()
+			.as_window()
+			.is_some_and(|window| window.dismiss_popups_at(pos));
+		if dismissed {
+			return;
+		}
+		// the release got lost, e.g. because it happened outside of the window without a capture
+		if self.get_cursor_context(mouse_index).pressed_buttons.contains(&button) {
+			self.handle_mouse_button_up(widget_path, mouse_index, button, pos);
+		}
+
+		let now = Instant::now();
+		let settings = self.click_settings;
+		let modifiers = self.modifiers(mouse_index);
 		let cursor_ctx = self.get_cursor_context(mouse_index);
+		cursor_ctx.pressed_buttons.insert(button);
+		cursor_ctx.pos = *pos;
+		let count = match &cursor_ctx.last_press {
+			Some(last) if last.button == button
+				&& now.saturating_duration_since(last.time) <= settings.max_interval
+				&& (*pos - last.pos).magnitude() <= settings.max_distance => last.count % 3 + 1,
+			_ => 1,
+		};
+		cursor_ctx.last_press = Some(ButtonPress {
+			button,
+			pos: *pos,
+			time: now,
+			count,
+		});
 
 		let down_event = WidgetEvent::OnMouseButtonDown {
 			mouse: mouse_index,
 			button,
 			pos: *pos,
+			modifiers,
 		};
 
 		if let Some(captured_cursor) = cursor_ctx.captured_by_widget.clone() {
 			let reply = captured_cursor.get().on_event(&down_event);
 			self.process_reply(&captured_cursor, &reply);
+		} else if let Some(widget) = self.tunnel_preview(widget_path, &down_event) {
+			// the intercepting widget may still get clicked
+			let cursor_ctx = self.get_cursor_context(mouse_index);
+			cursor_ctx.about_to_be_clicked.insert(button, HashSet::from([widget]));
 		} else {
 			let mut down_widgets: HashSet<WidgetRef<dyn Widget>> = HashSet::new();
 			for widget in widget_path.bubble() {
@@ -440,9 +1044,29 @@ impl EventContext {
 			}
 			if down_widgets.len() > 0 {
 				let cursor_ctx = self.get_cursor_context(mouse_index);
-				*cursor_ctx.about_to_be_clicked.entry(0).or_default() = down_widgets;
+				cursor_ctx.about_to_be_clicked.insert(button, down_widgets);
 			}
 		}
+
+		self.update_cursor_icon(widget_path, mouse_index);
+
+		let multi_click_event = match count {
+			2 => WidgetEvent::OnDoubleClick {
+				mouse: mouse_index,
+				button,
+				pos: *pos,
+				modifiers,
+			},
+			3 => WidgetEvent::OnTripleClick {
+				mouse: mouse_index,
+				button,
+				pos: *pos,
+				modifiers,
+			},
+			_ => return,
+		};
+		// the down event may have captured the cursor, so the multi click follows the capture
+		self.dispatch_to_cursor(widget_path, mouse_index, &multi_click_event);
 	}
 
 	pub fn handle_mouse_button_up(
@@ -452,15 +1076,30 @@ impl EventContext {
 		button: MouseButton,
 		pos: &Vector2<scalar>,
 	) {
+		self.validate_capture(cursor_index);
+		self.validate_focus();
+		let cursor_ctx = self.get_cursor_context(cursor_index);
+		cursor_ctx.pressed_buttons.remove(&button);
+		cursor_ctx.pos = *pos;
+		if self.is_dragging(cursor_index) {
+			self.handle_drop(widget_path, cursor_index, pos);
+			self.get_cursor_context(cursor_index).about_to_be_clicked.remove(&button);
+			self.update_cursor_icon(widget_path, cursor_index);
+			return;
+		}
+
+		let modifiers = self.modifiers(cursor_index);
 		let up_event = WidgetEvent::OnMouseButtonUp {
-			mouse: 0,
+			mouse: cursor_index,
 			button,
 			pos: *pos,
+			modifiers,
 		};
 		let click_event = WidgetEvent::OnClick {
 			mouse: cursor_index,
 			button,
 			pos: *pos,
+			modifiers,
 		};
 
 		let cursor_ctx = self.get_cursor_context(cursor_index);
@@ -469,34 +1108,50 @@ impl EventContext {
 			self.process_reply(&captured_cursor, &reply);
 		} else {
 			let mut handled_click = None;
-			let mut up_widgets: HashSet<WidgetRef<dyn Widget>> = HashSet::new();
-			for widget in widget_path.bubble() {
-				up_widgets.insert(widget.clone());
-				let up_reply = widget.get().on_event(&up_event);
+			if let Some(widget) = self.tunnel_preview(widget_path, &up_event) {
 				let cursor_ctx = self.get_cursor_context(cursor_index);
-				let about_to_be_clicked = cursor_ctx.about_to_be_clicked.get(&cursor_index);
-				let reply = about_to_be_clicked.and_then(|about_to| {
-					if handled_click.is_none() || about_to.contains(widget) {
-						Some(widget.get().on_event(&click_event))
-					} else {
-						None
-					}
-				});
-				if let Some(click_reply) = reply {
+				let clicked = cursor_ctx
+					.about_to_be_clicked
+					.get(&button)
+					.is_some_and(|about_to| about_to.contains(&widget));
+				if clicked {
+					let click_reply = widget.get().on_event(&click_event);
 					if click_reply.handled {
-						handled_click = Some(widget.clone());
-						self.process_reply(widget, &click_reply);
+						self.process_reply(&widget, &click_reply);
+						handled_click = Some(widget);
 					}
 				}
+			} else {
+				let mut up_widgets: HashSet<WidgetRef<dyn Widget>> = HashSet::new();
+				for widget in widget_path.bubble() {
+					up_widgets.insert(widget.clone());
+					let up_reply = widget.get().on_event(&up_event);
+					let cursor_ctx = self.get_cursor_context(cursor_index);
+					let about_to_be_clicked = cursor_ctx.about_to_be_clicked.get(&button);
+					let reply = about_to_be_clicked.and_then(|about_to| {
+						if handled_click.is_none() && about_to.contains(widget) {
+							Some(widget.get().on_event(&click_event))
+						} else {
+							None
+						}
+					});
+					if let Some(click_reply) = reply {
+						if click_reply.handled {
+							handled_click = Some(widget.clone());
+							self.process_reply(widget, &click_reply);
+						}
+					}
 
-				if up_reply.handled {
-					break;
+					if up_reply.handled {
+						break;
+					}
 				}
 			}
 			let cursor_ctx = self.get_cursor_context(cursor_index);
-			cursor_ctx.about_to_be_clicked.remove(&cursor_index);
+			cursor_ctx.about_to_be_clicked.remove(&button);
 
-			let keyboard_ctx = self.try_get_keyboard_context(0);
+			// every pointer controls the focus of the keyboard with the same index
+			let keyboard_ctx = self.try_get_keyboard_context(cursor_index);
 			if let Some(keyboard_ctx) = keyboard_ctx {
 				if handled_click.is_none()
 					|| (keyboard_ctx.focused_widget.is_some()
@@ -507,25 +1162,519 @@ impl EventContext {
 				}
 			}
 		}
+		self.update_cursor_icon(widget_path, cursor_index);
 	}
 
+	/// Resolves the cursor icon from the widget capturing the cursor or the widgets under it,
+	/// the deepest widget with an icon wins. Changes get applied through the platform.
+	fn update_cursor_icon(&mut self, widget_path: &WidgetPath, cursor_index: usize) {
+		let cursor_ctx = self.get_cursor_context(cursor_index);
+		let icon = match &cursor_ctx.captured_by_widget {
+			Some(captured) => std::iter::successors(Some(captured.clone()), |widget| widget.get().get_parent())
+				.find_map(|widget| widget.get().cursor_icon()),
+			None => widget_path.bubble().find_map(|widget| widget.get().cursor_icon()),
+		}
+		.unwrap_or_default();
+
+		let root = Some(widget_path.widget.clone());
+		if cursor_ctx.icon == icon && cursor_ctx.root == root {
+			return;
+		}
+		cursor_ctx.icon = icon.clone();
+		cursor_ctx.root = root;
+		cursor_ctx.update_custom_icon();
+		GUIApplication::get().platform_context_mut().set_cursor_icon(cursor_index, &icon);
+	}
+
+	/// Bubbles a scroll along the widget path under the cursor until a widget handles it.
+	///
+	/// Scrolling by lines while Shift is held scrolls horizontally.
+	pub fn handle_scroll(
+		&mut self,
+		widget_path: &WidgetPath,
+		cursor_index: usize,
+		delta: ScrollDelta,
+		phase: ScrollPhase,
+		pos: &Vector2<scalar>,
+	) {
+		self.validate_capture(cursor_index);
+		self.validate_focus();
+		self.hide_tooltip_of(cursor_index);
+		let modifiers = self.modifiers(cursor_index);
+		let delta = match delta {
+			ScrollDelta::Lines(lines) if modifiers.shift() => ScrollDelta::Lines(Vector2::new(lines.y, lines.x)),
+			delta => delta,
+		};
+		let scroll_event = WidgetEvent::OnScroll {
+			cursor: cursor_index,
+			delta,
+			phase,
+			pos: *pos,
+			modifiers,
+		};
+
+		self.dispatch_to_cursor(widget_path, cursor_index, &scroll_event);
+	}
+
+	/// Sends the event to the widget capturing the cursor,
+	/// or routes it along the widget path until a widget handles it.
+	fn dispatch_to_cursor(&mut self, widget_path: &WidgetPath, cursor_index: usize, event: &WidgetEvent) {
+		let cursor_ctx = self.get_cursor_context(cursor_index);
+		if let Some(captured_cursor) = cursor_ctx.captured_by_widget.clone() {
+			let reply = captured_cursor.get().on_event(event);
+			self.process_reply(&captured_cursor, &reply);
+		} else if self.tunnel_preview(widget_path, event).is_none() {
+			for widget in widget_path.bubble() {
+				let reply = widget.get().on_event(event);
+				self.process_reply(widget, &reply);
+				if reply.handled {
+					break;
+				}
+			}
+		}
+	}
+
+	/// Bubbles a touch down along the widget path under the finger.
+	/// The widget handling it receives all further events of that finger.
+	///
+	/// Independent of that, the first widget on the path subscribed to gestures
+	/// gets the gestures recognized from all fingers touching it.
+	pub fn handle_touch_down(
+		&mut self,
+		widget_path: &WidgetPath,
+		finger: u64,
+		pos: &Vector2<scalar>,
+		force: Option<TouchForce>,
+	) {
+		self.validate_focus();
+		let down_event = WidgetEvent::OnTouchDown {
+			finger,
+			pos: *pos,
+			force,
+		};
+		let mut target = self.tunnel_preview(widget_path, &down_event);
+		if target.is_none() {
+			for widget in widget_path.bubble() {
+				let reply = widget.get().on_event(&down_event);
+				self.process_reply(widget, &reply);
+				if reply.handled {
+					target = Some(widget.clone());
+					break;
+				}
+			}
+		}
+		self.touches.insert(finger, target);
+
+		let now = Instant::now();
+		let subscriber = widget_path
+			.bubble()
+			.map(|widget| (widget, widget.get().gestures()))
+			.find(|(_, gestures)| !gestures.is_empty());
+		if let Some((widget, gestures)) = subscriber {
+			let index = match self
+				.gesture_recognizers
+				.iter()
+				.position(|recognizer| &recognizer.target == widget)
+			{
+				Some(index) => index,
+				None => {
+					self.gesture_recognizers
+						.push(GestureRecognizer::new(widget.clone(), gestures, now));
+					self.gesture_recognizers.len() - 1
+				}
+			};
+			let recognizer = &mut self.gesture_recognizers[index];
+			let gestures = recognizer.touch_down(finger, *pos, now);
+			let target = recognizer.target.clone();
+			self.dispatch_gestures(&target, gestures);
+		}
+	}
+
+	pub fn handle_touch_move(&mut self, finger: u64, pos: &Vector2<scalar>, force: Option<TouchForce>) {
+		let move_event = WidgetEvent::OnTouchMove {
+			finger,
+			pos: *pos,
+			force,
+		};
+		if let Some(Some(target)) = self.touches.get(&finger).cloned() {
+			let reply = target.get().on_event(&move_event);
+			self.process_reply(&target, &reply);
+		}
+
+		self.update_gestures(finger, |recognizer| recognizer.touch_move(finger, *pos));
+	}
+
+	pub fn handle_touch_up(&mut self, finger: u64, pos: &Vector2<scalar>) {
+		let up_event = WidgetEvent::OnTouchUp { finger, pos: *pos };
+		if let Some(Some(target)) = self.touches.remove(&finger) {
+			let reply = target.get().on_event(&up_event);
+			self.process_reply(&target, &reply);
+		}
+
+		let now = Instant::now();
+		self.update_gestures(finger, |recognizer| recognizer.touch_up(finger, *pos, now));
+	}
+
+	pub fn handle_touch_cancel(&mut self, finger: u64) {
+		let cancel_event = WidgetEvent::OnTouchCancel { finger };
+		if let Some(Some(target)) = self.touches.remove(&finger) {
+			let reply = target.get().on_event(&cancel_event);
+			self.process_reply(&target, &reply);
+		}
+
+		self.update_gestures(finger, |recognizer| recognizer.touch_cancel(finger));
+	}
+
+	/// Feeds the finger into the gesture recognizer tracking it,
+	/// dispatches the recognized gestures and drops the recognizer once all its fingers got lifted.
+	fn update_gestures(&mut self, finger: u64, update: impl FnOnce(&mut GestureRecognizer) -> Vec<Gesture>) {
+		let index = match self
+			.gesture_recognizers
+			.iter()
+			.position(|recognizer| recognizer.has_finger(finger))
+		{
+			Some(index) => index,
+			None => return,
+		};
+		let recognizer = &mut self.gesture_recognizers[index];
+		let gestures = update(recognizer);
+		let target = recognizer.target.clone();
+		if recognizer.is_finished() {
+			self.gesture_recognizers.remove(index);
+		}
+		self.dispatch_gestures(&target, gestures);
+	}
+
+	fn dispatch_gestures(&mut self, target: &WidgetRef<dyn Widget>, gestures: Vec<Gesture>) {
+		for gesture in gestures {
+			let gesture_event = WidgetEvent::OnGesture { gesture };
+			let reply = target.get().on_event(&gesture_event);
+			self.process_reply(target, &reply);
+		}
+	}
+
+	/// Starts dragging the payload with the given cursor, cancelling a drag already in progress.
+	///
+	/// Widgets normally start drags with [Reply::start_drag()],
+	/// platforms call this directly for drags from outside the application without a source widget.
+	pub fn start_drag(
+		&mut self,
+		cursor_index: usize,
+		source: Option<WidgetRef<dyn Widget>>,
+		payload: DragPayload,
+		visual: Option<WidgetRef<dyn Widget>>,
+	) {
+		self.cancel_drag(cursor_index);
+		let cursor_ctx = self.get_cursor_context(cursor_index);
+		cursor_ctx.about_to_be_clicked.clear();
+		cursor_ctx.drag = Some(DragState::new(source, payload, visual));
+		self.release_capture(cursor_index);
+	}
+
+	pub fn is_dragging(&self, cursor_index: usize) -> bool {
+		self.cursors
+			.get(&cursor_index)
+			.is_some_and(|cursor_ctx| cursor_ctx.drag.is_some())
+	}
+
+	/// Moves the drag of the cursor, sending drag enter and leave events to the widgets along the path
+	/// and routing a drag over to find the widget accepting the drop.
+	pub fn handle_drag_move(&mut self, widget_path: &WidgetPath, cursor_index: usize, pos: &Vector2<scalar>) {
+		let mut drag = match self.get_cursor_context(cursor_index).drag.take() {
+			Some(drag) => drag,
+			None => return,
+		};
+		drag.move_to(widget_path.widget.clone(), *pos);
+
+		let enter_event = WidgetEvent::OnDragEnter {
+			cursor: cursor_index,
+			payload: drag.payload.clone(),
+		};
+		let leave_event = WidgetEvent::OnDragLeave {
+			cursor: cursor_index,
+		};
+		let mut over_widgets: HashSet<WidgetRef<dyn Widget>> = HashSet::new();
+		for widget in widget_path.bubble() {
+			over_widgets.insert(widget.clone());
+			if !drag.over_widgets.remove(widget) {
+				widget.get().on_event(&enter_event);
+			}
+		}
+		for widget in &drag.over_widgets {
+			widget.get().on_event(&leave_event);
+		}
+		drag.over_widgets = over_widgets;
+
+		let over_event = WidgetEvent::OnDragOver {
+			cursor: cursor_index,
+			payload: drag.payload.clone(),
+			pos: *pos,
+		};
+		drag.target = None;
+		let routing = widget_path
+			.tunnel()
+			.map(|widget| (widget, true))
+			.chain(widget_path.bubble().map(|widget| (widget, false)));
+		for (widget, preview) in routing {
+			let reply = if preview {
+				widget.get().on_preview_event(&over_event)
+			} else {
+				widget.get().on_event(&over_event)
+			};
+			self.process_reply(widget, &reply);
+			if reply.handled {
+				if !reply.reject_drop {
+					drag.target = Some(widget.clone());
+				}
+				break;
+			}
+		}
+
+		// a widget may have started a new drag while handling the events
+		let cursor_ctx = self.get_cursor_context(cursor_index);
+		if cursor_ctx.drag.is_none() {
+			cursor_ctx.drag = Some(drag);
+		}
+	}
+
+	/// Drops the payload of the cursor's drag on the widget accepting it at the given position.
+	/// Returns true if the drop got handled.
+	pub fn handle_drop(&mut self, widget_path: &WidgetPath, cursor_index: usize, pos: &Vector2<scalar>) -> bool {
+		self.handle_drag_move(widget_path, cursor_index, pos);
+		let drag = match self.get_cursor_context(cursor_index).drag.take() {
+			Some(drag) => drag,
+			None => return false,
+		};
+		let mut dropped = false;
+		if let Some(target) = &drag.target {
+			let drop_event = WidgetEvent::OnDrop {
+				cursor: cursor_index,
+				payload: drag.payload.clone(),
+				pos: *pos,
+			};
+			let reply = target.get().on_event(&drop_event);
+			self.process_reply(target, &reply);
+			dropped = reply.handled;
+		}
+		self.end_drag(cursor_index, drag, dropped);
+		dropped
+	}
+
+	/// Ends the drag of the cursor without dropping it.
+	pub fn cancel_drag(&mut self, cursor_index: usize) {
+		let drag = self
+			.try_get_cursor_context(cursor_index)
+			.and_then(|cursor_ctx| cursor_ctx.drag.take());
+		if let Some(drag) = drag {
+			self.end_drag(cursor_index, drag, false);
+		}
+	}
+
+	fn end_drag(&mut self, cursor_index: usize, drag: DragState, dropped: bool) {
+		drag.hide_visual();
+		let leave_event = WidgetEvent::OnDragLeave {
+			cursor: cursor_index,
+		};
+		for widget in &drag.over_widgets {
+			widget.get().on_event(&leave_event);
+		}
+		if let Some(source) = &drag.source {
+			let end_event = WidgetEvent::OnDragEnd {
+				cursor: cursor_index,
+				dropped,
+			};
+			let reply = source.get().on_event(&end_event);
+			self.process_reply(source, &reply);
+		}
+	}
+
+	/// Sends the event as preview from the root down the widget path until a widget handles it.
+	/// Returns the widget that handled it.
+	fn tunnel_preview(&mut self, widget_path: &WidgetPath, event: &WidgetEvent) -> Option<WidgetRef<dyn Widget>> {
+		for widget in widget_path.tunnel() {
+			let reply = widget.get().on_preview_event(event);
+			self.process_reply(widget, &reply);
+			if reply.handled {
+				return Some(widget.clone());
+			}
+		}
+		None
+	}
+
+	/// Sends the event as preview from the root down to the focused widget until a widget handles it.
+	/// Returns true if it got handled.
+	fn tunnel_preview_focused(&mut self, focused_widget: &WidgetRef<dyn Widget>, event: &WidgetEvent) -> bool {
+		let mut ancestors = vec![focused_widget.clone()];
+		while let Some(parent) = ancestors.last().unwrap().get().get_parent() {
+			ancestors.push(parent);
+		}
+		for widget in ancestors.iter().rev() {
+			let reply = widget.get().on_preview_event(event);
+			self.process_reply(widget, &reply);
+			if reply.handled {
+				return true;
+			}
+		}
+		false
+	}
+
+	/// Returns when the next requested tick or pending gesture timeout is due.
+	pub fn next_tick(&self) -> Option<Instant> {
+		self.tick_requests
+			.iter()
+			.map(|(_, time)| *time)
+			.chain(self.gesture_recognizers.iter().filter_map(|recognizer| recognizer.deadline()))
+			.chain(self.tooltip.as_ref().and_then(|tooltip| tooltip.due))
+			.min()
+	}
+
+	/// Sends [WidgetEvent::OnTick] to all widgets whose requested tick is due at the given time
+	/// and recognizes gestures that depend on time, like long presses.
+	pub fn handle_tick(&mut self, now: Instant) {
+		self.validate_focus();
+		let (due, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.tick_requests)
+			.into_iter()
+			.partition(|(_, time)| *time <= now);
+		self.tick_requests = pending;
+		let tick_event = WidgetEvent::OnTick { time: now };
+		for (widget, _) in due {
+			let reply = widget.get().on_event(&tick_event);
+			self.process_reply(&widget, &reply);
+		}
+
+		let recognized: Vec<_> = self
+			.gesture_recognizers
+			.iter_mut()
+			.map(|recognizer| (recognizer.target.clone(), recognizer.tick(now)))
+			.collect();
+		for (target, gestures) in recognized {
+			self.dispatch_gestures(&target, gestures);
+		}
+
+		let due_tooltip = self
+			.tooltip
+			.as_ref()
+			.filter(|tooltip| tooltip.due.is_some_and(|due| due <= now))
+			.map(|tooltip| tooltip.cursor);
+		if let Some(cursor_index) = due_tooltip {
+			let pos = self.get_cursor_context(cursor_index).pos;
+			if let Some(tooltip) = &mut self.tooltip {
+				tooltip.show(pos);
+			}
+		}
+	}
+
+	/// Dispatches a key down to the widget focused by the keyboard.
+	///
+	/// If the key and the held modifiers are bound to a command that gets handled,
+	/// the key down itself does not get dispatched.
+	/// If the key is Tab and the focused widget does not handle it,
+	/// the focus moves to the next focusable widget of the window (previous if Shift is held).
 	pub fn handle_key_down(
 		&mut self,
+		window: &WidgetRef<dyn Widget>,
 		keyboard_index: usize,
 		key_physical: usize,
 		key: Option<VirtualKeyCode>,
 	) {
-		let keyboard_ctx = self.try_get_keyboard_context(keyboard_index);
-		if let Some(keyboard_ctx) = keyboard_ctx {
-			if let Some(focused_widget) = &keyboard_ctx.focused_widget {
-				let key_down_event = WidgetEvent::OnKeyDown {
-					keyboard: keyboard_index,
-					key_physical,
-					key,
-				};
-				focused_widget.get().on_event(&key_down_event);
+		self.validate_focus();
+		let keyboard_ctx = self.get_keyboard_context(keyboard_index);
+		if let Some(key) = key {
+			keyboard_ctx.pressed_keys.insert(key);
+		}
+		let modifiers = keyboard_ctx.modifiers;
+		self.hide_tooltip();
+
+		// escape cancels the drag of the pointer with the same index or closes the popup on top
+		if key == Some(VirtualKeyCode::Escape) {
+			if self.is_dragging(keyboard_index) {
+				self.cancel_drag(keyboard_index);
+				return;
+			}
+			if window.get().as_window().is_some_and(|window| window.dismiss_top_popup()) {
+				return;
+			}
+		}
+
+		// a modal popup blocks the keyboard input to everything below it
+		let modal = window.get().as_window().and_then(|window| window.modal_popup());
+		if let Some(modal) = &modal {
+			let keyboard_ctx = self.get_keyboard_context(keyboard_index);
+			let focused_below = keyboard_ctx.focused_widget.as_ref().is_some_and(|focused| {
+				!std::iter::successors(Some(focused.clone()), |widget| widget.get().get_parent())
+					.any(|widget| &widget == modal)
+			});
+			if focused_below {
+				keyboard_ctx.change_focus(None);
+			}
+		}
+
+		if let Some(key) = key.filter(|key| !is_modifier_key(*key)) {
+			if self.handle_chord(window, keyboard_index, KeyChord::new(modifiers, key)) {
+				return;
+			}
+		}
+
+		let keyboard_ctx = self.get_keyboard_context(keyboard_index);
+
+		let mut handled = false;
+		if let Some(focused_widget) = keyboard_ctx.focused_widget.clone() {
+			let key_down_event = WidgetEvent::OnKeyDown {
+				keyboard: keyboard_index,
+				key_physical,
+				key,
+				modifiers,
+			};
+			handled = self.tunnel_preview_focused(&focused_widget, &key_down_event);
+			if !handled {
+				let reply = focused_widget.get().on_event(&key_down_event);
+				self.process_reply(&focused_widget, &reply);
+				handled = reply.handled;
 			}
 		}
+
+		if !handled && key == Some(VirtualKeyCode::Tab) {
+			let keyboard_ctx = self.get_keyboard_context(keyboard_index);
+			keyboard_ctx.move_focus(modal.as_ref().unwrap_or(window), modifiers.shift());
+		}
+	}
+
+	/// Sends the commands bound to the chord until one gets handled, returns true if one got handled.
+	fn handle_chord(&mut self, window: &WidgetRef<dyn Widget>, keyboard_index: usize, chord: KeyChord) -> bool {
+		let focused = self
+			.try_get_keyboard_context(keyboard_index)
+			.and_then(|keyboard_ctx| keyboard_ctx.focused_widget.clone());
+		let bindings: Vec<(String, BindingScope)> = self
+			.commands
+			.resolve(chord, window, focused.as_ref())
+			.into_iter()
+			.map(|binding| (binding.command.clone(), binding.scope.clone()))
+			.collect();
+
+		for (command, scope) in bindings {
+			let command_event = WidgetEvent::OnCommand {
+				keyboard: keyboard_index,
+				command: command.clone(),
+			};
+			let mut widget = focused.clone().or_else(|| Some(window.clone()));
+			while let Some(current) = widget {
+				let reply = current.get().on_event(&command_event);
+				self.process_reply(&current, &reply);
+				if reply.handled {
+					return true;
+				}
+				if let BindingScope::Widget(scope) = &scope {
+					if scope == &current {
+						break;
+					}
+				}
+				widget = current.get().get_parent();
+			}
+			if self.commands.execute(&command, keyboard_index) {
+				return true;
+			}
+		}
+		false
 	}
 
 	pub fn handle_key_up(
@@ -534,35 +1683,74 @@ impl EventContext {
 		key_physical: usize,
 		key: Option<VirtualKeyCode>,
 	) {
+		self.validate_focus();
 		let keyboard_ctx = self.try_get_keyboard_context(keyboard_index);
 		if let Some(keyboard_ctx) = keyboard_ctx {
-			if let Some(focused_widget) = &keyboard_ctx.focused_widget {
-				let key_up_event = WidgetEvent::OnKeyUp {
-					keyboard: keyboard_index,
-					key_physical,
-					key,
-				};
-				focused_widget.get().on_event(&key_up_event);
+			if let Some(key) = key {
+				keyboard_ctx.pressed_keys.remove(&key);
 			}
+			let key_up_event = WidgetEvent::OnKeyUp {
+				keyboard: keyboard_index,
+				key_physical,
+				key,
+				modifiers: keyboard_ctx.modifiers,
+			};
+			self.dispatch_to_focused(keyboard_index, &key_up_event);
 		}
 	}
 
 	pub fn handle_text(&mut self, keyboard_index: usize, character: char) {
-		let keyboard_ctx = self.try_get_keyboard_context(keyboard_index);
-		if let Some(keyboard_ctx) = keyboard_ctx {
-			if let Some(focused_widget) = &keyboard_ctx.focused_widget {
-				let text_event = WidgetEvent::OnText {
-					keyboard: keyboard_index,
-					character,
-				};
-				focused_widget.get().on_event(&text_event);
+		self.validate_focus();
+		let text_event = WidgetEvent::OnText {
+			keyboard: keyboard_index,
+			character,
+			modifiers: self.modifiers(keyboard_index),
+		};
+		self.dispatch_to_focused(keyboard_index, &text_event);
+	}
+
+	pub fn handle_ime(&mut self, keyboard_index: usize, event: ImeEvent) {
+		self.validate_focus();
+		let ime_event = match event {
+			ImeEvent::Enabled => WidgetEvent::OnImeEnabled {
+				keyboard: keyboard_index,
+			},
+			ImeEvent::Preedit(text, cursor) => WidgetEvent::OnImePreedit {
+				keyboard: keyboard_index,
+				text,
+				cursor,
+			},
+			ImeEvent::Commit(text) => WidgetEvent::OnImeCommit {
+				keyboard: keyboard_index,
+				text,
+			},
+			ImeEvent::Disabled => WidgetEvent::OnImeDisabled {
+				keyboard: keyboard_index,
+			},
+		};
+		self.dispatch_to_focused(keyboard_index, &ime_event);
+	}
+
+	/// Sends the event to the widget focused by the given keyboard and processes its reply,
+	/// unless a parent intercepts it in the preview.
+	fn dispatch_to_focused(&mut self, keyboard_index: usize, event: &WidgetEvent) {
+		let focused_widget = self
+			.try_get_keyboard_context(keyboard_index)
+			.and_then(|keyboard_ctx| keyboard_ctx.focused_widget.clone());
+		if let Some(focused_widget) = focused_widget {
+			if self.tunnel_preview_focused(&focused_widget, event) {
+				return;
 			}
+			let reply = focused_widget.get().on_event(event);
+			self.process_reply(&focused_widget, &reply);
 		}
 	}
 
 	pub fn handle_cursor_leave(&mut self, cursor_index: usize) {
 		let cursor_ctx = self.try_get_cursor_context(cursor_index);
 		if let Some(cursor_ctx) = cursor_ctx {
+			cursor_ctx.root = None;
+			cursor_ctx.update_custom_icon();
 			let cursor_leave_event = WidgetEvent::OnCursorLeave {
 				cursor: cursor_index,
 			};
@@ -570,6 +1758,7 @@ impl EventContext {
 				widget.get().on_event(&cursor_leave_event);
 			}
 		}
+		self.hide_tooltip_of(cursor_index);
 	}
 
 	pub fn handle_cursor_enter(&mut self, cursor_index: usize) {
@@ -584,3 +1773,175 @@ impl EventContext {
 		}
 	}
 }
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+	use std::cell::{Ref, RefMut};
+	use crate::{
+		events::{get_widget_path_under_position, EventContext, Reply, WidgetEvent},
+		paint::Painter,
+		testing::with_application,
+		util::{Geometry, WidgetRef},
+		widgets::{Arrangements, Children, PanelState, PanelWidget, Widget, WidgetArrangement, WidgetImpl, WidgetState},
+	};
+	use cgmath::Vector2;
+	use skia_safe::scalar;
+
+	/// Panel that counts the moves it receives and optionally intercepts them to capture the cursor.
+	struct CounterState {
+		panel: PanelState,
+		child: Option<WidgetRef<dyn Widget>>,
+		intercept: bool,
+		previewed_moves: usize,
+		moves: usize,
+	}
+
+	type Counter = WidgetImpl<CounterState>;
+
+	fn counter(child: Option<WidgetRef<dyn Widget>>, intercept: bool) -> WidgetRef<Counter> {
+		let counter = WidgetRef::new(Counter::from(CounterState {
+			panel: Default::default(),
+			child,
+			intercept,
+			previewed_moves: 0,
+			moves: 0,
+		}));
+		if let Some(child) = &counter.get().state().child {
+			child.get().set_parent(Some(counter.clone()));
+		}
+		counter
+	}
+
+	impl Widget for Counter {
+		fn widget_state(&self) -> Ref<WidgetState> {
+			self.widget_state(|v| &v.panel.widget)
+		}
+
+		fn widget_state_mut(&self) -> RefMut<WidgetState> {
+			self.widget_state_mut(|v| &mut v.panel.widget)
+		}
+
+		fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+			self.panel_paint(geometry, layer, painter)
+		}
+
+		fn get_desired_size(&self) -> Vector2<scalar> {
+			Vector2::new(0.0, 0.0)
+		}
+
+		fn get_children(&self) -> Children {
+			self.state().child.iter().cloned().collect()
+		}
+
+		fn arrange_children(&self, geometry: Geometry) {
+			self.panel_arrange_children(geometry);
+		}
+
+		fn get_arranged_children(&self) -> Arrangements {
+			self.panel_get_arranged_children()
+		}
+
+		fn cached_geometry(&self) -> Geometry {
+			self.panel_cached_geometry()
+		}
+
+		fn on_preview_event(&self, event: &WidgetEvent) -> Reply {
+			match event {
+				WidgetEvent::OnCursorMove { cursor, .. } => {
+					let mut state = self.state_mut();
+					state.previewed_moves += 1;
+					if state.intercept {
+						Reply::handled().capture_cursor(*cursor)
+					} else {
+						Reply::unhandled()
+					}
+				}
+				_ => Reply::unhandled(),
+			}
+		}
+
+		fn on_event(&self, event: &WidgetEvent) -> Reply {
+			match event {
+				WidgetEvent::OnCursorMove { .. } => {
+					self.state_mut().moves += 1;
+					Reply::handled()
+				}
+				_ => Reply::unhandled(),
+			}
+		}
+	}
+
+	impl PanelWidget for Counter {
+		fn panel_state(&self) -> Ref<PanelState> {
+			self.widget_state(|v| &v.panel)
+		}
+
+		fn panel_state_mut(&self) -> RefMut<PanelState> {
+			self.widget_state_mut(|v| &mut v.panel)
+		}
+
+		fn rearrange_children(&self, geometry: Geometry) -> Vec<WidgetArrangement> {
+			self.state().child
+				.iter()
+				.map(|child| geometry.child_widget(child.clone(), Vector2::new(0.0, 0.0), geometry.local_size()))
+				.collect()
+		}
+	}
+
+	#[test]
+	fn intercepted_move_captures_cursor() {
+		let counts = with_application(|_| {
+			let child = counter(None, false);
+			let parent = counter(Some(child.clone()), true);
+			let root: WidgetRef<dyn Widget> = parent.clone();
+			let geometry = Geometry::new(
+				Vector2::new(0.0, 0.0),
+				Vector2::new(100.0, 100.0),
+				Vector2::new(0.0, 0.0),
+				Vector2::new(1.0, 1.0),
+			);
+			root.get().arrange_children(geometry);
+
+			let mut event_context = EventContext::new();
+			for pos in [Vector2::new(10.0, 10.0), Vector2::new(20.0, 20.0)] {
+				let path = get_widget_path_under_position(geometry, root.clone(), &pos);
+				event_context.handle_mouse_move(&path, 0, &pos);
+			}
+			event_context.remove_cursor(0);
+
+			let (parent, child) = (parent.get(), child.get());
+			let counts = (
+				parent.state().previewed_moves,
+				parent.state().moves,
+				child.state().previewed_moves,
+				child.state().moves,
+			);
+			counts
+		});
+
+		// the second move only goes to the capturing parent, without another preview
+		assert_eq!(counts, (1, 1, 0, 0));
+	}
+
+	#[test]
+	fn focus_drops_when_widget_leaves_tree() {
+		let focused = with_application(|_| {
+			let child = counter(None, false);
+			let _parent = counter(Some(child.clone()), false);
+			let child: WidgetRef<dyn Widget> = child;
+
+			let mut event_context = EventContext::new();
+			event_context.change_focus(0, Some(child.clone()));
+			event_context.handle_text(0, 'a');
+			let focused_in_tree = event_context.try_get_keyboard_context(0).unwrap().focused_widget().is_some();
+
+			child.get().set_parent(None);
+			event_context.handle_text(0, 'b');
+			let focused_after_removal = event_context.try_get_keyboard_context(0).unwrap().focused_widget().is_some();
+			event_context.remove_keyboard(0);
+			(focused_in_tree, focused_after_removal)
+		});
+
+		assert_eq!(focused, (true, false));
+	}
+}
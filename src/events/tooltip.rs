@@ -0,0 +1,92 @@
+use std::time::{Duration, Instant};
+use crate::{
+	util::WidgetRef,
+	widgets::{TextBlockWidget, TooltipWidget, Widget},
+};
+use cgmath::Vector2;
+use skia_safe::scalar;
+
+/// Time the cursor has to rest on a widget until its tooltip appears.
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+/// Offset of the tooltip from the cursor position, so the cursor does not cover it.
+const TOOLTIP_OFFSET: Vector2<scalar> = Vector2::new(12.0, 16.0);
+
+/// Content of a tooltip, see [Widget::tooltip()].
+#[derive(Clone)]
+pub enum Tooltip {
+	Text(String),
+	Widget(WidgetRef<dyn Widget>),
+}
+
+impl Tooltip {
+	/// Creates the widget showing the tooltip, the content framed by a [TooltipWidget].
+	pub fn create_widget(&self) -> WidgetRef<dyn Widget> {
+		let content: WidgetRef<dyn Widget> = match self {
+			Tooltip::Text(text) => TextBlockWidget::new().text(text.clone()).build(),
+			Tooltip::Widget(widget) => widget.clone(),
+		};
+		TooltipWidget::new(content).build()
+	}
+}
+
+/// The tooltip of the widget a cursor rests on, waiting for the delay or already shown.
+pub(crate) struct TooltipState {
+	pub owner: WidgetRef<dyn Widget>,
+	pub content: Tooltip,
+	pub cursor: usize,
+	/// The root of the widget tree the owner is part of, normally the window.
+	pub root: WidgetRef<dyn Widget>,
+	/// When the tooltip appears, None once it got shown.
+	pub due: Option<Instant>,
+	pub widget: Option<WidgetRef<dyn Widget>>,
+}
+
+impl TooltipState {
+	pub fn new(
+		owner: WidgetRef<dyn Widget>,
+		content: Tooltip,
+		cursor: usize,
+		root: WidgetRef<dyn Widget>,
+		due: Instant,
+	) -> Self {
+		Self {
+			owner,
+			content,
+			cursor,
+			root,
+			due: Some(due),
+			widget: None,
+		}
+	}
+
+	/// Creates the tooltip widget and shows it as overlay of the window next to the cursor position.
+	/// If it would leave the window, it moves to the left or flips above the cursor.
+	pub fn show(&mut self, cursor_pos: Vector2<scalar>) {
+		let widget = self.content.create_widget();
+		let size = widget.get().get_desired_size();
+		let window_size = self.root.get().cached_geometry().local_size();
+
+		let mut pos = cursor_pos + TOOLTIP_OFFSET;
+		if pos.x + size.x > window_size.x {
+			pos.x = window_size.x - size.x;
+		}
+		if pos.y + size.y > window_size.y {
+			pos.y = cursor_pos.y - size.y;
+		}
+		pos.x = pos.x.max(0.0);
+		pos.y = pos.y.max(0.0);
+
+		if let Some(window) = self.root.get().as_window() {
+			window.show_overlay(widget.clone(), pos);
+		}
+		self.due = None;
+		self.widget = Some(widget);
+	}
+
+	/// Removes the shown tooltip from the window.
+	pub fn hide(&self) {
+		if let (Some(widget), Some(window)) = (&self.widget, self.root.get().as_window()) {
+			window.hide_overlay(widget);
+		}
+	}
+}
@@ -11,5 +11,6 @@ pub mod application;
 pub mod events;
 pub mod paint;
 pub mod platform;
+pub mod testing;
 pub mod util;
 pub mod widgets;
@@ -1,7 +1,7 @@
 mod painter;
 
 pub use painter::Painter;
-use skia_safe::{Font, Paint};
+use skia_safe::{scalar, textlayout::FontCollection, Color, Font, FontMgr, Paint};
 
 pub struct TextStyle {
 	pub font: Font,
@@ -16,3 +16,64 @@ impl Default for TextStyle {
 		}
 	}
 }
+
+/// A piece of text with its own style, used to build rich text.
+///
+/// Style attributes that are not set get inherited from the [TextStyle] of the widget showing the span.
+#[derive(Clone, Default)]
+pub struct TextSpan {
+	pub text: String,
+	pub bold: bool,
+	pub italic: bool,
+	pub underline: bool,
+	pub color: Option<Color>,
+	pub size: Option<scalar>,
+}
+
+impl TextSpan {
+	pub fn new(text: impl Into<String>) -> Self {
+		Self {
+			text: text.into(),
+			..Default::default()
+		}
+	}
+
+	pub fn bold(mut self) -> Self {
+		self.bold = true;
+		self
+	}
+
+	pub fn italic(mut self) -> Self {
+		self.italic = true;
+		self
+	}
+
+	pub fn underline(mut self) -> Self {
+		self.underline = true;
+		self
+	}
+
+	pub fn color(mut self, color: Color) -> Self {
+		self.color = Some(color);
+		self
+	}
+
+	pub fn size(mut self, size: scalar) -> Self {
+		self.size = Some(size);
+		self
+	}
+}
+
+thread_local! {
+	static FONT_COLLECTION: FontCollection = {
+		let mut collection = FontCollection::new();
+		collection.set_default_font_manager(FontMgr::new(), None);
+		collection
+	};
+}
+
+/// Returns the font collection used for text layout.
+/// It uses the system fonts and provides font fallback, e.g. for CJK or emoji.
+pub fn font_collection() -> FontCollection {
+	FONT_COLLECTION.with(|collection| collection.clone())
+}
@@ -1,5 +1,6 @@
 use std::cell::RefCell;
-use crate::{events::EventContext, util::WidgetRef, widgets::Window};
+use crate::{events::{input::CursorIcon, EventContext}, util::WidgetRef, widgets::{Widget, Window}};
+use skia_safe::Rect;
 use crate::platform::common::PlatformMessage;
 
 pub trait PlatformContext {
@@ -7,4 +8,17 @@ pub trait PlatformContext {
 	fn run(this: &RefCell<Self>, event_context: &RefCell<EventContext>) where Self: Sized;
 
 	fn set_capture_cursor(&mut self, cursor: usize, should_capture: bool);
+
+	/// Changes the shape of the cursor in the window it is over.
+	/// For [CursorIcon::Custom] the system cursor gets hidden, as the window paints the image.
+	fn set_cursor_icon(&mut self, cursor: usize, icon: &CursorIcon);
+
+	/// Returns the current text content of the clipboard, if there is any.
+	fn get_clipboard(&self) -> Option<String>;
+	/// Replaces the content of the clipboard with the given text.
+	fn set_clipboard(&mut self, text: String);
+
+	/// Tells the input method editor of the window containing the given widget
+	/// where the text cursor is (in window coordinates).
+	fn set_ime_cursor_area(&mut self, widget: &WidgetRef<dyn Widget>, area: Rect);
 }
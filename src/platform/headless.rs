@@ -0,0 +1,414 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Instant;
+use crate::{
+	events,
+	events::{
+		input::{CursorIcon, Modifiers, MouseButton, ScrollDelta, ScrollPhase, TouchForce},
+		DragPayload, DroppedFiles, EventContext, WidgetPath,
+	},
+	platform::common::{PlatformContext, PlatformMessage},
+	util::{scalar, Geometry, WidgetRef},
+	widgets::{validate_layout, Widget, Window},
+};
+use cgmath::Vector2;
+use winit::event::VirtualKeyCode;
+
+/// Identifies a window managed by the headless platform.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WindowId(u64);
+
+/// Holds information specific to a single in-memory window.
+/// Additionally contains a reference to the frameworks window representation
+/// and the bitmap the window gets rendered into.
+pub struct HeadlessWindow {
+	pub framework_window: WidgetRef<dyn Window>,
+	pub skia_data: (skia_safe::ImageInfo, skia_safe::Bitmap),
+	pub size: (u32, u32),
+}
+
+impl HeadlessWindow {
+	fn new(framework_window: WidgetRef<dyn Window>, size: (u32, u32)) -> Self {
+		let mut window = Self {
+			framework_window,
+			skia_data: (skia_safe::ImageInfo::default(), skia_safe::Bitmap::new()),
+			size: (0, 0),
+		};
+		window.resize(size);
+		window
+	}
+
+	fn geometry(&self) -> Geometry {
+		Geometry::new(
+			Vector2::new(0.0, 0.0),
+			Vector2::new(self.size.0 as scalar, self.size.1 as scalar),
+			Vector2::new(0.0, 0.0),
+			Vector2::new(1.0, 1.0),
+		)
+	}
+
+	fn resize(&mut self, size: (u32, u32)) {
+		if self.size == size {
+			return;
+		}
+		self.size = size;
+
+		let info = skia_safe::ImageInfo::new_n32_premul((size.0 as i32, size.1 as i32), None);
+		self.skia_data.1.reset();
+		self.skia_data.1.alloc_pixels_flags(&info);
+		self.skia_data.0 = info;
+
+		self.framework_window.get().arrange_children(self.geometry());
+		self.framework_window.get().invalidate_paint();
+	}
+
+	fn render(&mut self) {
+		validate_layout(&*self.framework_window.get());
+
+		let mut canvas = skia_safe::Canvas::from_bitmap(&self.skia_data.1, None);
+		self.framework_window.get().draw(
+			&mut canvas,
+			(self.size.0 as scalar, self.size.1 as scalar),
+		);
+	}
+}
+
+/// Input that can be injected into the headless platform to simulate a user.
+///
+/// Pointer input gets dispatched at the last position the given cursor moved to.
+pub enum HeadlessInput {
+	CursorMove {
+		window: WindowId,
+		cursor: usize,
+		pos: Vector2<scalar>,
+	},
+	CursorEnter {
+		cursor: usize,
+	},
+	CursorLeave {
+		cursor: usize,
+	},
+	/// The pointing device got disconnected.
+	CursorRemoved {
+		cursor: usize,
+	},
+	/// The platform lost the pointer, like a window losing the focus while a button is held.
+	CaptureLost {
+		cursor: usize,
+	},
+	MouseButton {
+		window: WindowId,
+		cursor: usize,
+		button: MouseButton,
+		pressed: bool,
+	},
+	Key {
+		window: WindowId,
+		keyboard: usize,
+		key_physical: usize,
+		key: Option<VirtualKeyCode>,
+		pressed: bool,
+	},
+	Text {
+		keyboard: usize,
+		character: char,
+	},
+	/// The modifier keys held down on the keyboard changed.
+	/// Modifier keys sent with [HeadlessInput::Key] do not change the modifiers on their own.
+	Modifiers {
+		keyboard: usize,
+		modifiers: Modifiers,
+	},
+	/// The keyboard got disconnected.
+	KeyboardRemoved {
+		keyboard: usize,
+	},
+	Scroll {
+		window: WindowId,
+		cursor: usize,
+		delta: ScrollDelta,
+		phase: ScrollPhase,
+	},
+	TouchDown {
+		window: WindowId,
+		finger: u64,
+		pos: Vector2<scalar>,
+		force: Option<TouchForce>,
+	},
+	TouchMove {
+		finger: u64,
+		pos: Vector2<scalar>,
+		force: Option<TouchForce>,
+	},
+	TouchUp {
+		finger: u64,
+		pos: Vector2<scalar>,
+	},
+	TouchCancel {
+		finger: u64,
+	},
+	/// Files from the OS get dragged at the last position of the cursor.
+	DragFiles {
+		window: WindowId,
+		cursor: usize,
+		files: Vec<PathBuf>,
+	},
+	/// Files from the OS get dropped at the last position of the cursor.
+	DropFiles {
+		window: WindowId,
+		cursor: usize,
+		files: Vec<PathBuf>,
+	},
+	/// The drag of the cursor got cancelled, like a drag of files leaving the window.
+	CancelDrag {
+		cursor: usize,
+	},
+	/// Lets time pass, sending all requested ticks that are due at the given time.
+	Tick {
+		time: Instant,
+	},
+}
+
+/// Platform context without any connection to a display server.
+///
+/// Windows only live in memory and get rendered into a Skia raster bitmap of the configured size.
+/// Because there are no OS events, [PlatformContext::run()] processes the pending messages,
+/// renders every window once and returns. Input has to be injected with [Context::inject_input()].
+pub struct Context {
+	windows: HashMap<WindowId, HeadlessWindow>,
+	deferred_messages: RefCell<Vec<PlatformMessage>>,
+	window_size: (u32, u32),
+	next_window_id: u64,
+
+	last_cursor_pos: HashMap<usize, Vector2<scalar>>,
+	clipboard: Option<String>,
+	ime_cursor_area: Option<skia_safe::Rect>,
+}
+
+impl Context {
+	/// Creates a new headless platform context, creating new windows with the given size
+	pub fn new(window_size: (u32, u32)) -> Self {
+		Self {
+			windows: Default::default(),
+			deferred_messages: Default::default(),
+			window_size,
+			next_window_id: 0,
+			last_cursor_pos: Default::default(),
+			clipboard: None,
+			ime_cursor_area: None,
+		}
+	}
+
+	/// Returns the size newly created windows get.
+	pub fn window_size(&self) -> (u32, u32) {
+		self.window_size
+	}
+
+	/// Changes the size newly created windows get.
+	pub fn set_window_size(&mut self, size: (u32, u32)) {
+		self.window_size = size;
+	}
+
+	/// Resizes an existing window and rearranges its widgets.
+	pub fn resize_window(&mut self, id: WindowId, size: (u32, u32)) {
+		if let Some(window) = self.windows.get_mut(&id) {
+			window.resize(size);
+		}
+	}
+
+	/// Returns the ids of all currently open windows.
+	pub fn window_ids(&self) -> Vec<WindowId> {
+		self.windows.keys().cloned().collect()
+	}
+
+	/// Returns the bitmap the given window got rendered into when it was last rendered.
+	pub fn window_bitmap(&self, id: WindowId) -> Option<&skia_safe::Bitmap> {
+		self.windows.get(&id).map(|window| &window.skia_data.1)
+	}
+
+	/// Returns the text cursor area last reported for the input method editor.
+	pub fn ime_cursor_area(&self) -> Option<skia_safe::Rect> {
+		self.ime_cursor_area
+	}
+
+	/// Renders all windows into their bitmaps.
+	pub fn render(&mut self) {
+		for window in self.windows.values_mut() {
+			window.render();
+		}
+	}
+
+	fn cursor_pos(&self, cursor: usize) -> Vector2<scalar> {
+		self.last_cursor_pos.get(&cursor).cloned().unwrap_or(Vector2::new(0.0, 0.0))
+	}
+
+	fn widget_path_under_position(&self, id: WindowId, pos: &Vector2<scalar>) -> Option<WidgetPath> {
+		let window = self.windows.get(&id)?;
+		Some(events::get_widget_path_under_position(
+			window.geometry(),
+			window.framework_window.clone(),
+			pos,
+		))
+	}
+
+	fn process_messages(&mut self) {
+		let messages = std::mem::take(&mut *self.deferred_messages.borrow_mut());
+		for message in messages {
+			match message {
+				PlatformMessage::NewWindow(window) => {
+					let id = WindowId(self.next_window_id);
+					self.next_window_id += 1;
+
+					window.get_mut().set_id(Some(id));
+
+					self.windows.insert(id, HeadlessWindow::new(window, self.window_size));
+				}
+				PlatformMessage::RemoveWindow(window) => {
+					let id = match window.get().id() {
+						Some(id) => id,
+						None => continue,
+					};
+					self.windows.remove(&id);
+				}
+			}
+		}
+	}
+
+	/// Dispatches the given simulated input to the event context like a real platform would.
+	pub fn inject_input(this: &RefCell<Self>, event_context: &RefCell<EventContext>, input: HeadlessInput) {
+		match input {
+			HeadlessInput::CursorMove { window, cursor, pos } => {
+				this.borrow_mut().last_cursor_pos.insert(cursor, pos);
+				let path = this.borrow().widget_path_under_position(window, &pos);
+				if let Some(path) = path {
+					event_context.borrow_mut().handle_mouse_move(&path, cursor, &pos);
+				}
+			}
+			HeadlessInput::CursorEnter { cursor } => {
+				event_context.borrow_mut().handle_cursor_enter(cursor);
+			}
+			HeadlessInput::CursorLeave { cursor } => {
+				event_context.borrow_mut().handle_cursor_leave(cursor);
+			}
+			HeadlessInput::CursorRemoved { cursor } => {
+				this.borrow_mut().last_cursor_pos.remove(&cursor);
+				event_context.borrow_mut().remove_cursor(cursor);
+			}
+			HeadlessInput::CaptureLost { cursor } => {
+				event_context.borrow_mut().handle_capture_lost(cursor);
+			}
+			HeadlessInput::MouseButton { window, cursor, button, pressed } => {
+				let pos = this.borrow().cursor_pos(cursor);
+				let path = this.borrow().widget_path_under_position(window, &pos);
+				if let Some(path) = path {
+					if pressed {
+						event_context.borrow_mut().handle_mouse_button_down(&path, cursor, button, &pos);
+					} else {
+						event_context.borrow_mut().handle_mouse_button_up(&path, cursor, button, &pos);
+					}
+				}
+			}
+			HeadlessInput::Key { window, keyboard, key_physical, key, pressed } => {
+				if pressed {
+					let root = this.borrow().windows.get(&window).map(|window| window.framework_window.clone());
+					if let Some(root) = root {
+						let root: WidgetRef<dyn Widget> = root;
+						event_context.borrow_mut().handle_key_down(&root, keyboard, key_physical, key);
+					}
+				} else {
+					event_context.borrow_mut().handle_key_up(keyboard, key_physical, key);
+				}
+			}
+			HeadlessInput::Text { keyboard, character } => {
+				event_context.borrow_mut().handle_text(keyboard, character);
+			}
+			HeadlessInput::Modifiers { keyboard, modifiers } => {
+				event_context.borrow_mut().handle_modifiers_changed(keyboard, modifiers);
+			}
+			HeadlessInput::KeyboardRemoved { keyboard } => {
+				event_context.borrow_mut().remove_keyboard(keyboard);
+			}
+			HeadlessInput::Scroll { window, cursor, delta, phase } => {
+				let pos = this.borrow().cursor_pos(cursor);
+				let path = this.borrow().widget_path_under_position(window, &pos);
+				if let Some(path) = path {
+					event_context.borrow_mut().handle_scroll(&path, cursor, delta, phase, &pos);
+				}
+			}
+			HeadlessInput::TouchDown { window, finger, pos, force } => {
+				let path = this.borrow().widget_path_under_position(window, &pos);
+				if let Some(path) = path {
+					event_context.borrow_mut().handle_touch_down(&path, finger, &pos, force);
+				}
+			}
+			HeadlessInput::TouchMove { finger, pos, force } => {
+				event_context.borrow_mut().handle_touch_move(finger, &pos, force);
+			}
+			HeadlessInput::TouchUp { finger, pos } => {
+				event_context.borrow_mut().handle_touch_up(finger, &pos);
+			}
+			HeadlessInput::TouchCancel { finger } => {
+				event_context.borrow_mut().handle_touch_cancel(finger);
+			}
+			HeadlessInput::DragFiles { window, cursor, files } => {
+				let pos = this.borrow().cursor_pos(cursor);
+				let path = this.borrow().widget_path_under_position(window, &pos);
+				if let Some(path) = path {
+					let mut event_context = event_context.borrow_mut();
+					event_context.start_drag(cursor, None, DragPayload::new(DroppedFiles(files)), None);
+					event_context.handle_drag_move(&path, cursor, &pos);
+				}
+			}
+			HeadlessInput::DropFiles { window, cursor, files } => {
+				let pos = this.borrow().cursor_pos(cursor);
+				let path = this.borrow().widget_path_under_position(window, &pos);
+				if let Some(path) = path {
+					let mut event_context = event_context.borrow_mut();
+					if !event_context.is_dragging(cursor) {
+						event_context.start_drag(cursor, None, DragPayload::new(DroppedFiles(files)), None);
+					}
+					event_context.handle_drop(&path, cursor, &pos);
+				}
+			}
+			HeadlessInput::CancelDrag { cursor } => {
+				event_context.borrow_mut().cancel_drag(cursor);
+			}
+			HeadlessInput::Tick { time } => {
+				event_context.borrow_mut().handle_tick(time);
+			}
+		}
+	}
+}
+
+impl PlatformContext for Context {
+	fn message(&self, message: PlatformMessage) {
+		self.deferred_messages.borrow_mut().push(message);
+	}
+
+	fn run(this: &RefCell<Self>, _event_context: &RefCell<EventContext>) {
+		let mut this = this.borrow_mut();
+		this.process_messages();
+		this.render();
+	}
+
+	fn set_capture_cursor(&mut self, _cursor: usize, _should_capture: bool) {}
+
+	fn set_cursor_icon(&mut self, _cursor: usize, _icon: &CursorIcon) {}
+
+	fn get_clipboard(&self) -> Option<String> {
+		self.clipboard.clone()
+	}
+
+	fn set_clipboard(&mut self, text: String) {
+		self.clipboard = Some(text);
+	}
+
+	fn set_ime_cursor_area(&mut self, _widget: &WidgetRef<dyn Widget>, area: skia_safe::Rect) {
+		self.ime_cursor_area = Some(area);
+	}
+}
+
+pub fn create_platform() -> Context {
+	Context::new((800, 600))
+}
@@ -1,14 +1,21 @@
 pub mod common;
 
-#[cfg(any(feature = "windows", feature = "wayland"))]
+// The headless backend replaces the windowing backends entirely, as they share the window id type
+// through the re-exports below. This keeps the feature additive to the default features.
+#[cfg(all(any(feature = "windows", feature = "wayland"), not(feature = "headless")))]
 pub mod winit;
 
-#[cfg(feature = "windows")]
+#[cfg(all(feature = "windows", not(feature = "headless")))]
 pub mod windows;
-#[cfg(feature = "windows")]
+#[cfg(all(feature = "windows", not(feature = "headless")))]
 pub use crate::platform::windows::*;
 
-#[cfg(feature = "wayland")]
+#[cfg(all(feature = "wayland", not(feature = "headless")))]
 pub mod wayland;
-#[cfg(feature = "wayland")]
+#[cfg(all(feature = "wayland", not(feature = "headless")))]
 pub use crate::platform::wayland::*;
+
+#[cfg(feature = "headless")]
+pub mod headless;
+#[cfg(feature = "headless")]
+pub use crate::platform::headless::*;
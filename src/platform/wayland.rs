@@ -1,5 +1,6 @@
 use crate::util::{SharedRef, WidgetRef};
 
+use copypasta::ClipboardProvider;
 use std::{cell::RefMut, fs::File, os::unix::prelude::AsRawFd};
 use wayland_client::{
 	protocol::{
@@ -135,6 +136,16 @@ impl WinitPlatformSpecifics for WaylandWinitSpecifics {
 			.unwrap();
 	}
 
+	fn create_clipboard(&mut self, event_loop: &EventLoop<()>) -> Box<dyn ClipboardProvider> {
+		// Wayland only hands the clipboard to focused clients, so it has to share winit's connection
+		let display = event_loop
+			.wayland_display()
+			.expect("Wayland platform used without a Wayland display");
+		let (_primary, clipboard) =
+			unsafe { copypasta::wayland_clipboard::create_clipboards_from_external(display) };
+		Box::new(clipboard)
+	}
+
 	fn resize_buffer(&mut self, window: SharedRef<Window<Self::WindowSpecificData>>) {
 		let mut window = window.get_mut();
 		let (mut data, mut window) = RefMut::map_split(window, |w| {
@@ -144,14 +155,13 @@ impl WinitPlatformSpecifics for WaylandWinitSpecifics {
 		data.resize_buffer(&mut window);
 	}
 
-	fn flush_window_buffer(&mut self, window: SharedRef<Window<Self::WindowSpecificData>>) {
+	fn flush_window_buffer(&mut self, window: SharedRef<Window<Self::WindowSpecificData>>, damage: &[skia_safe::IRect]) {
 		let (mut specific_data, mut winit_window) = RefMut::map_split(window.get_mut(), |w| {
 			(&mut w.platform_specific_data, &mut w.winit_window)
 		});
 		specific_data.resize_buffer(&mut winit_window);
 		drop((specific_data, winit_window));
 		let window = window.get_mut();
-		let size = window.size;
 		let (skia_data, mut specific_data) = RefMut::map_split(window, |w| {
 			(&mut w.skia_data, &mut w.platform_specific_data)
 		});
@@ -164,15 +174,25 @@ impl WinitPlatformSpecifics for WaylandWinitSpecifics {
 			.unwrap()
 			.copy_from_slice(data.unwrap());
 
-		specific_data.wayland_surface.commit();
 		specific_data.wayland_surface.attach(
 			Some(specific_data.wayland_buffer.as_ref().unwrap()),
 			0,
 			0,
 		);
-		specific_data
-			.wayland_surface
-			.damage(0, 0, size.width as i32, size.height as i32);
+		// damage_buffer is only available since version 4 of wl_surface
+		let damage_buffer = specific_data.wayland_surface.as_ref().version() >= 4;
+		for rect in damage {
+			if damage_buffer {
+				specific_data
+					.wayland_surface
+					.damage_buffer(rect.left, rect.top, rect.width(), rect.height());
+			} else {
+				specific_data
+					.wayland_surface
+					.damage(rect.left, rect.top, rect.width(), rect.height());
+			}
+		}
+		specific_data.wayland_surface.commit();
 		specific_data
 			.wayland_event_queue
 			.sync_roundtrip(&mut (), |_, _, _| {})
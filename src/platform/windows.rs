@@ -1,5 +1,6 @@
 pub use crate::platform::winit::*;
 use crate::{platform::winit::WinitPlatformSpecifics, util::WidgetRef};
+use copypasta::{ClipboardContext, ClipboardProvider};
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle, Win32Handle};
 use std::cmp::max;
 use windows::Win32::Graphics::Gdi::{
@@ -85,6 +86,10 @@ impl WinitPlatformSpecifics for WindowsWinitSpecifics {
 	) {
 	}
 
+	fn create_clipboard(&mut self, _event_loop: &EventLoop<()>) -> Box<dyn ClipboardProvider> {
+		Box::new(ClipboardContext::new().expect("Unable to access the Windows clipboard"))
+	}
+
 	fn resize_buffer(
 		&mut self,
 		window: SharedRef<crate::platform::winit::Window<Self::WindowSpecificData>>,
@@ -95,6 +100,7 @@ impl WinitPlatformSpecifics for WindowsWinitSpecifics {
 	fn flush_window_buffer(
 		&mut self,
 		window: SharedRef<crate::platform::winit::Window<Self::WindowSpecificData>>,
+		_damage: &[skia_safe::IRect],
 	) {
 		let mut window = window.get_mut();
 		let hwnd = window.platform_specific_data.hwnd;
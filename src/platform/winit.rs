@@ -1,18 +1,31 @@
 use std::cell::RefCell;
-use crate::{events, events::EventContext, platform::common::PlatformContext, util::*};
+use crate::{
+	events,
+	events::{
+		input::{CursorIcon, ImeEvent, Modifiers, ScrollDelta, ScrollPhase, TouchForce},
+		DragPayload, DroppedFiles, EventContext,
+	},
+	platform::common::PlatformContext,
+	util::*,
+};
 use cgmath::Vector2;
+use copypasta::ClipboardProvider;
 
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use std::time::Instant;
 use winit::{
-	dpi::PhysicalSize,
-	event::{ElementState, Event, WindowEvent},
+	dpi::{PhysicalPosition, PhysicalSize},
+	event::{DeviceEvent, DeviceId, ElementState, Event, Force, Ime, ModifiersState, MouseScrollDelta, Touch, TouchPhase, WindowEvent},
 	event_loop::{ControlFlow, EventLoop},
 	platform::run_return::EventLoopExtRunReturn,
+	window::CursorGrabMode,
 };
 use winit::event_loop::EventLoopWindowTarget;
 use crate::application::GUIApplication;
 use crate::platform::common::PlatformMessage;
+use crate::widgets::{validate_layout, Widget};
 
 pub type WindowId = winit::window::WindowId;
 
@@ -28,8 +41,48 @@ pub trait WinitPlatformSpecifics {
 		event_loop: &mut EventLoop<()>,
 	) -> Self::WindowSpecificData;
 	fn remove_window(&mut self, window: SharedRef<Window<Self::WindowSpecificData>>);
+	/// Connects to the clipboard of the OS, gets called once when the first window got created.
+	fn create_clipboard(&mut self, event_loop: &EventLoop<()>) -> Box<dyn ClipboardProvider>;
 	fn resize_buffer(&mut self, window: SharedRef<Window<Self::WindowSpecificData>>);
-	fn flush_window_buffer(&mut self, window: SharedRef<Window<Self::WindowSpecificData>>);
+	/// Presents the draw buffer of the window.
+	/// Only the given damaged areas changed since the last flush.
+	fn flush_window_buffer(&mut self, window: SharedRef<Window<Self::WindowSpecificData>>, damage: &[skia_safe::IRect]);
+}
+
+/// Maps winit device ids to the stable indices used by the event context.
+///
+/// New devices get the lowest free index, so with a single mouse and keyboard both have index `0`.
+///
+/// # Limitations
+/// Separate cursors and keyboards are not supported on Linux, neither on Wayland nor on X11.
+/// The indices can only be as fine grained as the device ids winit reports with window events.
+/// On Wayland all of them carry the same device id and on X11 key events come from the
+/// virtual core keyboard, so on Linux all pointers share cursor `0` and all keyboards share keyboard `0`.
+/// Telling the seats of a Wayland compositor apart would need access to the `wl_seat` objects,
+/// which winit does not expose.
+/// Devices get registered with their first input instead of [DeviceEvent::Added],
+/// as that event does not tell whether the device is a pointer or a keyboard.
+#[derive(Default)]
+struct DeviceIndices {
+	indices: HashMap<DeviceId, usize>,
+}
+
+impl DeviceIndices {
+	/// Returns the index of the given device and if it got newly assigned.
+	fn get_or_assign(&mut self, device_id: DeviceId) -> (usize, bool) {
+		if let Some(index) = self.indices.get(&device_id) {
+			return (*index, false);
+		}
+		let index = (0..)
+			.find(|index| !self.indices.values().any(|used| used == index))
+			.unwrap();
+		self.indices.insert(device_id, index);
+		(index, true)
+	}
+
+	fn remove(&mut self, device_id: DeviceId) -> Option<usize> {
+		self.indices.remove(&device_id)
+	}
 }
 
 /// Holds information specific to a single window in the context of winit.
@@ -55,7 +108,25 @@ pub struct Context<PS: WinitPlatformSpecifics> {
 	windows: HashMap<WindowId, SharedRef<Window<PS::WindowSpecificData>>>,
 	deferred_messages: RefCell<Vec<PlatformMessage>>,
 
-	last_cursor_pos: Vector2<scalar>,
+	cursor_devices: DeviceIndices,
+	keyboard_devices: DeviceIndices,
+	last_cursor_pos: HashMap<usize, Vector2<scalar>>,
+	/// The window each cursor was over last, captures grab the cursor in that window.
+	cursor_windows: HashMap<usize, WindowId>,
+	captured_cursors: HashMap<usize, WindowId>,
+	/// winit does not tell which keyboard text comes from,
+	/// so it gets attributed to the keyboard that last pressed a key.
+	last_keyboard: usize,
+	/// winit does not tell which cursor drags files in from the OS,
+	/// so they get attributed to the cursor that moved last.
+	last_cursor: usize,
+	/// Files hovered or dropped since the last flush, winit sends one event per file.
+	hovered_files: Vec<PathBuf>,
+	dropped_files: Vec<PathBuf>,
+	file_drag_window: Option<WindowId>,
+	/// winit has no clipboard support, so the clipboard of the OS gets accessed through
+	/// the platform specifics once the first window exists.
+	clipboard: RefCell<Option<Box<dyn ClipboardProvider>>>,
 }
 
 impl<PS> Context<PS>
@@ -68,8 +139,42 @@ where
 			platform_specifics,
 			windows: Default::default(),
 			deferred_messages: Default::default(),
-			last_cursor_pos: Vector2::new(0.0, 0.0),
+			cursor_devices: Default::default(),
+			keyboard_devices: Default::default(),
+			last_cursor_pos: Default::default(),
+			cursor_windows: Default::default(),
+			captured_cursors: Default::default(),
+			last_keyboard: 0,
+			last_cursor: 0,
+			hovered_files: Vec::new(),
+			dropped_files: Vec::new(),
+			file_drag_window: None,
+			clipboard: RefCell::new(None),
+		}
+	}
+
+	/// Returns the cursor index of the given pointing device,
+	/// registering the device in the event context if it is new.
+	fn cursor_index(this: &RefCell<Self>, device_id: DeviceId, event_context: &RefCell<EventContext>) -> usize {
+		let (index, is_new) = this.borrow_mut().cursor_devices.get_or_assign(device_id);
+		if is_new {
+			event_context.borrow_mut().add_cursor(index);
+		}
+		index
+	}
+
+	/// Returns the keyboard index of the given keyboard,
+	/// registering the device in the event context if it is new.
+	fn keyboard_index(this: &RefCell<Self>, device_id: DeviceId, event_context: &RefCell<EventContext>) -> usize {
+		let (index, is_new) = this.borrow_mut().keyboard_devices.get_or_assign(device_id);
+		if is_new {
+			event_context.borrow_mut().add_keyboard(index);
 		}
+		index
+	}
+
+	fn cursor_pos(&self, cursor: usize) -> Vector2<scalar> {
+		self.last_cursor_pos.get(&cursor).cloned().unwrap_or(Vector2::new(0.0, 0.0))
 	}
 
 	fn window_by_id(&self, id: WindowId) -> Option<SharedRef<Window<PS::WindowSpecificData>>> {
@@ -112,7 +217,9 @@ where
 			Vector2::new(0.0, 0.0),
 			Vector2::new(1.0, 1.0),
 		);
-		window.get().framework_window.get().deref().arrange_children(geometry);
+		let framework_window = window.get().framework_window.clone();
+		framework_window.get().arrange_children(geometry);
+		framework_window.get().invalidate_paint();
 	}
 
 	fn handle_window_event(this: &RefCell<Self>, event: WindowEvent, window: SharedRef<Window<PS::WindowSpecificData>>, window_target: &EventLoopWindowTarget<()>, control_flow: &mut ControlFlow, event_context: &RefCell<EventContext>) {
@@ -124,12 +231,15 @@ where
 				window.get().winit_window.request_redraw();
 			}
 			WindowEvent::CursorMoved {
-					device_id: _,
+					device_id,
 					position,
 					modifiers: _,
 			} => {
+				let cursor = Self::cursor_index(this, device_id, event_context);
 				let pos = Vector2::new(position.x as f32, position.y as f32);
-				this.borrow_mut().last_cursor_pos = pos;
+				this.borrow_mut().last_cursor_pos.insert(cursor, pos);
+				this.borrow_mut().last_cursor = cursor;
+				this.borrow_mut().cursor_windows.insert(cursor, window.get().winit_window.id());
 
 				let size = window.get().winit_window.inner_size();
 				let geometry = Geometry::new(
@@ -139,100 +249,253 @@ where
 					Vector2::new(1.0, 1.0),
 				);
 
-				// TODO: Add multi device support
 				let path = events::get_widget_path_under_position(
 					geometry,
 					window.get().framework_window.clone(),
 					&pos,
 				);
-				event_context.borrow_mut().handle_mouse_move(&path, 0, &this.borrow().last_cursor_pos);
-
-				window.get().winit_window.request_redraw();
+				event_context.borrow_mut().handle_mouse_move(&path, cursor, &pos);
 			}
 			WindowEvent::MouseInput {
-				device_id: _,
+				device_id,
 				button,
 				state,
 				modifiers: _,
 			} => {
-				if button == winit::event::MouseButton::Left {
-					let size = window.get().winit_window.inner_size();
-					let geometry = Geometry::new(
-						Vector2::new(0.0, 0.0),
-						Vector2::new(size.width as scalar, size.height as scalar),
-						Vector2::new(0.0, 0.0),
-						Vector2::new(1.0, 1.0),
-					);
-
-					let path = events::get_widget_path_under_position(
-						geometry,
-						window.get().framework_window.clone(),
-						&this.borrow().last_cursor_pos,
-					);
-					let pos = this.borrow().last_cursor_pos.clone();
-					match state {
-						ElementState::Pressed => event_context.borrow_mut()
-							.handle_mouse_button_down(
-								&path,
-								0,
-								conv_mouse_button(button),
-								&pos,
-							),
-						ElementState::Released => event_context.borrow_mut()
-							.handle_mouse_button_up(
-								&path,
-								0,
-								conv_mouse_button(button),
-								&pos,
-							),
-					}
+				let cursor = Self::cursor_index(this, device_id, event_context);
+				let size = window.get().winit_window.inner_size();
+				let geometry = Geometry::new(
+					Vector2::new(0.0, 0.0),
+					Vector2::new(size.width as scalar, size.height as scalar),
+					Vector2::new(0.0, 0.0),
+					Vector2::new(1.0, 1.0),
+				);
 
-					window.get().winit_window.request_redraw();
+				let pos = this.borrow().cursor_pos(cursor);
+				let path = events::get_widget_path_under_position(
+					geometry,
+					window.get().framework_window.clone(),
+					&pos,
+				);
+				match state {
+					ElementState::Pressed => event_context.borrow_mut()
+						.handle_mouse_button_down(
+							&path,
+							cursor,
+							conv_mouse_button(button),
+							&pos,
+						),
+					ElementState::Released => event_context.borrow_mut()
+						.handle_mouse_button_up(
+							&path,
+							cursor,
+							conv_mouse_button(button),
+							&pos,
+						),
 				}
 			}
+			WindowEvent::MouseWheel {
+				device_id,
+				delta,
+				phase,
+				..
+			} => {
+				let cursor = Self::cursor_index(this, device_id, event_context);
+				let size = window.get().winit_window.inner_size();
+				let geometry = Geometry::new(
+					Vector2::new(0.0, 0.0),
+					Vector2::new(size.width as scalar, size.height as scalar),
+					Vector2::new(0.0, 0.0),
+					Vector2::new(1.0, 1.0),
+				);
+
+				let pos = this.borrow().cursor_pos(cursor);
+				let path = events::get_widget_path_under_position(
+					geometry,
+					window.get().framework_window.clone(),
+					&pos,
+				);
+				let delta = match delta {
+					MouseScrollDelta::LineDelta(x, y) => ScrollDelta::Lines(Vector2::new(x, y)),
+					MouseScrollDelta::PixelDelta(delta) => {
+						ScrollDelta::Pixels(Vector2::new(delta.x as scalar, delta.y as scalar))
+					}
+				};
+				let phase = match phase {
+					TouchPhase::Started => ScrollPhase::Started,
+					TouchPhase::Moved => ScrollPhase::Moved,
+					TouchPhase::Ended => ScrollPhase::Ended,
+					TouchPhase::Cancelled => ScrollPhase::Cancelled,
+				};
+				event_context.borrow_mut().handle_scroll(&path, cursor, delta, phase, &pos);
+			}
 			WindowEvent::KeyboardInput {
-				device_id: _,
+				device_id,
 				input,
 				is_synthetic: _,
 			} => {
+				let keyboard = Self::keyboard_index(this, device_id, event_context);
+				this.borrow_mut().last_keyboard = keyboard;
 				match input.state {
-					// TODO: Add multi device support
 					ElementState::Pressed => {
+						let root: WidgetRef<dyn Widget> = window.get().framework_window.clone();
 						event_context.borrow_mut().handle_key_down(
-							0,
+							&root,
+							keyboard,
 							input.scancode as usize,
 							input.virtual_keycode,
 						);
 					}
 					ElementState::Released => {
 						event_context.borrow_mut().handle_key_up(
-							0,
+							keyboard,
 							input.scancode as usize,
 							input.virtual_keycode,
 						);
 					}
 				}
-				window.get().winit_window.request_redraw();
+			}
+			WindowEvent::ModifiersChanged(modifiers) => {
+				// winit does not tell which keyboard changed the modifiers
+				let keyboard = this.borrow().last_keyboard;
+				event_context.borrow_mut().handle_modifiers_changed(keyboard, conv_modifiers(modifiers));
+			}
+			WindowEvent::Ime(ime) => {
+				let ime = match ime {
+					Ime::Enabled => ImeEvent::Enabled,
+					Ime::Preedit(text, cursor) => ImeEvent::Preedit(text, cursor),
+					Ime::Commit(text) => ImeEvent::Commit(text),
+					Ime::Disabled => ImeEvent::Disabled,
+				};
+				let keyboard = this.borrow().last_keyboard;
+				event_context.borrow_mut().handle_ime(keyboard, ime);
 			}
 			WindowEvent::ReceivedCharacter(char) => {
-				// TODO: Add multi device support
-				event_context.borrow_mut().handle_text(0, char);
-				window.get().winit_window.request_redraw();
+				let keyboard = this.borrow().last_keyboard;
+				event_context.borrow_mut().handle_text(keyboard, char);
 			}
-			WindowEvent::CursorLeft { device_id: _ } => {
-				// TODO: Add multi device support
-				event_context.borrow_mut().handle_cursor_leave(0);
-				window.get().winit_window.request_redraw();
+			WindowEvent::CursorLeft { device_id } => {
+				let cursor = Self::cursor_index(this, device_id, event_context);
+				event_context.borrow_mut().handle_cursor_leave(cursor);
 			}
-			WindowEvent::CursorEntered { device_id: _ } => {
-				// TODO: Add multi device support
-				event_context.borrow_mut().handle_cursor_enter(0);
-				window.get().winit_window.request_redraw();
+			WindowEvent::CursorEntered { device_id } => {
+				let cursor = Self::cursor_index(this, device_id, event_context);
+				this.borrow_mut().cursor_windows.insert(cursor, window.get().winit_window.id());
+				event_context.borrow_mut().handle_cursor_enter(cursor);
+			}
+			WindowEvent::Touch(Touch {
+				phase,
+				location,
+				force,
+				id,
+				..
+			}) => {
+				let pos = Vector2::new(location.x as f32, location.y as f32);
+				let force = force.map(conv_force);
+				match phase {
+					TouchPhase::Started => {
+						let size = window.get().winit_window.inner_size();
+						let geometry = Geometry::new(
+							Vector2::new(0.0, 0.0),
+							Vector2::new(size.width as scalar, size.height as scalar),
+							Vector2::new(0.0, 0.0),
+							Vector2::new(1.0, 1.0),
+						);
+
+						let path = events::get_widget_path_under_position(
+							geometry,
+							window.get().framework_window.clone(),
+							&pos,
+						);
+						event_context.borrow_mut().handle_touch_down(&path, id, &pos, force);
+					}
+					TouchPhase::Moved => event_context.borrow_mut().handle_touch_move(id, &pos, force),
+					TouchPhase::Ended => event_context.borrow_mut().handle_touch_up(id, &pos),
+					TouchPhase::Cancelled => event_context.borrow_mut().handle_touch_cancel(id),
+				}
+			}
+			WindowEvent::Focused(false) => {
+				// the button releases may never arrive, so the captures of the window end now
+				let id = window.get().winit_window.id();
+				let cursors: Vec<usize> = this
+					.borrow()
+					.captured_cursors
+					.iter()
+					.filter(|(_, window)| **window == id)
+					.map(|(cursor, _)| *cursor)
+					.collect();
+				for cursor in cursors {
+					event_context.borrow_mut().handle_capture_lost(cursor);
+				}
+			}
+			WindowEvent::HoveredFile(file) => {
+				let mut this = this.borrow_mut();
+				this.hovered_files.push(file);
+				this.file_drag_window = Some(window.get().winit_window.id());
+			}
+			WindowEvent::DroppedFile(file) => {
+				let mut this = this.borrow_mut();
+				this.dropped_files.push(file);
+				this.file_drag_window = Some(window.get().winit_window.id());
+			}
+			WindowEvent::HoveredFileCancelled => {
+				let cursor = this.borrow().last_cursor;
+				this.borrow_mut().hovered_files.clear();
+				event_context.borrow_mut().cancel_drag(cursor);
 			}
 			_ => (),
 		}
 	}
 
+	/// Turns the files hovered and dropped since the last call into a drag of [DroppedFiles],
+	/// as winit sends the files one by one and without a position.
+	fn flush_file_drag(this: &RefCell<Self>, event_context: &RefCell<EventContext>) {
+		let (hovered, dropped, window, cursor) = {
+			let mut this = this.borrow_mut();
+			(
+				std::mem::take(&mut this.hovered_files),
+				std::mem::take(&mut this.dropped_files),
+				this.file_drag_window,
+				this.last_cursor,
+			)
+		};
+		if hovered.is_empty() && dropped.is_empty() {
+			return;
+		}
+		let window = match window.and_then(|id| this.borrow().window_by_id(id)) {
+			Some(window) => window,
+			None => return,
+		};
+
+		let size = window.get().winit_window.inner_size();
+		let geometry = Geometry::new(
+			Vector2::new(0.0, 0.0),
+			Vector2::new(size.width as scalar, size.height as scalar),
+			Vector2::new(0.0, 0.0),
+			Vector2::new(1.0, 1.0),
+		);
+		let pos = this.borrow().cursor_pos(cursor);
+		let path = events::get_widget_path_under_position(
+			geometry,
+			window.get().framework_window.clone(),
+			&pos,
+		);
+
+		let mut event_context = event_context.borrow_mut();
+		if !hovered.is_empty() {
+			event_context.start_drag(cursor, None, DragPayload::new(DroppedFiles(hovered)), None);
+			event_context.handle_drag_move(&path, cursor, &pos);
+		}
+		if !dropped.is_empty() {
+			// not every platform reports hovering before the drop
+			if !event_context.is_dragging(cursor) {
+				event_context.start_drag(cursor, None, DragPayload::new(DroppedFiles(dropped)), None);
+			}
+			event_context.handle_drop(&path, cursor, &pos);
+			this.borrow_mut().file_drag_window = None;
+		}
+	}
+
 	fn handle_event(this: &RefCell<Self>, event: Event<()>, window_target: &EventLoopWindowTarget<()>, control_flow: &mut ControlFlow, event_context: &RefCell<EventContext>) {
 		*control_flow = ControlFlow::Wait;
 
@@ -246,10 +509,26 @@ where
 					Self::handle_window_event(this, event, window, window_target, control_flow, event_context);
 				}
 			}
+			Event::DeviceEvent {
+				device_id,
+				event: DeviceEvent::Removed,
+			} => {
+				let cursor = this.borrow_mut().cursor_devices.remove(device_id);
+				if let Some(cursor) = cursor {
+					this.borrow_mut().last_cursor_pos.remove(&cursor);
+					this.borrow_mut().cursor_windows.remove(&cursor);
+					event_context.borrow_mut().remove_cursor(cursor);
+				}
+				let keyboard = this.borrow_mut().keyboard_devices.remove(device_id);
+				if let Some(keyboard) = keyboard {
+					event_context.borrow_mut().remove_keyboard(keyboard);
+				}
+			}
 			Event::RedrawRequested(window_id) => {
 				let window = this.borrow().window_by_id(window_id);
 				if let Some(window) = window {
 					this.borrow_mut().resize_buffer(window.clone());
+					validate_layout(&*window.get().framework_window.get());
 
 					let size = window.get().winit_window.inner_size();
 					let window_ref = window.get();
@@ -259,14 +538,28 @@ where
 						None,
 					);
 
-					window_ref.framework_window.get().draw(
+					let damage = window_ref.framework_window.get().draw(
 						&mut canvas,
 						(size.width as scalar, size.height as scalar),
 					);
 
 					drop(window_ref);
 
-					this.borrow_mut().platform_specifics.flush_window_buffer(window.clone());
+					if !damage.is_empty() {
+						this.borrow_mut().platform_specifics.flush_window_buffer(window.clone(), &damage);
+					}
+				}
+			}
+			Event::MainEventsCleared => {
+				Self::flush_file_drag(this, event_context);
+				event_context.borrow_mut().handle_tick(Instant::now());
+
+				// only redraw windows that got invalidated while handling the events
+				for window in this.borrow().windows.values() {
+					let window = window.get();
+					if window.framework_window.get().widget_state().paint_dirty {
+						window.winit_window.request_redraw();
+					}
 				}
 			}
 			Event::RedrawEventsCleared => {
@@ -279,6 +572,11 @@ where
 			_ => ()
 		}
 
+		// wake up for the next requested tick
+		if let Some(tick) = event_context.borrow().next_tick() {
+			*control_flow = ControlFlow::WaitUntil(tick);
+		}
+
 		// End Event loop if no windows are left or if there are messages to handle
 		if this.borrow().windows.len() < 1 || this.borrow().deferred_messages.borrow().len() > 0 {
 			*control_flow = ControlFlow::Exit;
@@ -313,6 +611,7 @@ where
 							.with_transparent(true)
 							.build(&event_loop)
 							.unwrap();
+						winit_window.set_ime_allowed(true);
 
 						let id: WindowId = winit_window.id();
 
@@ -334,6 +633,11 @@ where
 
 						this.borrow_mut().resize_buffer(window.clone());
 
+						if this.borrow().clipboard.borrow().is_none() {
+							let clipboard = this.borrow_mut().platform_specifics.create_clipboard(&event_loop);
+							*this.borrow().clipboard.borrow_mut() = Some(clipboard);
+						}
+
 						this.borrow_mut().windows.insert(id, window);
 					}
 					PlatformMessage::RemoveWindow(window) => {
@@ -346,13 +650,91 @@ where
 							None => return,
 						};
 						this.borrow_mut().platform_specifics.remove_window(window);
+						let cursors: Vec<usize> = this
+							.borrow()
+							.captured_cursors
+							.iter()
+							.filter(|(_, window)| **window == id)
+							.map(|(cursor, _)| *cursor)
+							.collect();
+						for cursor in cursors {
+							event_context.borrow_mut().handle_capture_lost(cursor);
+						}
 					}
 				}
 			}
 		}
 	}
 
-	fn set_capture_cursor(&mut self, _cursor: usize, _should_capture: bool) {}
+	/// Confines the pointer to the window it is over, so the capturing widget keeps receiving its movement.
+	/// winit implements the confinement with the pointer constraints protocol on Wayland.
+	fn set_capture_cursor(&mut self, cursor: usize, should_capture: bool) {
+		let id = if should_capture {
+			match self.cursor_windows.get(&cursor) {
+				Some(id) => *id,
+				None => return,
+			}
+		} else {
+			match self.captured_cursors.remove(&cursor) {
+				Some(id) => id,
+				None => return,
+			}
+		};
+		let window = match self.window_by_id(id) {
+			Some(window) => window,
+			None => return,
+		};
+		let mode = if should_capture {
+			self.captured_cursors.insert(cursor, id);
+			CursorGrabMode::Confined
+		} else if self.captured_cursors.values().any(|window| *window == id) {
+			// another cursor still captures the window
+			return;
+		} else {
+			CursorGrabMode::None
+		};
+		// not every platform can confine the pointer, the capture still works within the window
+		let _ = window.get().winit_window.set_cursor_grab(mode);
+	}
+
+	/// winit can not use images as cursor, so custom icons hide the cursor and get painted by the window.
+	fn set_cursor_icon(&mut self, cursor: usize, icon: &CursorIcon) {
+		let window = match self.cursor_windows.get(&cursor).and_then(|id| self.window_by_id(*id)) {
+			Some(window) => window,
+			None => return,
+		};
+		let window = window.get();
+		match conv_cursor_icon(icon) {
+			Some(icon) => {
+				window.winit_window.set_cursor_icon(icon);
+				window.winit_window.set_cursor_visible(true);
+			}
+			None => window.winit_window.set_cursor_visible(false),
+		}
+	}
+
+	fn get_clipboard(&self) -> Option<String> {
+		let mut clipboard = self.clipboard.borrow_mut();
+		clipboard.as_mut()?.get_contents().ok().filter(|text| !text.is_empty())
+	}
+
+	fn set_clipboard(&mut self, text: String) {
+		if let Some(clipboard) = self.clipboard.get_mut() {
+			// there is nothing sensible to do if the OS refuses the content
+			let _ = clipboard.set_contents(text);
+		}
+	}
+
+	fn set_ime_cursor_area(&mut self, widget: &WidgetRef<dyn Widget>, area: skia_safe::Rect) {
+		for window in self.windows.values() {
+			let window = window.get();
+			let framework_window: WidgetRef<dyn Widget> = window.framework_window.clone();
+			if &framework_window == widget {
+				// the candidate window gets placed below the cursor
+				window.winit_window.set_ime_position(PhysicalPosition::new(area.left as f64, area.bottom as f64));
+			}
+		}
+	}
 }
 
 pub fn conv_mouse_button(btn: winit::event::MouseButton) -> events::input::MouseButton {
@@ -363,3 +745,43 @@ pub fn conv_mouse_button(btn: winit::event::MouseButton) -> events::input::Mouse
 		winit::event::MouseButton::Other(c) => events::input::MouseButton::Other(c),
 	}
 }
+
+pub fn conv_force(force: Force) -> TouchForce {
+	let altitude_angle = match force {
+		Force::Calibrated { altitude_angle, .. } => altitude_angle.map(|angle| angle as scalar),
+		Force::Normalized(_) => None,
+	};
+	TouchForce {
+		pressure: force.normalized() as scalar,
+		altitude_angle,
+	}
+}
+
+/// Returns None for [CursorIcon::Custom], as winit has no equivalent.
+pub fn conv_cursor_icon(icon: &CursorIcon) -> Option<winit::window::CursorIcon> {
+	Some(match icon {
+		CursorIcon::Default => winit::window::CursorIcon::Default,
+		CursorIcon::Text => winit::window::CursorIcon::Text,
+		CursorIcon::Hand => winit::window::CursorIcon::Hand,
+		CursorIcon::Grab => winit::window::CursorIcon::Grab,
+		CursorIcon::Grabbing => winit::window::CursorIcon::Grabbing,
+		CursorIcon::Move => winit::window::CursorIcon::Move,
+		CursorIcon::Crosshair => winit::window::CursorIcon::Crosshair,
+		CursorIcon::Wait => winit::window::CursorIcon::Wait,
+		CursorIcon::NotAllowed => winit::window::CursorIcon::NotAllowed,
+		CursorIcon::ResizeHorizontal => winit::window::CursorIcon::EwResize,
+		CursorIcon::ResizeVertical => winit::window::CursorIcon::NsResize,
+		CursorIcon::ResizeDiagonal => winit::window::CursorIcon::NwseResize,
+		CursorIcon::ResizeAntiDiagonal => winit::window::CursorIcon::NeswResize,
+		CursorIcon::Custom { .. } => return None,
+	})
+}
+
+pub fn conv_modifiers(modifiers: ModifiersState) -> Modifiers {
+	let mut result = Modifiers::empty();
+	result.set(Modifiers::SHIFT, modifiers.shift());
+	result.set(Modifiers::CTRL, modifiers.ctrl());
+	result.set(Modifiers::ALT, modifiers.alt());
+	result.set(Modifiers::SUPER, modifiers.logo());
+	result
+}
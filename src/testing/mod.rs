@@ -0,0 +1,502 @@
+use std::{
+	fmt::{Display, Formatter},
+	path::{Path, PathBuf},
+};
+#[cfg(feature = "headless")]
+use std::{
+	panic::AssertUnwindSafe,
+	sync::{mpsc, LazyLock, Mutex},
+};
+#[cfg(feature = "headless")]
+use crate::application::GUIApplication;
+use crate::{
+	util::{Geometry, WidgetRef},
+	widgets::Widget,
+};
+use cgmath::Vector2;
+use skia_safe::{
+	image::CachingHint, AlphaType, Color, ColorType, Data, EncodedImageFormat, Image, ImageInfo,
+	Surface,
+};
+
+/// Name of the environment variable that, if set, makes snapshot checks overwrite the stored
+/// golden images with the newly rendered ones instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "RUITACHI_UPDATE_SNAPSHOTS";
+
+/// Describes why a snapshot check failed.
+#[derive(Debug)]
+pub enum SnapshotError {
+	Io(PathBuf, std::io::Error),
+	/// There is no stored golden image yet.
+	/// Run the check with [UPDATE_SNAPSHOTS_ENV] set to create it.
+	Missing(PathBuf),
+	Decode(PathBuf),
+	Encode,
+	SizeMismatch {
+		expected: (i32, i32),
+		actual: (i32, i32),
+	},
+	/// Some pixels differ more than the tolerance allows.
+	/// The diff image marks all those pixels in red.
+	PixelMismatch {
+		mismatched_pixels: usize,
+		diff_path: PathBuf,
+	},
+}
+
+impl Display for SnapshotError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SnapshotError::Io(path, err) => write!(f, "failed to access '{}': {}", path.display(), err),
+			SnapshotError::Missing(path) => write!(
+				f,
+				"snapshot '{}' does not exist, set {} to create it",
+				path.display(),
+				UPDATE_SNAPSHOTS_ENV
+			),
+			SnapshotError::Decode(path) => write!(f, "failed to decode snapshot '{}'", path.display()),
+			SnapshotError::Encode => write!(f, "failed to encode rendered image as PNG"),
+			SnapshotError::SizeMismatch { expected, actual } => write!(
+				f,
+				"snapshot size mismatch, expected {:?} but rendered {:?}",
+				expected, actual
+			),
+			SnapshotError::PixelMismatch {
+				mismatched_pixels,
+				diff_path,
+			} => write!(
+				f,
+				"{} pixels differ from the snapshot, see '{}'",
+				mismatched_pixels,
+				diff_path.display()
+			),
+		}
+	}
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Arranges the given widget with the given geometry and paints it into a new raster image
+/// of the geometries local size.
+///
+/// The background stays transparent, so only what the widget paints ends up in the image.
+///
+/// # Panics
+/// The widget gets rendered like the root of a window, so the geometry has to be placed at the origin.
+pub fn render_widget(widget: &WidgetRef<dyn Widget>, geometry: Geometry) -> Image {
+	assert_eq!(
+		geometry.absolute_pos(),
+		Vector2::new(0.0, 0.0),
+		"Snapshots can only be rendered for widgets placed at the origin"
+	);
+	let size = geometry.local_size();
+	let mut surface = Surface::new_raster_n32_premul((
+		(size.x.ceil() as i32).max(1),
+		(size.y.ceil() as i32).max(1),
+	))
+	.expect("Unable to create raster surface for widget snapshot");
+
+	widget.get().arrange_children(geometry);
+
+	let canvas = surface.canvas();
+	canvas.clear(Color::TRANSPARENT);
+	canvas.save();
+	widget.get().paint(geometry, 0, canvas);
+	canvas.restore();
+
+	surface.image_snapshot()
+}
+
+/// Renders the widget like [render_widget()] and compares the result with the PNG stored at
+/// the given path.
+///
+/// A pixel counts as different if any of its channels differs by more than `tolerance`.
+/// If any pixel is different, a diff image gets written next to the snapshot
+/// (`<name>.diff.png`) and the rendered image as `<name>.actual.png`.
+///
+/// If the environment variable [UPDATE_SNAPSHOTS_ENV] is set, the snapshot gets overwritten
+/// with the rendered image instead.
+pub fn check_snapshot(
+	widget: &WidgetRef<dyn Widget>,
+	geometry: Geometry,
+	path: impl AsRef<Path>,
+	tolerance: u8,
+) -> Result<(), SnapshotError> {
+	let path = path.as_ref();
+	let actual = render_widget(widget, geometry);
+
+	if std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+		return write_png(&actual, path);
+	}
+
+	let expected = match std::fs::read(path) {
+		Ok(bytes) => bytes,
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+			return Err(SnapshotError::Missing(path.to_path_buf()));
+		}
+		Err(err) => return Err(SnapshotError::Io(path.to_path_buf(), err)),
+	};
+	let expected = Image::from_encoded(Data::new_copy(&expected))
+		.ok_or_else(|| SnapshotError::Decode(path.to_path_buf()))?;
+
+	if expected.dimensions() != actual.dimensions() {
+		write_png(&actual, &sibling_path(path, "actual"))?;
+		return Err(SnapshotError::SizeMismatch {
+			expected: (expected.width(), expected.height()),
+			actual: (actual.width(), actual.height()),
+		});
+	}
+
+	let info = rgba_info(&actual);
+	let expected_pixels = read_rgba(&expected, &info).ok_or_else(|| SnapshotError::Decode(path.to_path_buf()))?;
+	let actual_pixels = read_rgba(&actual, &info).ok_or(SnapshotError::Encode)?;
+
+	let mut mismatched_pixels = 0;
+	let mut diff = Vec::with_capacity(actual_pixels.len());
+	for (expected, actual) in expected_pixels.chunks_exact(4).zip(actual_pixels.chunks_exact(4)) {
+		let mismatch = expected
+			.iter()
+			.zip(actual)
+			.any(|(e, a)| e.abs_diff(*a) > tolerance);
+		if mismatch {
+			mismatched_pixels += 1;
+			diff.extend_from_slice(&[255, 0, 0, 255]);
+		} else {
+			// faded version of the matching pixel to give some context in the diff image
+			diff.extend_from_slice(&[actual[0] / 4, actual[1] / 4, actual[2] / 4, 255]);
+		}
+	}
+
+	if mismatched_pixels == 0 {
+		return Ok(());
+	}
+
+	let diff_path = sibling_path(path, "diff");
+	let diff = Image::from_raster_data(&info, Data::new_copy(&diff), info.min_row_bytes())
+		.ok_or(SnapshotError::Encode)?;
+	write_png(&diff, &diff_path)?;
+	write_png(&actual, &sibling_path(path, "actual"))?;
+
+	Err(SnapshotError::PixelMismatch {
+		mismatched_pixels,
+		diff_path,
+	})
+}
+
+/// Same as [check_snapshot()] but panics if the check fails.
+#[track_caller]
+pub fn assert_snapshot(
+	widget: &WidgetRef<dyn Widget>,
+	geometry: Geometry,
+	path: impl AsRef<Path>,
+	tolerance: u8,
+) {
+	if let Err(err) = check_snapshot(widget, geometry, path, tolerance) {
+		panic!("Snapshot check failed: {}", err);
+	}
+}
+
+#[cfg(feature = "headless")]
+type ApplicationJob = Box<dyn FnOnce() + Send>;
+
+#[cfg(feature = "headless")]
+static APPLICATION_THREAD: LazyLock<Mutex<mpsc::Sender<ApplicationJob>>> = LazyLock::new(|| {
+	let (sender, receiver) = mpsc::channel::<ApplicationJob>();
+	std::thread::Builder::new()
+		.name("ruitachi-application".into())
+		.spawn(move || receiver.into_iter().for_each(|job| job()))
+		.expect("Unable to spawn the application thread");
+	Mutex::new(sender)
+});
+
+/// Runs the given function with the [GUIApplication] and returns its result.
+///
+/// The application belongs to the thread that first accessed it, while tests each run in their
+/// own thread. So all functions passed here run one after another on a single dedicated thread.
+/// As they share the application, they should remove the windows, cursors and keyboards they added.
+///
+/// A panic inside the function gets resumed in the calling thread.
+#[cfg(feature = "headless")]
+pub fn with_application<R, F>(func: F) -> R
+where
+	R: Send + 'static,
+	F: FnOnce(&GUIApplication) -> R + Send + 'static,
+{
+	let (result_sender, result_receiver) = mpsc::channel();
+	let job: ApplicationJob = Box::new(move || {
+		let result = std::panic::catch_unwind(AssertUnwindSafe(|| func(GUIApplication::get())));
+		let _ = result_sender.send(result);
+	});
+	APPLICATION_THREAD
+		.lock()
+		.unwrap_or_else(|poisoned| poisoned.into_inner())
+		.send(job)
+		.expect("The application thread stopped");
+	match result_receiver.recv().expect("The application thread stopped") {
+		Ok(result) => result,
+		Err(panic) => std::panic::resume_unwind(panic),
+	}
+}
+
+fn rgba_info(image: &Image) -> ImageInfo {
+	ImageInfo::new(image.dimensions(), ColorType::RGBA8888, AlphaType::Unpremul, None)
+}
+
+fn read_rgba(image: &Image, info: &ImageInfo) -> Option<Vec<u8>> {
+	let row_bytes = info.min_row_bytes();
+	let mut pixels = vec![0u8; row_bytes * info.height() as usize];
+	image
+		.read_pixels(info, &mut pixels, row_bytes, (0, 0), CachingHint::Allow)
+		.then_some(pixels)
+}
+
+fn write_png(image: &Image, path: &Path) -> Result<(), SnapshotError> {
+	let data = image
+		.encode_to_data(EncodedImageFormat::PNG)
+		.ok_or(SnapshotError::Encode)?;
+	if let Some(parent) = path.parent() {
+		std::fs::create_dir_all(parent).map_err(|err| SnapshotError::Io(parent.to_path_buf(), err))?;
+	}
+	std::fs::write(path, data.as_bytes()).map_err(|err| SnapshotError::Io(path.to_path_buf(), err))
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+	let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+	path.with_file_name(format!("{}.{}.png", stem, suffix))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::{Path, PathBuf};
+	use std::sync::Mutex;
+	use crate::{
+		util::{Geometry, WidgetRef},
+		widgets::{
+			BoxPanel, Growth, HorizontalAlignment, LinearPanel, LinearPanelDirection, ScrollPanel,
+			ScrollPanelDirection, TestWidget, VerticalAlignment, Widget,
+		},
+	};
+	use cgmath::Vector2;
+	use skia_safe::Color;
+	use super::{assert_snapshot, check_snapshot, render_widget, write_png, SnapshotError, UPDATE_SNAPSHOTS_ENV};
+
+	/// Snapshot checks read [UPDATE_SNAPSHOTS_ENV], so they must not run while a test sets it.
+	static SNAPSHOT_ENV: Mutex<()> = Mutex::new(());
+
+	fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+		SNAPSHOT_ENV.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	fn snapshot_path(name: &str) -> PathBuf {
+		PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("src/testing/snapshots")
+			.join(format!("{}.png", name))
+	}
+
+	/// Returns the path of a snapshot in an empty directory only used by the calling test.
+	fn temp_snapshot_path(test: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("ruitachi-snapshot-{}-{}", test, std::process::id()));
+		let _ = std::fs::remove_dir_all(&dir);
+		dir.join("widget.png")
+	}
+
+	fn root_geometry(width: f32, height: f32) -> Geometry {
+		Geometry::new(
+			Vector2::new(0.0, 0.0),
+			Vector2::new(width, height),
+			Vector2::new(0.0, 0.0),
+			Vector2::new(1.0, 1.0),
+		)
+	}
+
+	fn block(width: f32, height: f32, color: Color) -> WidgetRef<dyn Widget> {
+		TestWidget::new().size(Vector2::new(width, height)).color(color).build()
+	}
+
+	/// Stores the rendering of a filled block as snapshot.
+	fn store_block(path: &Path, geometry: Geometry, color: Color) {
+		let image = render_widget(&block(10.0, 10.0, color), geometry);
+		write_png(&image, path).unwrap();
+	}
+
+	#[test]
+	fn linear_panel_distributes_space() {
+		let _env = lock_env();
+		let panel: WidgetRef<dyn Widget> = LinearPanel::new(LinearPanelDirection::Horizontal)
+			.slot(block(20.0, 10.0, Color::RED), Growth::Fit)
+			.slot(block(10.0, 10.0, Color::GREEN), Growth::Fill)
+			.slot(block(10.0, 10.0, Color::BLUE), Growth::Fill)
+			.build();
+
+		assert_snapshot(&panel, root_geometry(100.0, 20.0), snapshot_path("linear_panel"), 0);
+	}
+
+	#[test]
+	fn box_panel_aligns_child() {
+		let _env = lock_env();
+		let panel: WidgetRef<dyn Widget> = BoxPanel::new(block(20.0, 10.0, Color::GREEN))
+			.h_align(HorizontalAlignment::Center)
+			.v_align(VerticalAlignment::Bottom)
+			.build();
+
+		assert_snapshot(&panel, root_geometry(60.0, 40.0), snapshot_path("box_panel"), 0);
+	}
+
+	#[test]
+	fn scroll_panel_offsets_content() {
+		let _env = lock_env();
+		let content = LinearPanel::new(LinearPanelDirection::Vertical)
+			.slot(block(40.0, 63.0, Color::GREEN), Growth::Fit)
+			.slot(block(40.0, 63.0, Color::YELLOW), Growth::Fit)
+			.build();
+		let panel = ScrollPanel::new()
+			.direction(ScrollPanelDirection::Vertical)
+			.content(content)
+			.build();
+		let geometry = root_geometry(50.0, 66.0);
+
+		// arrange once so the scroll bar knows the overflow before scrolling half way down
+		panel.get().arrange_children(geometry);
+		assert!(panel.get().scroll_by(Vector2::new(0.0, -30.0)));
+
+		let panel: WidgetRef<dyn Widget> = panel;
+		assert_snapshot(&panel, geometry, snapshot_path("scroll_panel"), 0);
+	}
+
+	#[test]
+	fn missing_snapshot_fails() {
+		let _env = lock_env();
+		let path = temp_snapshot_path("missing");
+
+		let result = check_snapshot(&block(10.0, 10.0, Color::RED), root_geometry(10.0, 10.0), &path, 0);
+
+		assert!(matches!(result, Err(SnapshotError::Missing(missing)) if missing == path));
+		assert!(!path.exists());
+	}
+
+	#[test]
+	fn differences_within_tolerance_pass() {
+		let _env = lock_env();
+		let path = temp_snapshot_path("tolerance");
+		let geometry = root_geometry(10.0, 10.0);
+		store_block(&path, geometry, Color::from_rgb(250, 0, 0));
+
+		check_snapshot(&block(10.0, 10.0, Color::from_rgb(255, 0, 0)), geometry, &path, 5).unwrap();
+
+		assert!(!path.with_file_name("widget.diff.png").exists());
+		assert!(!path.with_file_name("widget.actual.png").exists());
+	}
+
+	#[test]
+	fn pixel_mismatch_writes_diff_and_actual() {
+		let _env = lock_env();
+		let path = temp_snapshot_path("mismatch");
+		let geometry = root_geometry(10.0, 10.0);
+		store_block(&path, geometry, Color::from_rgb(250, 0, 0));
+
+		let result = check_snapshot(&block(10.0, 10.0, Color::from_rgb(255, 0, 0)), geometry, &path, 4);
+
+		let diff_path = path.with_file_name("widget.diff.png");
+		match result {
+			Err(SnapshotError::PixelMismatch {
+				mismatched_pixels,
+				diff_path: reported,
+			}) => {
+				assert_eq!(mismatched_pixels, 100);
+				assert_eq!(reported, diff_path);
+			}
+			other => panic!("expected a pixel mismatch, got {:?}", other),
+		}
+		assert!(diff_path.exists());
+		// the actual image is a valid snapshot of the new rendering
+		let actual_path = path.with_file_name("widget.actual.png");
+		check_snapshot(&block(10.0, 10.0, Color::from_rgb(255, 0, 0)), geometry, &actual_path, 0).unwrap();
+	}
+
+	#[test]
+	fn size_mismatch_writes_actual() {
+		let _env = lock_env();
+		let path = temp_snapshot_path("size");
+		store_block(&path, root_geometry(10.0, 10.0), Color::RED);
+
+		let result = check_snapshot(&block(10.0, 10.0, Color::RED), root_geometry(20.0, 10.0), &path, 0);
+
+		assert!(matches!(
+			result,
+			Err(SnapshotError::SizeMismatch {
+				expected: (10, 10),
+				actual: (20, 10),
+			})
+		));
+		assert!(path.with_file_name("widget.actual.png").exists());
+	}
+
+	#[test]
+	fn update_env_overwrites_snapshot() {
+		let _env = lock_env();
+		let path = temp_snapshot_path("update");
+		let geometry = root_geometry(10.0, 10.0);
+		store_block(&path, geometry, Color::RED);
+		let widget = block(10.0, 10.0, Color::BLUE);
+
+		std::env::set_var(UPDATE_SNAPSHOTS_ENV, "1");
+		let updated = check_snapshot(&widget, geometry, &path, 0);
+		std::env::remove_var(UPDATE_SNAPSHOTS_ENV);
+
+		updated.unwrap();
+		check_snapshot(&widget, geometry, &path, 0).unwrap();
+	}
+
+	#[cfg(feature = "headless")]
+	#[test]
+	fn injected_click_hovers_and_clicks_button() {
+		use std::{cell::Cell, rc::Rc};
+		use crate::{
+			application::GUIApplication,
+			events::input::MouseButton,
+			platform::HeadlessInput,
+			widgets::{ButtonWidget, Window, WindowWidget},
+		};
+		use super::with_application;
+
+		let (clicks, idle, hovered) = with_application(|app| {
+			let clicks = Rc::new(Cell::new(0));
+			let counter = clicks.clone();
+			let button: WidgetRef<dyn Widget> = ButtonWidget::new("Click")
+				.on_click(move || counter.set(counter.get() + 1))
+				.build();
+			let window: WidgetRef<dyn Window> = WindowWidget::new(Some(button)).build();
+			app.add_window(window.clone());
+			app.run();
+
+			let id = window.get().id().unwrap();
+			let pixel = |app: &GUIApplication| app.platform().window_bitmap(id).unwrap().get_color((20, 20));
+			let idle = pixel(app);
+
+			app.inject_input(HeadlessInput::CursorMove {
+				window: id,
+				cursor: 100,
+				pos: Vector2::new(20.0, 20.0),
+			});
+			for pressed in [true, false] {
+				app.inject_input(HeadlessInput::MouseButton {
+					window: id,
+					cursor: 100,
+					button: MouseButton::Left,
+					pressed,
+				});
+			}
+			app.platform_mut().render();
+			let hovered = pixel(app);
+
+			app.inject_input(HeadlessInput::CursorRemoved { cursor: 100 });
+			app.remove_window(window);
+			app.run();
+			(clicks.get(), idle, hovered)
+		});
+
+		assert_eq!(clicks, 1);
+		assert_eq!(idle, Color::from_rgb(0x50, 0x50, 0x50));
+		assert_eq!(hovered, Color::from_rgb(0x60, 0x60, 0x60));
+	}
+}
@@ -257,7 +257,7 @@ impl Geometry {
 	}
 
 	pub fn absolute_pos(&self) -> Vector2<scalar> {
-		self.local_pos
+		self.absolute_pos
 	}
 
 	pub fn set_absolute_pos(&mut self, pos: Vector2<scalar>) {
@@ -101,7 +101,7 @@ impl Widget for BoxPanel {
 	}
 
 	fn get_children(&self) -> Children {
-		Vec::new()
+		vec![self.state().child.clone()]
 	}
 
 	fn arrange_children(&self, geometry: Geometry) {
@@ -0,0 +1,264 @@
+use std::cell::{Ref, RefMut};
+use crate::{
+	events::{Reply, WidgetEvent},
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Color, Font, Paint, PaintStyle, Point, Rect};
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+use crate::widgets::pressable::{PressableState, PressableWidget};
+
+/// Look of buttons, check boxes, radio buttons and toggle switches.
+pub struct ButtonStyle {
+	pub font: Font,
+	pub foreground: Paint,
+	/// Paint of the label while disabled.
+	pub disabled_foreground: Paint,
+	pub background: Paint,
+	pub hovered: Paint,
+	pub pressed: Paint,
+	pub disabled: Paint,
+	/// Fill of a checked box, a selected radio button or a switched on toggle.
+	pub accent: Paint,
+	/// Outline drawn around the widget while it is focused.
+	pub focus: Paint,
+	/// Space around the label of a button and between the indicator and the label of the others.
+	pub padding: scalar,
+}
+
+impl Default for ButtonStyle {
+	fn default() -> Self {
+		let paint = |color: Color| {
+			let mut paint = Paint::default();
+			paint.set_anti_alias(true);
+			paint.set_color(color);
+			paint
+		};
+		let mut focus = paint(Color::from_rgb(0x33, 0x99, 0xFF));
+		focus.set_style(PaintStyle::Stroke);
+		focus.set_stroke_width(2.0);
+		Self {
+			font: Font::default(),
+			foreground: paint(Color::WHITE),
+			disabled_foreground: paint(Color::from_rgb(0x80, 0x80, 0x80)),
+			background: paint(Color::from_rgb(0x50, 0x50, 0x50)),
+			hovered: paint(Color::from_rgb(0x60, 0x60, 0x60)),
+			pressed: paint(Color::from_rgb(0x40, 0x40, 0x40)),
+			disabled: paint(Color::from_rgb(0x38, 0x38, 0x38)),
+			accent: paint(Color::from_rgb(0x33, 0x66, 0xCC)),
+			focus,
+			padding: 6.0,
+		}
+	}
+}
+
+impl ButtonStyle {
+	/// Returns the background for the interaction state.
+	pub(crate) fn background(&self, state: &PressableState) -> &Paint {
+		if !state.enabled {
+			&self.disabled
+		} else if state.pressed {
+			&self.pressed
+		} else if state.hovered {
+			&self.hovered
+		} else {
+			&self.background
+		}
+	}
+
+	pub(crate) fn label_paint(&self, state: &PressableState) -> &Paint {
+		if state.enabled {
+			&self.foreground
+		} else {
+			&self.disabled_foreground
+		}
+	}
+
+	pub(crate) fn label_size(&self, label: &str) -> Vector2<scalar> {
+		let width = self.font.measure_str(label, Some(&self.foreground)).0;
+		let metrics = self.font.metrics().1;
+		Vector2::new(width, metrics.bottom - metrics.top)
+	}
+
+	/// Returns the size of an indicator followed by the label, like a check box.
+	pub(crate) fn indicator_size(&self, indicator: Vector2<scalar>, label: &str) -> Vector2<scalar> {
+		if label.is_empty() {
+			return indicator;
+		}
+		let label = self.label_size(label);
+		Vector2::new(indicator.x + self.padding + label.x, indicator.y.max(label.y))
+	}
+
+	/// Draws the label starting at x, vertically centered within the height.
+	pub(crate) fn draw_label(&self, painter: &mut Painter, label: &str, x: scalar, height: scalar, state: &PressableState) {
+		let metrics = self.font.metrics().1;
+		let base_line = (height - (metrics.bottom - metrics.top)) / 2.0 - metrics.top;
+		painter.draw_str(label, Point::new(x, base_line), &self.font, self.label_paint(state));
+	}
+
+	/// Draws the focus outline around the area if the widget is focused.
+	pub(crate) fn draw_focus(&self, painter: &mut Painter, area: Rect, radius: scalar, state: &PressableState) {
+		if state.focused {
+			painter.draw_round_rect(area, radius, radius, &self.focus);
+		}
+	}
+}
+
+pub struct ButtonWidgetState {
+	leaf: LeafState,
+	pressable: PressableState,
+	label: String,
+	style: ButtonStyle,
+	on_click: Option<Box<dyn Fn()>>,
+}
+
+pub type ButtonWidget = WidgetImpl<ButtonWidgetState>;
+
+pub struct ButtonWidgetBuilder(ButtonWidget);
+
+/// A push button with a text label.
+///
+/// Gets clicked by releasing the left mouse button over it or by pressing Space or Enter while focused.
+impl ButtonWidget {
+	pub fn new(label: impl Into<String>) -> ButtonWidgetBuilder {
+		let mut leaf = LeafState::default();
+		leaf.widget.focusable = true;
+		ButtonWidgetBuilder(ButtonWidgetState {
+			leaf,
+			pressable: Default::default(),
+			label: label.into(),
+			style: Default::default(),
+			on_click: None,
+		}.into())
+	}
+
+	pub fn label(&self) -> String {
+		self.state().label.clone()
+	}
+
+	pub fn set_label(&self, label: String) {
+		self.state_mut().label = label;
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+		self.invalidate_paint();
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.pressable_is_enabled()
+	}
+
+	pub fn set_enabled(&self, enabled: bool) {
+		self.pressable_set_enabled(enabled);
+	}
+
+	pub fn set_on_click<F>(&self, func: F) where F: Fn() + 'static {
+		self.state_mut().on_click = Some(Box::new(func));
+	}
+}
+
+impl ButtonWidgetBuilder {
+	pub fn on_click<F>(self, func: F) -> Self where F: Fn() + 'static {
+		self.0.state_mut().on_click = Some(Box::new(func));
+		self
+	}
+
+	pub fn enabled(self, enabled: bool) -> Self {
+		let mut state = self.0.state_mut();
+		state.pressable.enabled = enabled;
+		state.leaf.widget.focusable = enabled;
+		drop(state);
+		self
+	}
+
+	pub fn style(self, style: ButtonStyle) -> Self {
+		self.0.state_mut().style = style;
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.state_mut().leaf.widget.tab_index = tab_index;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<ButtonWidget> {
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for ButtonWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let state = self.state();
+		let style = &state.style;
+		let rect = Rect::from_wh(size.x, size.y);
+		painter.draw_round_rect(rect, 3.0, 3.0, style.background(&state.pressable));
+		let label_width = style.label_size(&state.label).x;
+		style.draw_label(painter, &state.label, (size.x - label_width) / 2.0, size.y, &state.pressable);
+		style.draw_focus(painter, rect.with_inset((1.0, 1.0)), 3.0, &state.pressable);
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let padding = Vector2::new(state.style.padding, state.style.padding) * 2.0;
+		state.style.label_size(&state.label) + padding
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		self.pressable_on_event(event)
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for ButtonWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
+
+impl PressableWidget for ButtonWidget {
+	fn pressable_state(&self) -> Ref<PressableState> {
+		self.widget_state(|v| &v.pressable)
+	}
+
+	fn pressable_state_mut(&self) -> RefMut<PressableState> {
+		self.widget_state_mut(|v| &mut v.pressable)
+	}
+
+	fn activate(&self) {
+		let on_click = self.state_mut().on_click.take();
+		if let Some(on_click) = on_click {
+			on_click();
+			self.state_mut().on_click = Some(on_click);
+		}
+	}
+}
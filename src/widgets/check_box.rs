@@ -0,0 +1,201 @@
+use std::cell::{Ref, RefMut};
+use crate::{
+	events::{Reply, WidgetEvent},
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{ButtonStyle, Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, PaintStyle, Point, Rect};
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+use crate::widgets::pressable::{PressableState, PressableWidget};
+
+/// Width and height of the box.
+const BOX_SIZE: scalar = 16.0;
+
+pub struct CheckBoxWidgetState {
+	leaf: LeafState,
+	pressable: PressableState,
+	checked: bool,
+	label: String,
+	style: ButtonStyle,
+	on_checked_changed: Option<Box<dyn Fn(bool)>>,
+}
+
+pub type CheckBoxWidget = WidgetImpl<CheckBoxWidgetState>;
+
+pub struct CheckBoxWidgetBuilder(CheckBoxWidget);
+
+/// A box with an optional label next to it, that gets checked and unchecked by activating it.
+impl CheckBoxWidget {
+	pub fn new() -> CheckBoxWidgetBuilder {
+		let mut leaf = LeafState::default();
+		leaf.widget.focusable = true;
+		CheckBoxWidgetBuilder(CheckBoxWidgetState {
+			leaf,
+			pressable: Default::default(),
+			checked: false,
+			label: String::new(),
+			style: Default::default(),
+			on_checked_changed: None,
+		}.into())
+	}
+
+	pub fn is_checked(&self) -> bool {
+		self.state().checked
+	}
+
+	pub fn set_checked(&self, checked: bool) {
+		if self.state().checked == checked {
+			return;
+		}
+		self.state_mut().checked = checked;
+		self.invalidate_paint();
+		let delegate = self.state_mut().on_checked_changed.take();
+		if let Some(delegate) = delegate {
+			delegate(checked);
+			self.state_mut().on_checked_changed = Some(delegate);
+		}
+	}
+
+	pub fn set_label(&self, label: String) {
+		self.state_mut().label = label;
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+		self.invalidate_paint();
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.pressable_is_enabled()
+	}
+
+	pub fn set_enabled(&self, enabled: bool) {
+		self.pressable_set_enabled(enabled);
+	}
+}
+
+impl CheckBoxWidgetBuilder {
+	pub fn checked(self, checked: bool) -> Self {
+		self.0.state_mut().checked = checked;
+		self
+	}
+
+	pub fn label(self, label: impl Into<String>) -> Self {
+		self.0.state_mut().label = label.into();
+		self
+	}
+
+	/// Called with the new state whenever the box gets checked or unchecked.
+	pub fn on_checked_changed<F>(self, func: F) -> Self where F: Fn(bool) + 'static {
+		self.0.state_mut().on_checked_changed = Some(Box::new(func));
+		self
+	}
+
+	pub fn enabled(self, enabled: bool) -> Self {
+		let mut state = self.0.state_mut();
+		state.pressable.enabled = enabled;
+		state.leaf.widget.focusable = enabled;
+		drop(state);
+		self
+	}
+
+	pub fn style(self, style: ButtonStyle) -> Self {
+		self.0.state_mut().style = style;
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.state_mut().leaf.widget.tab_index = tab_index;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<CheckBoxWidget> {
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for CheckBoxWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let state = self.state();
+		let style = &state.style;
+		let indicator = Rect::from_xywh(0.0, (size.y - BOX_SIZE) / 2.0, BOX_SIZE, BOX_SIZE);
+		if state.checked && state.pressable.enabled {
+			painter.draw_round_rect(indicator, 2.0, 2.0, &style.accent);
+		} else {
+			painter.draw_round_rect(indicator, 2.0, 2.0, style.background(&state.pressable));
+		}
+		if state.checked {
+			let mut mark = style.label_paint(&state.pressable).clone();
+			mark.set_style(PaintStyle::Stroke);
+			mark.set_stroke_width(2.0);
+			let center = Point::new(indicator.center_x(), indicator.center_y());
+			let bottom = Point::new(center.x - 1.5, center.y + 3.5);
+			painter.draw_line(Point::new(center.x - 5.0, center.y), bottom, &mark);
+			painter.draw_line(bottom, Point::new(center.x + 5.0, center.y - 4.5), &mark);
+		}
+		style.draw_focus(painter, indicator.with_outset((2.0, 2.0)), 3.0, &state.pressable);
+		if !state.label.is_empty() {
+			style.draw_label(painter, &state.label, BOX_SIZE + style.padding, size.y, &state.pressable);
+		}
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		state.style.indicator_size(Vector2::new(BOX_SIZE, BOX_SIZE), &state.label)
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		self.pressable_on_event(event)
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for CheckBoxWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
+
+impl PressableWidget for CheckBoxWidget {
+	fn pressable_state(&self) -> Ref<PressableState> {
+		self.widget_state(|v| &v.pressable)
+	}
+
+	fn pressable_state_mut(&self) -> RefMut<PressableState> {
+		self.widget_state_mut(|v| &mut v.pressable)
+	}
+
+	fn activate(&self) {
+		let checked = self.state().checked;
+		self.set_checked(!checked);
+	}
+}
@@ -0,0 +1,79 @@
+use crate::{
+	events::{
+		input::{CursorIcon, ScrollDelta},
+		Reply,
+	},
+	widgets::Axis,
+};
+use cgmath::Vector2;
+use skia_safe::scalar;
+
+/// Dragging a handle along an axis, like the handle of a [crate::widgets::ScrollBarWidget]
+/// or the thumb of a [crate::widgets::SliderWidget].
+///
+/// Remembers the value and the cursor position of the press and maps the cursor movement since then to a value.
+/// The cursor stays captured while dragging, so the drag continues outside of the widget.
+#[derive(Default, Copy, Clone)]
+pub struct HandleDrag(Option<(f64, Vector2<scalar>)>);
+
+impl HandleDrag {
+	pub fn is_dragging(&self) -> bool {
+		self.0.is_some()
+	}
+
+	/// Starts dragging at the press, the reply captures the cursor.
+	pub fn start(&mut self, mouse: usize, value: f64, pos: Vector2<scalar>) -> Reply {
+		self.0 = Some((value, pos));
+		Reply::handled().capture_cursor(mouse)
+	}
+
+	/// Ends dragging at the release, the reply releases the cursor.
+	/// Returns None if there was no drag.
+	pub fn end(&mut self, mouse: usize) -> Option<Reply> {
+		self.0.take().map(|_| Reply::handled().release_cursor(mouse))
+	}
+
+	/// Returns the value at the cursor position while dragging.
+	/// Moving the cursor by the length of the track along the axis changes the value by the span,
+	/// a negative span makes the value grow towards the start of the axis.
+	pub fn value_at(&self, pos: &Vector2<scalar>, axis: Axis, track: scalar, span: f64) -> Option<f64> {
+		let (start_value, start_pos) = self.0?;
+		let diff = axis.get_vec_axis(pos - start_pos).0 as f64;
+		Some(start_value + diff * span / track.max(1.0) as f64)
+	}
+
+	pub fn cursor_icon(&self) -> CursorIcon {
+		if self.is_dragging() {
+			CursorIcon::Grabbing
+		} else {
+			CursorIcon::Grab
+		}
+	}
+}
+
+/// Steps moved by PageUp and PageDown.
+pub const PAGE_STEPS: f64 = 10.0;
+/// Pixels a touchpad has to scroll to move the value by one step.
+pub const PIXELS_PER_STEP: scalar = 20.0;
+
+/// Scrolling a value by steps, like the value of a [crate::widgets::SliderWidget]
+/// or a [crate::widgets::SpinBoxWidget].
+///
+/// Touchpads scroll by fractions of a step, so the scrolled steps not applied yet get accumulated.
+#[derive(Default, Copy, Clone)]
+pub struct ScrollSteps(f64);
+
+impl ScrollSteps {
+	/// Adds the scrolled delta and returns the whole steps the value should move by.
+	/// Scrolling up moves the value up, if `horizontal` is set scrolling left does too.
+	pub fn scroll(&mut self, delta: &ScrollDelta, horizontal: bool) -> f64 {
+		let (x, y) = match delta {
+			ScrollDelta::Lines(lines) => (lines.x as f64, lines.y as f64),
+			ScrollDelta::Pixels(pixels) => ((pixels.x / PIXELS_PER_STEP) as f64, (pixels.y / PIXELS_PER_STEP) as f64),
+		};
+		self.0 += if horizontal { y - x } else { y };
+		let steps = self.0.trunc();
+		self.0 -= steps;
+		steps
+	}
+}
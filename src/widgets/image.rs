@@ -0,0 +1,94 @@
+use std::cell::{Ref, RefMut};
+use crate::{
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Image};
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+
+pub struct ImageWidgetState {
+	leaf: LeafState,
+	image: Image,
+}
+
+pub type ImageWidget = WidgetImpl<ImageWidgetState>;
+
+pub struct ImageWidgetBuilder(ImageWidget);
+
+/// A Widget showing an image at its original size.
+impl ImageWidget {
+	pub fn new(image: Image) -> ImageWidgetBuilder {
+		ImageWidgetBuilder(ImageWidgetState {
+			leaf: Default::default(),
+			image,
+		}.into())
+	}
+
+	pub fn image(&self) -> Image {
+		self.state().image.clone()
+	}
+
+	pub fn set_image(&self, image: Image) {
+		let size_changed = image.dimensions() != self.state().image.dimensions();
+		self.state_mut().image = image;
+		if size_changed {
+			self.get_parent().inspect(|p| p.get().invalidate_layout());
+		}
+		self.invalidate_paint();
+	}
+}
+
+impl ImageWidgetBuilder {
+	pub fn build(self) -> WidgetRef<ImageWidget> {
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for ImageWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, _geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		painter.draw_image(&self.state().image, (0.0, 0.0), None);
+		layer
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let image = &self.state().image;
+		Vector2::new(image.width() as scalar, image.height() as scalar)
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for ImageWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
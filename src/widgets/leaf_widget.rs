@@ -26,9 +26,10 @@ pub trait LeafWidget: Widget {
     ///
     /// # Default Implementation
     /// Does nothing just safes the geometry as cached to leaf state
+    /// and damages the old and new area if the geometry changed
     fn leaf_arrange_children(&self, geometry: Geometry){
-        let mut state = self.leaf_state_mut();
-        state.cached_geometry = geometry;
+        let old_geometry = std::mem::replace(&mut self.leaf_state_mut().cached_geometry, geometry);
+        self.damage_geometry_change(old_geometry, geometry);
     }
 
     /// Leaf Implementation of [Widget::get_arranged_children()]
@@ -0,0 +1,463 @@
+use std::cell::{Ref, RefMut};
+use std::ops::Range;
+use std::rc::Rc;
+use crate::{
+	events::{input::ScrollDelta, Reply, WidgetEvent},
+	paint::Painter,
+	util::{scalar, Geometry, WidgetRef, WidgetRefFromSelf},
+	widgets::{Axis, PanelState, PanelWidget, ScrollBarWidget, Widget, WidgetArrangement, WidgetState},
+};
+use cgmath::Vector2;
+use skia_bindings::SkClipOp;
+use skia_safe::Rect;
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::scroll_panel::{scroll_bar_by, LINE_SCROLL_DISTANCE};
+
+/// An item widget together with the function binding it to an item index.
+struct ListViewItem {
+	widget: WidgetRef<dyn Widget>,
+	bind: Box<dyn Fn(usize)>,
+}
+
+pub struct ListViewState {
+	panel: PanelState,
+	item_count: usize,
+	item_height: scalar,
+	create_item: Option<Rc<dyn Fn() -> ListViewItem>>,
+	/// Item widgets bound to the items within the visible range, ordered by item index.
+	visible: Vec<(usize, ListViewItem)>,
+	/// Item widgets not bound to any visible item, reused before new ones get created.
+	recycled: Vec<ListViewItem>,
+	scroll_bar: WidgetRef<ScrollBarWidget>,
+}
+
+pub type ListView = WidgetImpl<ListViewState>;
+
+pub struct ListViewBuilder(WidgetRef<ListView>);
+
+/// A vertical list of equally high items, that only has widgets for the items within the visible range.
+///
+/// The items are given by their count and a pair of functions, one creating item widgets and one binding
+/// an item widget to an item index. While scrolling, widgets of items leaving the visible range get bound
+/// to the items entering it, so the amount of widgets only depends on the height of the list.
+///
+/// Item widgets get rebound by [ListView::refresh()], so changed data of visible items gets shown.
+impl ListView {
+	pub fn new() -> ListViewBuilder {
+		ListViewBuilder(WidgetRef::new(ListViewState {
+			panel: Default::default(),
+			item_count: 0,
+			item_height: 24.0,
+			create_item: None,
+			visible: Vec::new(),
+			recycled: Vec::new(),
+			scroll_bar: ScrollBarWidget::new().direction(Axis::Vertical).build(),
+		}.into()))
+	}
+
+	pub fn item_count(&self) -> usize {
+		self.state().item_count
+	}
+
+	/// Changes the amount of items, all visible item widgets get rebound.
+	pub fn set_item_count(&self, item_count: usize) {
+		self.state_mut().item_count = item_count;
+		self.refresh();
+	}
+
+	pub fn item_height(&self) -> scalar {
+		self.state().item_height
+	}
+
+	pub fn set_item_height(&self, item_height: scalar) {
+		self.state_mut().item_height = item_height;
+		self.invalidate_layout();
+	}
+
+	/// Sets the functions creating new item widgets and binding an item widget to an item index.
+	/// All existing item widgets get dropped.
+	///
+	/// Binding gets called whenever the widget gets reused for another item, so it has to replace
+	/// everything the widget shows of the previous item.
+	pub fn set_items<W, C, B>(&self, create: C, bind: B)
+		where
+			W: Widget + 'static,
+			C: Fn() -> WidgetRef<W> + 'static,
+			B: Fn(&W, usize) + 'static,
+	{
+		let bind = Rc::new(bind);
+		let mut state = self.state_mut();
+		state.create_item = Some(Rc::new(move || {
+			let widget = create();
+			let bind = bind.clone();
+			let item = widget.clone();
+			ListViewItem {
+				widget,
+				bind: Box::new(move |index| bind(&item.get(), index)),
+			}
+		}));
+		for (_, item) in state.visible.drain(..) {
+			item.widget.get().set_parent(None);
+		}
+		state.recycled.clear();
+		drop(state);
+		self.invalidate_layout();
+	}
+
+	/// Rebinds all visible item widgets, e.g. after the data of the items changed.
+	pub fn refresh(&self) {
+		let mut state = self.state_mut();
+		let visible = std::mem::take(&mut state.visible);
+		state.recycled.extend(visible.into_iter().map(|(_, item)| item));
+		drop(state);
+		self.invalidate_layout();
+	}
+
+	/// Returns the range of items that currently have widgets.
+	pub fn visible_range(&self) -> Range<usize> {
+		let state = self.state();
+		match (state.visible.first(), state.visible.last()) {
+			(Some((first, _)), Some((last, _))) => *first..*last + 1,
+			_ => 0..0,
+		}
+	}
+
+	/// Returns the widget bound to the item, if the item is within the visible range.
+	pub fn item_widget(&self, index: usize) -> Option<WidgetRef<dyn Widget>> {
+		let state = self.state();
+		state.visible.iter().find(|(item_index, _)| *item_index == index).map(|(_, item)| item.widget.clone())
+	}
+
+	/// Returns the index of the item at the position in window coordinates.
+	pub fn index_at(&self, pos: &Vector2<scalar>) -> Option<usize> {
+		let geometry = self.cached_geometry();
+		if !geometry.contains_absolute_pos(pos) {
+			return None;
+		}
+		let state = self.state();
+		let y = (pos.y - geometry.absolute_pos().y) as f64 + self.scroll_offset();
+		let index = (y / state.item_height as f64).floor();
+		if index < 0.0 || index >= state.item_count as f64 {
+			return None;
+		}
+		Some(index as usize)
+	}
+
+	/// Scrolls by the given amount of pixels, positive values scroll towards the start.
+	/// Returns true if anything scrolled.
+	pub fn scroll_by(&self, delta: scalar) -> bool {
+		let scroll_bar = self.state().scroll_bar.clone();
+		let scroll_bar = scroll_bar.get();
+		scroll_bar_by(&scroll_bar, -delta)
+	}
+
+	/// Scrolls as little as possible, so the item is completely visible.
+	pub fn scroll_into_view(&self, index: usize) {
+		let overflow = self.overflow();
+		if overflow <= 0.0 {
+			return;
+		}
+		let viewport = self.cached_geometry().local_size().y as f64;
+		let item_height = self.state().item_height as f64;
+		let offset = self.scroll_offset();
+		let start = index as f64 * item_height;
+		let end = start + item_height;
+		let new_offset = if start < offset {
+			start
+		} else if end > offset + viewport {
+			(end - viewport).min(start)
+		} else {
+			return;
+		};
+		let scroll_bar = self.state().scroll_bar.clone();
+		scroll_bar.get().set_value(new_offset.clamp(0.0, overflow) / overflow);
+	}
+
+	/// Returns by how many pixels the items are higher than the list.
+	fn overflow(&self) -> f64 {
+		let state = self.state();
+		let content_height = state.item_count as f64 * state.item_height as f64;
+		(content_height - self.cached_geometry().local_size().y as f64).max(0.0)
+	}
+
+	/// Returns the distance in pixels from the top of the first item to the top of the list.
+	fn scroll_offset(&self) -> f64 {
+		let value = self.state().scroll_bar.get().value();
+		value * self.overflow()
+	}
+
+	/// Binds widgets to the items within the range.
+	/// Widgets of items that stay visible are kept, the others get recycled.
+	///
+	/// Recycled widgets leave the widget tree, so they lose the focus, as they show other items afterwards.
+	/// They only get reused by the next realization, to give the event context a chance to notice that.
+	fn realize(&self, range: Range<usize>) {
+		let mut state = self.state_mut();
+		let old_visible = std::mem::take(&mut state.visible);
+		let mut kept = Vec::new();
+		let mut recycled = Vec::new();
+		for (index, item) in old_visible {
+			if range.contains(&index) {
+				kept.push((index, item));
+			} else {
+				item.widget.get().set_parent(None);
+				recycled.push(item);
+			}
+		}
+		drop(state);
+
+		let mut kept = kept.into_iter().peekable();
+		let mut visible = Vec::with_capacity(range.len());
+		for index in range {
+			if kept.peek().is_some_and(|(kept_index, _)| *kept_index == index) {
+				visible.push(kept.next().unwrap());
+				continue;
+			}
+			let reused = self.state_mut().recycled.pop();
+			let item = match reused {
+				Some(item) => item,
+				None => {
+					let create_item = self.state().create_item.clone();
+					match create_item {
+						Some(create_item) => create_item(),
+						None => break,
+					}
+				}
+			};
+			item.widget.get().set_parent(Some(self.widget_ref()));
+			(item.bind)(index);
+			visible.push((index, item));
+		}
+		let mut state = self.state_mut();
+		state.visible = visible;
+		state.recycled.extend(recycled);
+	}
+}
+
+impl ListViewBuilder {
+	pub fn item_count(self, item_count: usize) -> Self {
+		self.0.get().state_mut().item_count = item_count;
+		self
+	}
+
+	/// Height of every item in pixels.
+	pub fn item_height(self, item_height: scalar) -> Self {
+		self.0.get().state_mut().item_height = item_height;
+		self
+	}
+
+	/// Sets the functions creating new item widgets and binding an item widget to an item index,
+	/// see [ListView::set_items()].
+	pub fn items<W, C, B>(self, create: C, bind: B) -> Self
+		where
+			W: Widget + 'static,
+			C: Fn() -> WidgetRef<W> + 'static,
+			B: Fn(&W, usize) + 'static,
+	{
+		self.0.get().set_items(create, bind);
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<ListView> {
+		let widget = self.0.get();
+		let scroll_bar = widget.state().scroll_bar.clone();
+		scroll_bar.get().set_parent(Some(self.0.clone()));
+		let weak = self.0.downgrade();
+		scroll_bar.get().state_mut().on_value_changed = Some(Box::new(move |_, _| {
+			weak.upgrade().inspect(|list| list.get().invalidate_layout());
+		}));
+		drop(widget);
+		self.0
+	}
+}
+
+impl Widget for ListView {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.panel.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.panel.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		painter.save();
+		painter.clip_rect(
+			Rect::new(0.0, 0.0, geometry.local_size().x, geometry.local_size().y),
+			Some(SkClipOp::Intersect),
+			None,
+		);
+		let layer = self.panel_paint(geometry, layer, painter);
+		painter.restore();
+		layer
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let width = state.visible
+			.iter()
+			.map(|(_, item)| item.widget.get().get_desired_size().x)
+			.fold(0.0, scalar::max);
+		Vector2::new(width + state.scroll_bar.get().get_desired_size().x, 0.0)
+	}
+
+	fn get_children(&self) -> Children {
+		let state = self.state();
+		state.visible
+			.iter()
+			.map(|(_, item)| item.widget.clone())
+			.chain(std::iter::once(state.scroll_bar.clone() as WidgetRef<dyn Widget>))
+			.collect()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.panel_arrange_children(geometry);
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.panel_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnScroll { delta, .. } => {
+				let pixels = match delta {
+					ScrollDelta::Lines(lines) => lines.y * LINE_SCROLL_DISTANCE,
+					ScrollDelta::Pixels(pixels) => pixels.y,
+				};
+				if self.scroll_by(pixels) {
+					Reply::handled()
+				} else {
+					Reply::unhandled()
+				}
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.panel_cached_geometry()
+	}
+}
+
+impl PanelWidget for ListView {
+	fn panel_state(&self) -> Ref<PanelState> {
+		self.widget_state(|v| &v.panel)
+	}
+
+	fn panel_state_mut(&self) -> RefMut<PanelState> {
+		self.widget_state_mut(|v| &mut v.panel)
+	}
+
+	fn rearrange_children(&self, geometry: Geometry) -> Vec<WidgetArrangement> {
+		let size = geometry.local_size();
+		let state = self.state();
+		let item_height = state.item_height as f64;
+		let content_height = state.item_count as f64 * item_height;
+		let item_count = state.item_count;
+		let scroll_bar = state.scroll_bar.clone();
+		drop(state);
+
+		let overflow = (content_height - size.y as f64).max(0.0);
+		scroll_bar.get().set_range(0.0..overflow);
+		let offset = scroll_bar.get().value() * overflow;
+		let range = if item_height > 0.0 {
+			let first = (offset / item_height).floor() as usize;
+			let end = ((offset + size.y as f64) / item_height).ceil() as usize;
+			first.min(item_count)..end.min(item_count)
+		} else {
+			0..0
+		};
+		self.realize(range);
+
+		let scroll_bar_width = match overflow > 0.0 {
+			true => scroll_bar.get().get_desired_size().x,
+			false => 0.0,
+		};
+		let state = self.state();
+		let mut arrangements: Vec<WidgetArrangement> = state.visible
+			.iter()
+			.map(|(index, item)| {
+				// positioned relative to the offset in double precision, so long lists stay exact
+				let y = (*index as f64 * item_height - offset) as scalar;
+				geometry.child_widget(
+					item.widget.clone(),
+					Vector2::new(0.0, y),
+					Vector2::new(size.x - scroll_bar_width, state.item_height),
+				)
+			})
+			.collect();
+		if overflow > 0.0 {
+			arrangements.push(geometry.child_widget(
+				scroll_bar.clone(),
+				Vector2::new(size.x - scroll_bar_width, 0.0),
+				Vector2::new(scroll_bar_width, size.y),
+			));
+		}
+		arrangements
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::RefCell;
+	use std::rc::Rc;
+	use crate::{
+		util::{Geometry, WidgetRef},
+		widgets::{ButtonWidget, ListView, Widget},
+	};
+	use cgmath::Vector2;
+
+	#[test]
+	fn scrolling_recycles_item_widgets() {
+		let created: Rc<RefCell<Vec<WidgetRef<ButtonWidget>>>> = Default::default();
+		let create = created.clone();
+		let list = ListView::new()
+			.item_count(100)
+			.item_height(20.0)
+			.items(
+				move || {
+					let button = ButtonWidget::new("").build();
+					create.borrow_mut().push(button.clone());
+					button
+				},
+				|button: &ButtonWidget, index| button.set_label(index.to_string()),
+			)
+			.build();
+		let geometry = Geometry::new(
+			Vector2::new(0.0, 0.0),
+			Vector2::new(100.0, 100.0),
+			Vector2::new(0.0, 0.0),
+			Vector2::new(1.0, 1.0),
+		);
+		let bound_labels = || {
+			let mut labels: Vec<String> = created
+				.borrow()
+				.iter()
+				.filter(|button| button.get().get_parent().is_some())
+				.map(|button| button.get().label())
+				.collect();
+			labels.sort_by_key(|label| label.parse::<usize>().unwrap());
+			labels
+		};
+
+		list.get().arrange_children(geometry);
+		assert_eq!(list.get().visible_range(), 0..5);
+		let first = created.borrow()[0].clone();
+
+		// the 2000 pixels of items overflow by 1900 pixels, scroll half way down
+		assert!(list.get().scroll_by(-950.0));
+		list.get().arrange_children(geometry);
+		assert_eq!(list.get().visible_range(), 47..53);
+		// the widgets recycled by this realization left the tree and are not reused yet
+		assert!(first.get().get_parent().is_none());
+		assert_eq!(created.borrow().len(), 11);
+		assert_eq!(bound_labels(), (47..53).map(|index| index.to_string()).collect::<Vec<_>>());
+
+		assert!(list.get().scroll_by(-475.0));
+		list.get().arrange_children(geometry);
+		assert_eq!(list.get().visible_range(), 71..77);
+		assert_eq!(created.borrow().len(), 12);
+		assert_eq!(bound_labels(), (71..77).map(|index| index.to_string()).collect::<Vec<_>>());
+	}
+}
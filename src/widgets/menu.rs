@@ -0,0 +1,1053 @@
+use std::cell::{Cell, Ref, RefMut};
+use std::rc::Rc;
+use crate::{
+	events::{input::MouseButton, KeyChord, Reply, WidgetEvent, WidgetFocusChange},
+	paint::Painter,
+	util::{Geometry, WidgetRef, WidgetRefFromSelfSpecific, WidgetWeak},
+	widgets::{Popup, PopupPlacement, PopupSide, Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Color, Font, Paint, PaintStyle, Point, Rect};
+use winit::event::VirtualKeyCode;
+use crate::widgets::{Arrangements, Children, PanelState, PanelWidget, WidgetArrangement, WidgetImpl};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+
+/// Space between the frame of a menu and its items.
+const MENU_PADDING: scalar = 4.0;
+/// Space above and below the label of an item.
+const ITEM_PADDING: scalar = 4.0;
+const SEPARATOR_HEIGHT: scalar = 9.0;
+/// Width of the column left of the labels, showing check marks and radio dots.
+const CHECK_COLUMN: scalar = 24.0;
+/// Width of the column right of the accelerators, showing the arrows of submenus.
+const ARROW_COLUMN: scalar = 20.0;
+/// Minimum space between a label and its accelerator.
+const ACCELERATOR_GAP: scalar = 24.0;
+/// Space left and right of a title of a menu bar.
+const TITLE_PADDING: scalar = 8.0;
+
+/// What a [MenuItem] does when it gets activated.
+#[derive(Clone)]
+pub enum MenuItemKind {
+	Action,
+	/// Toggles the checked state.
+	Check(Rc<Cell<bool>>),
+	/// Checks the item and unchecks all other radio items of the same group within the same menu.
+	Radio {
+		group: String,
+		checked: Rc<Cell<bool>>,
+	},
+	/// Opens the nested items as submenu next to the item.
+	Submenu(Vec<MenuItem>),
+	Separator,
+}
+
+/// An entry of a menu, see [MenuWidget].
+///
+/// All clones of a check or radio item share the checked state,
+/// so the application can keep a clone to read and change it.
+#[derive(Clone)]
+pub struct MenuItem {
+	label: String,
+	kind: MenuItemKind,
+	accelerator: Option<String>,
+	enabled: bool,
+	on_activate: Option<Rc<dyn Fn(&MenuItem)>>,
+}
+
+impl MenuItem {
+	fn new(label: impl Into<String>, kind: MenuItemKind) -> Self {
+		Self {
+			label: label.into(),
+			kind,
+			accelerator: None,
+			enabled: true,
+			on_activate: None,
+		}
+	}
+
+	pub fn action(label: impl Into<String>) -> Self {
+		Self::new(label, MenuItemKind::Action)
+	}
+
+	pub fn check(label: impl Into<String>, checked: bool) -> Self {
+		Self::new(label, MenuItemKind::Check(Rc::new(Cell::new(checked))))
+	}
+
+	pub fn radio(label: impl Into<String>, group: impl Into<String>, checked: bool) -> Self {
+		Self::new(label, MenuItemKind::Radio {
+			group: group.into(),
+			checked: Rc::new(Cell::new(checked)),
+		})
+	}
+
+	pub fn submenu(label: impl Into<String>, items: Vec<MenuItem>) -> Self {
+		Self::new(label, MenuItemKind::Submenu(items))
+	}
+
+	pub fn separator() -> Self {
+		Self::new("", MenuItemKind::Separator)
+	}
+
+	/// Shows the chord right aligned next to the label.
+	/// It is only a label, bind the chord with the [crate::events::CommandRegistry] to make it work.
+	pub fn accelerator(mut self, chord: KeyChord) -> Self {
+		self.accelerator = Some(chord.to_string());
+		self
+	}
+
+	/// Disabled items are grayed out and can not be highlighted or activated.
+	pub fn enabled(mut self, enabled: bool) -> Self {
+		self.enabled = enabled;
+		self
+	}
+
+	/// Called after the item got activated and the menu got closed.
+	/// Check and radio items already changed their checked state.
+	pub fn on_activate(mut self, on_activate: impl Fn(&MenuItem) + 'static) -> Self {
+		self.on_activate = Some(Rc::new(on_activate));
+		self
+	}
+
+	pub fn label(&self) -> &str {
+		&self.label
+	}
+
+	pub fn kind(&self) -> &MenuItemKind {
+		&self.kind
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+
+	/// Returns true if the item is a checked check or radio item.
+	pub fn is_checked(&self) -> bool {
+		match &self.kind {
+			MenuItemKind::Check(checked) | MenuItemKind::Radio { checked, .. } => checked.get(),
+			_ => false,
+		}
+	}
+
+	/// Changes the checked state of a check or radio item, does nothing for other items.
+	/// Other radio items of the group do not get unchecked.
+	pub fn set_checked(&self, value: bool) {
+		if let MenuItemKind::Check(checked) | MenuItemKind::Radio { checked, .. } = &self.kind {
+			checked.set(value);
+		}
+	}
+
+	/// Returns true if the item can be highlighted and activated.
+	fn is_selectable(&self) -> bool {
+		self.enabled && !matches!(self.kind, MenuItemKind::Separator)
+	}
+}
+
+/// Look of menus and menu bars, shared by a menu and all its submenus.
+pub struct MenuStyle {
+	pub font: Font,
+	pub foreground: Paint,
+	/// Paint of the labels of disabled items.
+	pub disabled: Paint,
+	pub background: Paint,
+	/// Paint of the frame around menus and of separators.
+	pub border: Paint,
+	/// Background of the highlighted item and of the title of the open menu.
+	pub highlight: Paint,
+}
+
+impl Default for MenuStyle {
+	fn default() -> Self {
+		let mut foreground = Paint::default();
+		foreground.set_color(Color::WHITE);
+		let mut disabled = Paint::default();
+		disabled.set_color(Color::from_rgb(0x80, 0x80, 0x80));
+		let mut background = Paint::default();
+		background.set_color(Color::from_rgb(0x30, 0x30, 0x30));
+		let mut border = Paint::default();
+		border.set_color(Color::from_rgb(0x60, 0x60, 0x60));
+		border.set_style(PaintStyle::Stroke);
+		let mut highlight = Paint::default();
+		highlight.set_color(Color::from_rgb(0x33, 0x66, 0xCC));
+		Self {
+			font: Font::default(),
+			foreground,
+			disabled,
+			background,
+			border,
+			highlight,
+		}
+	}
+}
+
+impl MenuStyle {
+	fn item_height(&self) -> scalar {
+		let metrics = self.font.metrics().1;
+		metrics.bottom - metrics.top + ITEM_PADDING * 2.0
+	}
+
+	fn measure(&self, text: &str) -> scalar {
+		self.font.measure_str(text, Some(&self.foreground)).0
+	}
+
+	/// Draws the text starting at x, vertically centered within the area.
+	fn draw_text(&self, painter: &mut Painter, text: &str, x: scalar, area: Rect, paint: &Paint) {
+		let metrics = self.font.metrics().1;
+		let base_line = area.center_y() - (metrics.bottom - metrics.top) / 2.0 - metrics.top;
+		painter.draw_str(text, Point::new(x, base_line), &self.font, paint);
+	}
+}
+
+/// Opens the menu as popup of the window the owner is part of.
+/// Returns false if the owner is not part of a window.
+fn open_popup(
+	owner: &dyn Widget,
+	menu: WidgetRef<MenuWidget>,
+	placement: PopupPlacement,
+	on_close: impl Fn() + 'static,
+) -> bool {
+	let root = owner.get_root();
+	let root_widget = root.get();
+	match root_widget.as_window() {
+		Some(window) => {
+			window.open_popup(Popup::new(menu, placement).on_close(on_close));
+			true
+		}
+		None => false,
+	}
+}
+
+/// Closes the popup of the widget and all popups above it in the window the owner is part of.
+fn close_popup(owner: &dyn Widget, widget: WidgetRef<dyn Widget>) {
+	let root = owner.get_root();
+	let root_widget = root.get();
+	if let Some(window) = root_widget.as_window() {
+		window.close_popup(&widget);
+	}
+}
+
+fn stroke(paint: &Paint) -> Paint {
+	let mut stroke = paint.clone();
+	stroke.set_style(PaintStyle::Stroke);
+	stroke.set_stroke_width(1.5);
+	stroke
+}
+
+pub struct MenuWidgetState {
+	leaf: LeafState,
+	items: Vec<MenuItem>,
+	style: Rc<MenuStyle>,
+	highlighted: Option<usize>,
+	/// The open submenu and the index of its item.
+	submenu: Option<(usize, WidgetRef<MenuWidget>)>,
+	/// The menu this menu is a submenu of.
+	parent_menu: Option<WidgetWeak<MenuWidget>>,
+}
+
+pub type MenuWidget = WidgetImpl<MenuWidgetState>;
+
+pub struct MenuWidgetBuilder(MenuWidget);
+
+/// A list of menu items shown as popup, normally opened by a [MenuBarWidget] or [ContextMenuWidget].
+///
+/// Submenus open as further popups next to their item,
+/// activating an item closes the menu together with all its parent and submenus.
+/// The menu does not take the keyboard focus,
+/// the widget that opened it forwards the pressed keys to [MenuWidget::handle_key()].
+impl MenuWidget {
+	pub fn new(items: Vec<MenuItem>) -> MenuWidgetBuilder {
+		MenuWidgetBuilder(MenuWidgetState {
+			leaf: Default::default(),
+			items,
+			style: Default::default(),
+			highlighted: None,
+			submenu: None,
+			parent_menu: None,
+		}.into())
+	}
+
+	pub fn highlighted(&self) -> Option<usize> {
+		self.state().highlighted
+	}
+
+	/// Navigates the menu with the key, returns true if the key got used.
+	///
+	/// Up and Down move the highlight, Right opens the submenu of the highlighted item and Left closes it again.
+	/// Enter and Space activate the highlighted item.
+	/// Once an item of the open submenu got highlighted, the keys navigate the submenu.
+	pub fn handle_key(&self, key: VirtualKeyCode) -> bool {
+		let submenu = self.state().submenu.clone();
+		if let Some((_, submenu)) = submenu {
+			let submenu = submenu.get();
+			if submenu.highlighted().is_some() {
+				if submenu.handle_key(key) {
+					return true;
+				}
+				if key == VirtualKeyCode::Left {
+					drop(submenu);
+					self.close_submenu();
+					return true;
+				}
+				return false;
+			}
+		}
+
+		let highlighted = self.state().highlighted;
+		match key {
+			VirtualKeyCode::Up => self.move_highlight(false),
+			VirtualKeyCode::Down => self.move_highlight(true),
+			VirtualKeyCode::Right => match highlighted.and_then(|index| self.open_submenu(index)) {
+				Some(submenu) => submenu.get().move_highlight(true),
+				None => return false,
+			},
+			VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter | VirtualKeyCode::Space => {
+				if let Some(index) = highlighted {
+					self.activate(index);
+				}
+			}
+			_ => return false,
+		}
+		true
+	}
+
+	/// Closes the menu together with the menus it is a submenu of and all its submenus.
+	pub fn close(&self) {
+		let root = self.root_menu();
+		close_popup(self, root);
+	}
+
+	fn root_menu(&self) -> WidgetRef<MenuWidget> {
+		let mut menu = self.self_ref();
+		loop {
+			let parent = menu.get().state().parent_menu.as_ref().and_then(|parent| parent.upgrade());
+			match parent {
+				Some(parent) => menu = parent,
+				None => return menu,
+			}
+		}
+	}
+
+	/// Returns the area of every item in local coordinates.
+	fn row_areas(&self, width: scalar) -> Vec<Rect> {
+		let state = self.state();
+		let item_height = state.style.item_height();
+		let mut top = MENU_PADDING;
+		state
+			.items
+			.iter()
+			.map(|item| {
+				let height = match item.kind {
+					MenuItemKind::Separator => SEPARATOR_HEIGHT,
+					_ => item_height,
+				};
+				let area = Rect::from_xywh(0.0, top, width, height);
+				top += height;
+				area
+			})
+			.collect()
+	}
+
+	/// Returns the index of the item at the position in window coordinates.
+	fn row_at(&self, pos: &Vector2<scalar>) -> Option<usize> {
+		let geometry = self.cached_geometry();
+		if !geometry.contains_absolute_pos(pos) {
+			return None;
+		}
+		let y = pos.y - geometry.absolute_pos().y;
+		self.row_areas(geometry.local_size().x)
+			.iter()
+			.position(|area| y >= area.top && y < area.bottom)
+	}
+
+	/// Highlights the item if it is selectable and closes the submenu of another item.
+	fn set_highlight(&self, index: Option<usize>) {
+		let index = index.filter(|index| self.state().items[*index].is_selectable());
+		if self.state().highlighted == index {
+			return;
+		}
+		let submenu_index = self.state().submenu.as_ref().map(|(submenu_index, _)| *submenu_index);
+		if submenu_index.is_some() && submenu_index != index {
+			self.close_submenu();
+		}
+		self.state_mut().highlighted = index;
+		self.invalidate_paint();
+	}
+
+	/// Highlights the next (or previous) selectable item, wrapping around at both ends.
+	fn move_highlight(&self, forward: bool) {
+		let state = self.state();
+		let count = state.items.len();
+		let order: Vec<usize> = if forward {
+			(0..count).collect()
+		} else {
+			(0..count).rev().collect()
+		};
+		let start = state
+			.highlighted
+			.and_then(|highlighted| order.iter().position(|index| *index == highlighted))
+			.map_or(0, |position| position + 1);
+		let next = order
+			.iter()
+			.cycle()
+			.skip(start)
+			.take(count)
+			.find(|index| state.items[**index].is_selectable())
+			.copied();
+		drop(state);
+		if next.is_some() {
+			self.set_highlight(next);
+		}
+	}
+
+	/// Opens the submenu of the item next to it, returns it if the item is an enabled submenu.
+	fn open_submenu(&self, index: usize) -> Option<WidgetRef<MenuWidget>> {
+		let open = self.state().submenu.clone();
+		match open {
+			Some((open_index, submenu)) if open_index == index => return Some(submenu),
+			Some(_) => self.close_submenu(),
+			None => {}
+		}
+
+		let state = self.state();
+		let items = match &state.items[index] {
+			MenuItem {
+				kind: MenuItemKind::Submenu(items),
+				enabled: true,
+				..
+			} => items.clone(),
+			_ => return None,
+		};
+		let submenu = MenuWidget::new(items).style(state.style.clone()).build();
+		drop(state);
+		submenu.get().state_mut().parent_menu = Some(self.self_weak());
+
+		let geometry = self.cached_geometry();
+		let area = self.row_areas(geometry.local_size().x)[index]
+			.with_offset((geometry.absolute_pos().x, geometry.absolute_pos().y));
+		let placement = PopupPlacement::Area {
+			area,
+			side: PopupSide::Right,
+		};
+		let weak = self.self_weak();
+		let opened = open_popup(self, submenu.clone(), placement, move || {
+			if let Some(menu) = weak.upgrade() {
+				menu.get().state_mut().submenu = None;
+			}
+		});
+		if !opened {
+			return None;
+		}
+		self.state_mut().submenu = Some((index, submenu.clone()));
+		Some(submenu)
+	}
+
+	fn close_submenu(&self) {
+		let submenu = self.state().submenu.clone();
+		if let Some((_, submenu)) = submenu {
+			close_popup(self, submenu);
+		}
+	}
+
+	/// Opens the submenu of the item or changes its checked state, closes the menus and notifies the item.
+	fn activate(&self, index: usize) {
+		let item = self.state().items[index].clone();
+		if !item.is_selectable() {
+			return;
+		}
+		match &item.kind {
+			MenuItemKind::Submenu(_) => {
+				if let Some(submenu) = self.open_submenu(index) {
+					submenu.get().move_highlight(true);
+				}
+				return;
+			}
+			MenuItemKind::Check(checked) => checked.set(!checked.get()),
+			MenuItemKind::Radio { group, checked } => {
+				for other in &self.state().items {
+					if let MenuItemKind::Radio {
+						group: other_group,
+						checked: other_checked,
+					} = &other.kind
+					{
+						if other_group == group {
+							other_checked.set(false);
+						}
+					}
+				}
+				checked.set(true);
+			}
+			MenuItemKind::Action | MenuItemKind::Separator => {}
+		}
+		self.close();
+		if let Some(on_activate) = &item.on_activate {
+			on_activate(&item);
+		}
+	}
+}
+
+impl MenuWidgetBuilder {
+	pub fn style(self, style: Rc<MenuStyle>) -> Self {
+		self.0.state_mut().style = style;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<MenuWidget> {
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for MenuWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let rows = self.row_areas(size.x);
+		let state = self.state();
+		let style = &state.style;
+		let rect = Rect::from_wh(size.x, size.y);
+		painter.draw_rect(rect, &style.background);
+		painter.draw_rect(rect, &style.border);
+
+		for (index, (item, area)) in state.items.iter().zip(rows).enumerate() {
+			if let MenuItemKind::Separator = item.kind {
+				let y = area.center_y();
+				painter.draw_line(
+					Point::new(MENU_PADDING, y),
+					Point::new(size.x - MENU_PADDING, y),
+					&style.border,
+				);
+				continue;
+			}
+			if state.highlighted == Some(index) {
+				painter.draw_rect(area, &style.highlight);
+			}
+			let paint = if item.enabled { &style.foreground } else { &style.disabled };
+			let center = Point::new(CHECK_COLUMN / 2.0, area.center_y());
+			match &item.kind {
+				MenuItemKind::Check(checked) if checked.get() => {
+					let mark = stroke(paint);
+					let bottom = Point::new(center.x - 1.0, center.y + 3.0);
+					painter.draw_line(Point::new(center.x - 4.0, center.y), bottom, &mark);
+					painter.draw_line(bottom, Point::new(center.x + 4.0, center.y - 4.0), &mark);
+				}
+				MenuItemKind::Radio { checked, .. } if checked.get() => {
+					painter.draw_circle(center, 3.0, paint);
+				}
+				MenuItemKind::Submenu(_) => {
+					let arrow = stroke(paint);
+					let tip = Point::new(size.x - ARROW_COLUMN / 2.0 + 2.0, center.y);
+					painter.draw_line(Point::new(tip.x - 4.0, tip.y - 4.0), tip, &arrow);
+					painter.draw_line(tip, Point::new(tip.x - 4.0, tip.y + 4.0), &arrow);
+				}
+				_ => {}
+			}
+			style.draw_text(painter, &item.label, CHECK_COLUMN, area, paint);
+			if let Some(accelerator) = &item.accelerator {
+				let x = size.x - ARROW_COLUMN - style.measure(accelerator);
+				style.draw_text(painter, accelerator, x, area, paint);
+			}
+		}
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let style = &state.style;
+		let mut label_width: scalar = 0.0;
+		let mut accelerator_width: scalar = 0.0;
+		let mut height = MENU_PADDING * 2.0;
+		for item in &state.items {
+			if let MenuItemKind::Separator = item.kind {
+				height += SEPARATOR_HEIGHT;
+				continue;
+			}
+			height += style.item_height();
+			label_width = label_width.max(style.measure(&item.label));
+			if let Some(accelerator) = &item.accelerator {
+				accelerator_width = accelerator_width.max(style.measure(accelerator) + ACCELERATOR_GAP);
+			}
+		}
+		Vector2::new(CHECK_COLUMN + label_width + accelerator_width + ARROW_COLUMN, height)
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnCursorMove { pos, .. } => {
+				let row = self.row_at(pos);
+				if row.is_some() {
+					self.set_highlight(row);
+					let highlighted = self.state().highlighted;
+					if let Some(index) = highlighted {
+						let is_submenu = matches!(self.state().items[index].kind, MenuItemKind::Submenu(_));
+						if is_submenu {
+							self.open_submenu(index);
+						}
+					}
+				}
+				Reply::handled()
+			}
+			WidgetEvent::OnMouseButtonDown {
+				button: MouseButton::Left,
+				..
+			}
+			| WidgetEvent::OnMouseButtonUp {
+				button: MouseButton::Left,
+				..
+			} => Reply::handled(),
+			WidgetEvent::OnClick {
+				button: MouseButton::Left,
+				pos,
+				..
+			} => {
+				if let Some(index) = self.row_at(pos) {
+					self.activate(index);
+				}
+				Reply::handled()
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for MenuWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
+
+pub struct MenuBarWidgetState {
+	leaf: LeafState,
+	menus: Vec<(String, Vec<MenuItem>)>,
+	style: Rc<MenuStyle>,
+	hovered: Option<usize>,
+	/// The open menu and the index of its title.
+	open: Option<(usize, WidgetRef<MenuWidget>)>,
+}
+
+pub type MenuBarWidget = WidgetImpl<MenuBarWidgetState>;
+
+pub struct MenuBarWidgetBuilder(MenuBarWidget);
+
+/// A row of menu titles, pressing a title opens its menu below it.
+///
+/// While a menu is open, hovering another title or pressing Left or Right switches to its menu.
+/// Opening a menu focuses the bar, so the arrow keys navigate the menu and Enter activates the highlighted item.
+impl MenuBarWidget {
+	pub fn new() -> MenuBarWidgetBuilder {
+		MenuBarWidgetBuilder(MenuBarWidgetState {
+			leaf: Default::default(),
+			menus: Vec::new(),
+			style: Default::default(),
+			hovered: None,
+			open: None,
+		}.into())
+	}
+
+	pub fn set_menus(&self, menus: Vec<(String, Vec<MenuItem>)>) {
+		self.close_menu();
+		self.state_mut().menus = menus;
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+		self.invalidate_paint();
+	}
+
+	/// Opens the menu of the title with the index, closing the open menu.
+	pub fn open_menu(&self, index: usize) -> Option<WidgetRef<MenuWidget>> {
+		let open = self.state().open.clone();
+		match open {
+			Some((open_index, menu)) if open_index == index => return Some(menu),
+			Some(_) => self.close_menu(),
+			None => {}
+		}
+
+		let state = self.state();
+		let items = state.menus.get(index)?.1.clone();
+		let menu = MenuWidget::new(items).style(state.style.clone()).build();
+		drop(state);
+
+		let geometry = self.cached_geometry();
+		let area = self.title_areas(geometry.local_size().y)[index]
+			.with_offset((geometry.absolute_pos().x, geometry.absolute_pos().y));
+		let placement = PopupPlacement::Area {
+			area,
+			side: PopupSide::Below,
+		};
+		let weak = self.self_weak();
+		let opened = open_popup(self, menu.clone(), placement, move || {
+			if let Some(bar) = weak.upgrade() {
+				bar.get().state_mut().open = None;
+				bar.get().invalidate_paint();
+			}
+		});
+		if !opened {
+			return None;
+		}
+		self.state_mut().open = Some((index, menu.clone()));
+		self.invalidate_paint();
+		Some(menu)
+	}
+
+	pub fn close_menu(&self) {
+		let open = self.state().open.clone();
+		if let Some((_, menu)) = open {
+			close_popup(self, menu);
+		}
+	}
+
+	/// Returns the area of every title in local coordinates.
+	fn title_areas(&self, height: scalar) -> Vec<Rect> {
+		let state = self.state();
+		let mut left = 0.0;
+		state
+			.menus
+			.iter()
+			.map(|(title, _)| {
+				let width = state.style.measure(title) + TITLE_PADDING * 2.0;
+				let area = Rect::from_xywh(left, 0.0, width, height);
+				left += width;
+				area
+			})
+			.collect()
+	}
+
+	/// Returns the index of the title at the position in window coordinates.
+	fn title_at(&self, pos: &Vector2<scalar>) -> Option<usize> {
+		let geometry = self.cached_geometry();
+		if !geometry.contains_absolute_pos(pos) {
+			return None;
+		}
+		let x = pos.x - geometry.absolute_pos().x;
+		self.title_areas(geometry.local_size().y)
+			.iter()
+			.position(|area| x >= area.left && x < area.right)
+	}
+}
+
+impl MenuBarWidgetBuilder {
+	/// Appends a title with the items of its menu.
+	pub fn menu(self, title: impl Into<String>, items: Vec<MenuItem>) -> Self {
+		self.0.state_mut().menus.push((title.into(), items));
+		self
+	}
+
+	/// Sets the style of the bar and its menus.
+	pub fn style(self, style: MenuStyle) -> Self {
+		self.0.state_mut().style = Rc::new(style);
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<MenuBarWidget> {
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for MenuBarWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let titles = self.title_areas(size.y);
+		let state = self.state();
+		let style = &state.style;
+		painter.draw_rect(Rect::from_wh(size.x, size.y), &style.background);
+		let active = state.open.as_ref().map(|(index, _)| *index).or(state.hovered);
+		for (index, ((title, _), area)) in state.menus.iter().zip(titles).enumerate() {
+			if active == Some(index) {
+				painter.draw_rect(area, &style.highlight);
+			}
+			style.draw_text(painter, title, area.left + TITLE_PADDING, area, &style.foreground);
+		}
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let width: scalar = state
+			.menus
+			.iter()
+			.map(|(title, _)| state.style.measure(title) + TITLE_PADDING * 2.0)
+			.sum();
+		Vector2::new(width, state.style.item_height())
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Left,
+				pos,
+				..
+			} => match self.title_at(pos) {
+				Some(index) => {
+					self.open_menu(index);
+					// captured until the release, so releasing on the opened menu does not move the focus away and close it
+					Reply::handled()
+						.take_focus(WidgetFocusChange::KeyboardList(vec![*mouse]))
+						.capture_cursor(*mouse)
+				}
+				None => Reply::unhandled(),
+			},
+			WidgetEvent::OnMouseButtonUp {
+				mouse,
+				button: MouseButton::Left,
+				..
+			} => Reply::handled().release_cursor(*mouse),
+			WidgetEvent::OnCursorMove { pos, .. } => {
+				let hovered = self.title_at(pos);
+				if self.state().hovered != hovered {
+					self.state_mut().hovered = hovered;
+					self.invalidate_paint();
+				}
+				let open = self.state().open.is_some();
+				match hovered {
+					Some(index) if open => {
+						self.open_menu(index);
+						Reply::handled()
+					}
+					_ => Reply::unhandled(),
+				}
+			}
+			WidgetEvent::OnCursorLeave { .. } => {
+				self.state_mut().hovered = None;
+				self.invalidate_paint();
+				Reply::handled()
+			}
+			WidgetEvent::OnKeyDown { key: Some(key), .. } => {
+				let open = self.state().open.clone();
+				let (index, menu) = match open {
+					Some(open) => open,
+					None => return Reply::unhandled(),
+				};
+				if menu.get().handle_key(*key) {
+					return Reply::handled();
+				}
+				let count = self.state().menus.len();
+				let next = match key {
+					VirtualKeyCode::Left => (index + count - 1) % count,
+					VirtualKeyCode::Right => (index + 1) % count,
+					_ => return Reply::unhandled(),
+				};
+				if let Some(menu) = self.open_menu(next) {
+					menu.get().move_highlight(true);
+				}
+				Reply::handled()
+			}
+			WidgetEvent::OnUnfocus { .. } => {
+				self.close_menu();
+				Reply::handled()
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for MenuBarWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
+
+pub struct ContextMenuWidgetState {
+	panel: PanelState,
+	child: WidgetRef<dyn Widget>,
+	items: Vec<MenuItem>,
+	style: Rc<MenuStyle>,
+	open: Option<WidgetRef<MenuWidget>>,
+}
+
+pub type ContextMenuWidget = WidgetImpl<ContextMenuWidgetState>;
+
+pub struct ContextMenuWidgetBuilder(WidgetRef<ContextMenuWidget>);
+
+/// Opens a menu at the cursor when the child gets pressed with the right mouse button
+/// and does not handle the press itself.
+///
+/// Opening the menu focuses this widget, so the arrow keys navigate the menu and Enter activates the highlighted item.
+impl ContextMenuWidget {
+	pub fn new(child: WidgetRef<dyn Widget>, items: Vec<MenuItem>) -> ContextMenuWidgetBuilder {
+		ContextMenuWidgetBuilder(WidgetRef::new(ContextMenuWidgetState {
+			panel: Default::default(),
+			child,
+			items,
+			style: Default::default(),
+			open: None,
+		}.into()))
+	}
+
+	pub fn set_items(&self, items: Vec<MenuItem>) {
+		self.state_mut().items = items;
+	}
+
+	/// Opens the menu at the position in window coordinates, closing the open menu.
+	pub fn open_menu(&self, pos: Vector2<scalar>) -> Option<WidgetRef<MenuWidget>> {
+		self.close_menu();
+		let state = self.state();
+		let menu = MenuWidget::new(state.items.clone()).style(state.style.clone()).build();
+		drop(state);
+
+		let weak = self.self_weak();
+		let opened = open_popup(self, menu.clone(), PopupPlacement::At(pos), move || {
+			if let Some(context_menu) = weak.upgrade() {
+				context_menu.get().state_mut().open = None;
+			}
+		});
+		if !opened {
+			return None;
+		}
+		self.state_mut().open = Some(menu.clone());
+		Some(menu)
+	}
+
+	pub fn close_menu(&self) {
+		let open = self.state().open.clone();
+		if let Some(menu) = open {
+			close_popup(self, menu);
+		}
+	}
+}
+
+impl ContextMenuWidgetBuilder {
+	pub fn style(self, style: MenuStyle) -> Self {
+		self.0.get().state_mut().style = Rc::new(style);
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<ContextMenuWidget> {
+		self.0.get().state().child.get().set_parent(Some(self.0.clone()));
+		self.0
+	}
+}
+
+impl Widget for ContextMenuWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.panel.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.panel.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		self.panel_paint(geometry, layer, painter)
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		self.state().child.get().get_desired_size()
+	}
+
+	fn get_children(&self) -> Children {
+		vec![self.state().child.clone()]
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.panel_arrange_children(geometry);
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.panel_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Right,
+				pos,
+				..
+			} => {
+				self.open_menu(*pos);
+				// the menu opens at the cursor, so the release would hit it and move the focus away, closing it again
+				Reply::handled()
+					.take_focus(WidgetFocusChange::KeyboardList(vec![*mouse]))
+					.capture_cursor(*mouse)
+			}
+			WidgetEvent::OnMouseButtonUp {
+				mouse,
+				button: MouseButton::Right,
+				..
+			} => Reply::handled().release_cursor(*mouse),
+			WidgetEvent::OnKeyDown { key: Some(key), .. } => {
+				let open = self.state().open.clone();
+				match open {
+					Some(menu) if menu.get().handle_key(*key) => Reply::handled(),
+					_ => Reply::unhandled(),
+				}
+			}
+			WidgetEvent::OnUnfocus { .. } => {
+				self.close_menu();
+				Reply::handled()
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.panel_cached_geometry()
+	}
+}
+
+impl PanelWidget for ContextMenuWidget {
+	fn panel_state(&self) -> Ref<PanelState> {
+		self.widget_state(|v| &v.panel)
+	}
+
+	fn panel_state_mut(&self) -> RefMut<PanelState> {
+		self.widget_state_mut(|v| &mut v.panel)
+	}
+
+	fn rearrange_children(&self, geometry: Geometry) -> Vec<WidgetArrangement> {
+		let state = self.state();
+		vec![geometry.child_widget(state.child.clone(), Vector2::new(0.0, 0.0), geometry.local_size())]
+	}
+}
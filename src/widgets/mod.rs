@@ -1,26 +1,54 @@
 mod box_panel;
+mod button;
+mod check_box;
+mod handle_drag;
+mod image;
 mod layout;
 mod linear_panel;
+mod list_view;
+mod menu;
 mod overlay_panel;
 mod panel;
+mod popup;
+mod pressable;
+mod radio_button;
 mod scroll_bar;
 mod scroll_panel;
+mod slider;
+mod spin_box;
 mod test_widget;
+mod text_area;
 mod text_block;
 mod text_edit;
+mod toggle_switch;
+mod tooltip;
+mod tree_view;
 mod widget;
 mod window;
 mod leaf_widget;
 
 pub use box_panel::*;
+pub use button::*;
+pub use check_box::*;
+pub use image::*;
 pub use layout::*;
 pub use linear_panel::*;
+pub use list_view::*;
+pub use menu::*;
 pub use overlay_panel::*;
 pub use panel::*;
+pub use popup::*;
+pub use radio_button::*;
 pub use scroll_bar::*;
 pub use scroll_panel::*;
+pub use slider::*;
+pub use spin_box::*;
 pub use test_widget::*;
+pub use text_area::*;
 pub use text_block::*;
 pub use text_edit::*;
+pub use toggle_switch::*;
+pub use tooltip::*;
+pub use tree_view::*;
 pub use widget::*;
 pub use window::*;
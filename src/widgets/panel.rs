@@ -28,9 +28,8 @@ pub trait PanelWidget: Widget {
 				child.geometry.local_pos().x,
 				child.geometry.local_pos().y,
 			));
-			child.widget.get().paint(child.geometry, layer, painter);
+			layer = child.widget.get().paint_cached(child.geometry, layer, painter);
 			painter.restore();
-			layer += 1;
 		}
 		layer
 	}
@@ -42,14 +41,17 @@ pub trait PanelWidget: Widget {
 	///
 	/// # Default Implementation
 	/// Arranges the children and stores the new arrangement as well as the new geometry in the widget state.
+	/// If the geometry changed, the old and new area get damaged.
 	fn panel_arrange_children(&self, geometry: Geometry){
 		let widgets = self.rearrange_children(geometry);
 		for widget in &widgets {
 			widget.widget.get().arrange_children(widget.geometry);
 		}
 		let mut state = self.panel_state_mut();
-		state.cached_geometry = geometry;
+		let old_geometry = std::mem::replace(&mut state.cached_geometry, geometry);
 		state.arranged_children = widgets;
+		drop(state);
+		self.damage_geometry_change(old_geometry, geometry);
 	}
 
 	/// Panel Implementation of [Widget::get_arranged_children()]
@@ -0,0 +1,457 @@
+use std::rc::Rc;
+use crate::{
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{Arrangements, Widget, WidgetArrangement},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Color, Color4f, Paint, Rect, Vector};
+
+/// The side of the anchor a popup gets placed at.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PopupSide {
+	/// Below the anchor, left aligned, like a dropdown.
+	Below,
+	Above,
+	/// Right of the anchor, top aligned, like a submenu.
+	Right,
+	Left,
+}
+
+/// Where a popup gets placed within the window.
+/// The popup always gets moved to stay inside the window.
+#[derive(Clone)]
+pub enum PopupPlacement {
+	/// The top left corner at the given position in window coordinates, like a context menu at the cursor.
+	At(Vector2<scalar>),
+	/// Next to the anchor widget at the given side,
+	/// flipped to the opposite side if there is not enough space.
+	Anchor {
+		widget: WidgetRef<dyn Widget>,
+		side: PopupSide,
+	},
+	/// Like [PopupPlacement::Anchor] but next to an area in window coordinates,
+	/// e.g. a single item of a widget.
+	Area {
+		area: Rect,
+		side: PopupSide,
+	},
+	/// Centered in the window, like a dialog.
+	Center,
+}
+
+/// A widget shown above the content of a window, see [crate::widgets::Window::open_popup()].
+#[derive(Clone)]
+pub struct Popup {
+	widget: WidgetRef<dyn Widget>,
+	placement: PopupPlacement,
+	modal: bool,
+	light_dismiss: bool,
+	on_close: Option<Rc<dyn Fn()>>,
+}
+
+impl Popup {
+	/// Creates a popup that gets closed by clicking outside of it or pressing Escape.
+	pub fn new(widget: WidgetRef<dyn Widget>, placement: PopupPlacement) -> Self {
+		Self {
+			widget,
+			placement,
+			modal: false,
+			light_dismiss: true,
+			on_close: None,
+		}
+	}
+
+	/// Makes the popup modal, blocking all input to the popups and content below it.
+	/// Modal popups dim the window below them and do not get light dismissed.
+	pub fn modal(mut self) -> Self {
+		self.modal = true;
+		self.light_dismiss = false;
+		self
+	}
+
+	/// Sets if the popup gets closed by clicking outside of it or pressing Escape.
+	pub fn light_dismiss(mut self, light_dismiss: bool) -> Self {
+		self.light_dismiss = light_dismiss;
+		self
+	}
+
+	/// Called after the popup got closed, no matter why.
+	pub fn on_close(mut self, on_close: impl Fn() + 'static) -> Self {
+		self.on_close = Some(Rc::new(on_close));
+		self
+	}
+
+	pub fn widget(&self) -> &WidgetRef<dyn Widget> {
+		&self.widget
+	}
+
+	pub fn is_modal(&self) -> bool {
+		self.modal
+	}
+
+	/// Returns the position and size of the popup within a window of the given size.
+	fn place(&self, window_size: Vector2<scalar>) -> (Vector2<scalar>, Vector2<scalar>) {
+		let size = self.widget.get().get_desired_size();
+		let size = Vector2::new(size.x.min(window_size.x), size.y.min(window_size.y));
+		let mut pos = match &self.placement {
+			PopupPlacement::At(pos) => *pos,
+			PopupPlacement::Center => (window_size - size) / 2.0,
+			PopupPlacement::Anchor { widget, side } => {
+				let anchor = widget.get().cached_geometry();
+				let area = Rect::from_xywh(
+					anchor.absolute_pos().x,
+					anchor.absolute_pos().y,
+					anchor.local_size().x,
+					anchor.local_size().y,
+				);
+				place_next_to(area, *side, size, window_size)
+			}
+			PopupPlacement::Area { area, side } => place_next_to(*area, *side, size, window_size),
+		};
+		pos.x = pos.x.min(window_size.x - size.x).max(0.0);
+		pos.y = pos.y.min(window_size.y - size.y).max(0.0);
+		(pos, size)
+	}
+}
+
+/// Returns the position of a popup of the given size next to the area,
+/// on the opposite side if it does not fit on the requested one.
+fn place_next_to(area: Rect, side: PopupSide, size: Vector2<scalar>, window_size: Vector2<scalar>) -> Vector2<scalar> {
+	let above = area.top - size.y;
+	let left = area.left - size.x;
+	match side {
+		PopupSide::Below if area.bottom + size.y > window_size.y && above >= 0.0 => Vector2::new(area.left, above),
+		PopupSide::Below => Vector2::new(area.left, area.bottom),
+		PopupSide::Above if above < 0.0 && area.bottom + size.y <= window_size.y => Vector2::new(area.left, area.bottom),
+		PopupSide::Above => Vector2::new(area.left, above),
+		PopupSide::Right if area.right + size.x > window_size.x && left >= 0.0 => Vector2::new(left, area.top),
+		PopupSide::Right => Vector2::new(area.right, area.top),
+		PopupSide::Left if left < 0.0 && area.right + size.x <= window_size.x => Vector2::new(area.right, area.top),
+		PopupSide::Left => Vector2::new(left, area.top),
+	}
+}
+
+#[derive(Clone)]
+struct PopupEntry {
+	popup: Popup,
+	arrangement: Option<WidgetArrangement>,
+}
+
+/// The popups of a window, the last popup is on top.
+///
+/// Above all popups there are overlays, widgets that never receive any input like tooltips,
+/// see [crate::widgets::Window::show_overlay()].
+#[derive(Default, Clone)]
+pub struct PopupStack {
+	popups: Vec<PopupEntry>,
+	overlays: Vec<WidgetArrangement>,
+}
+
+impl PopupStack {
+	pub fn is_empty(&self) -> bool {
+		self.popups.is_empty()
+	}
+
+	/// Returns the widgets of all popups, from bottom to top.
+	pub fn widgets(&self) -> Vec<WidgetRef<dyn Widget>> {
+		self.popups.iter().map(|entry| entry.popup.widget.clone()).collect()
+	}
+
+	pub fn contains(&self, widget: &WidgetRef<dyn Widget>) -> bool {
+		self.popups.iter().any(|entry| &entry.popup.widget == widget)
+	}
+
+	pub fn push(&mut self, popup: Popup) {
+		self.popups.push(PopupEntry {
+			popup,
+			arrangement: None,
+		});
+	}
+
+	/// Removes the popup of the widget and all popups above it, returns the removed popups.
+	pub fn remove(&mut self, widget: &WidgetRef<dyn Widget>) -> Vec<Popup> {
+		match self.popups.iter().position(|entry| &entry.popup.widget == widget) {
+			Some(index) => self.popups.drain(index..).map(|entry| entry.popup).collect(),
+			None => Vec::new(),
+		}
+	}
+
+	/// Removes the light dismissable popups from the top that do not contain the position,
+	/// until a popup contains it or is not light dismissable.
+	pub fn dismiss_outside(&mut self, pos: &Vector2<scalar>) -> Vec<Popup> {
+		let keep = self
+			.popups
+			.iter()
+			.rposition(|entry| {
+				!entry.popup.light_dismiss
+					|| entry.arrangement.as_ref().is_some_and(|arrangement| arrangement.geometry.contains_absolute_pos(pos))
+			})
+			.map_or(0, |index| index + 1);
+		self.popups.drain(keep..).map(|entry| entry.popup).collect()
+	}
+
+	/// Removes the popup on top if it is light dismissable.
+	pub fn dismiss_top(&mut self) -> Option<Popup> {
+		match self.popups.last() {
+			Some(entry) if entry.popup.light_dismiss => self.popups.pop().map(|entry| entry.popup),
+			_ => None,
+		}
+	}
+
+	/// Returns the widget of the modal popup closest to the top.
+	pub fn top_modal(&self) -> Option<WidgetRef<dyn Widget>> {
+		self.popups
+			.iter()
+			.rev()
+			.find(|entry| entry.popup.modal)
+			.map(|entry| entry.popup.widget.clone())
+	}
+
+	/// Places all popups within the geometry of the window.
+	/// The popups still have to arrange their children, before the arrangements get stored with [PopupStack::set_arrangements()].
+	pub fn place(&self, geometry: Geometry) -> Arrangements {
+		self.popups
+			.iter()
+			.map(|entry| {
+				let (pos, size) = entry.popup.place(geometry.local_size());
+				geometry.child_widget(entry.popup.widget.clone(), pos, size)
+			})
+			.collect()
+	}
+
+	pub fn set_arrangements(&mut self, arrangements: Arrangements) {
+		for entry in &mut self.popups {
+			entry.arrangement = arrangements
+				.iter()
+				.find(|arrangement| arrangement.widget == entry.popup.widget)
+				.cloned();
+		}
+	}
+
+	/// Returns the arrangements of all popups followed by the overlays.
+	pub fn arrangements(&self) -> Arrangements {
+		self.popups
+			.iter()
+			.filter_map(|entry| entry.arrangement.clone())
+			.chain(self.overlays.iter().cloned())
+			.collect()
+	}
+
+	pub fn overlays(&self) -> &[WidgetArrangement] {
+		&self.overlays
+	}
+
+	/// Adds the overlay on top of all other overlays, or replaces the arrangement if its widget already is one.
+	pub fn set_overlay(&mut self, overlay: WidgetArrangement) {
+		match self.overlays.iter_mut().find(|arrangement| arrangement.widget == overlay.widget) {
+			Some(arrangement) => *arrangement = overlay,
+			None => self.overlays.push(overlay),
+		}
+	}
+
+	/// Removes the overlay of the widget, returns false if the widget is no overlay.
+	pub fn remove_overlay(&mut self, widget: &WidgetRef<dyn Widget>) -> bool {
+		let len = self.overlays.len();
+		self.overlays.retain(|arrangement| &arrangement.widget != widget);
+		self.overlays.len() != len
+	}
+
+	/// Returns the popup that gets hit at the position, hiding everything below it.
+	/// If no popup contains the position, the outer Option is None if the content below can be hit
+	/// and Some(None) if a modal popup blocks it.
+	pub fn hit_test(&self, pos: &Vector2<scalar>) -> Option<Option<WidgetArrangement>> {
+		for entry in self.popups.iter().rev() {
+			if let Some(arrangement) = &entry.arrangement {
+				if arrangement.geometry.contains_absolute_pos(pos) {
+					return Some(Some(arrangement.clone()));
+				}
+			}
+			if entry.popup.modal {
+				return Some(None);
+			}
+		}
+		None
+	}
+
+	/// Paints all popups above each other, modal popups dim everything below them.
+	/// The overlays get painted on top.
+	pub fn paint(&self, geometry: Geometry, mut layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		for entry in &self.popups {
+			let arrangement = match &entry.arrangement {
+				Some(arrangement) => arrangement,
+				None => continue,
+			};
+			if entry.popup.modal {
+				let dim = Paint::new(Color4f::from(Color::from_argb(0x80, 0, 0, 0)), None);
+				painter.draw_rect(Rect::from_wh(size.x, size.y), &dim);
+				layer += 1;
+			}
+			painter.save();
+			painter.translate(Vector::new(
+				arrangement.geometry.local_pos().x,
+				arrangement.geometry.local_pos().y,
+			));
+			layer = arrangement.widget.get().paint_cached(arrangement.geometry, layer, painter);
+			painter.restore();
+		}
+		for overlay in &self.overlays {
+			painter.save();
+			painter.translate(Vector::new(
+				overlay.geometry.local_pos().x,
+				overlay.geometry.local_pos().y,
+			));
+			layer = overlay.widget.get().paint_cached(overlay.geometry, layer, painter);
+			painter.restore();
+		}
+		layer
+	}
+}
+
+/// Notifies the closed popups, the window has to be rearranged afterwards.
+pub(crate) fn popups_closed(popups: Vec<Popup>) {
+	for popup in popups {
+		popup.widget.get().set_parent(None);
+		if let Some(on_close) = &popup.on_close {
+			on_close();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		util::{Geometry, WidgetRef},
+		widgets::{ImageWidget, Widget},
+	};
+	use super::{place_next_to, Popup, PopupPlacement, PopupSide, PopupStack};
+	use cgmath::Vector2;
+	use skia_safe::{Rect, Surface};
+
+	/// A widget desiring the given size.
+	fn block(width: i32, height: i32) -> WidgetRef<dyn Widget> {
+		let image = Surface::new_raster_n32_premul((width, height)).unwrap().image_snapshot();
+		ImageWidget::new(image).build()
+	}
+
+	fn window_geometry() -> Geometry {
+		Geometry::new(
+			Vector2::new(0.0, 0.0),
+			Vector2::new(100.0, 100.0),
+			Vector2::new(0.0, 0.0),
+			Vector2::new(1.0, 1.0),
+		)
+	}
+
+	fn at(x: f32, y: f32) -> Popup {
+		Popup::new(block(20, 20), PopupPlacement::At(Vector2::new(x, y)))
+	}
+
+	/// Places the popups in a window of 100x100 pixels.
+	fn stack(popups: &[&Popup]) -> PopupStack {
+		let mut stack = PopupStack::default();
+		for popup in popups {
+			stack.push((*popup).clone());
+		}
+		let arrangements = stack.place(window_geometry());
+		stack.set_arrangements(arrangements);
+		stack
+	}
+
+	fn placed(side: PopupSide, left: f32, top: f32, right: f32, bottom: f32) -> Vector2<f32> {
+		let window = Vector2::new(100.0, 100.0);
+		place_next_to(Rect::new(left, top, right, bottom), side, Vector2::new(20.0, 20.0), window)
+	}
+
+	#[test]
+	fn place_next_to_uses_requested_side_if_it_fits() {
+		assert_eq!(placed(PopupSide::Below, 10.0, 10.0, 30.0, 20.0), Vector2::new(10.0, 20.0));
+		assert_eq!(placed(PopupSide::Above, 10.0, 50.0, 30.0, 60.0), Vector2::new(10.0, 30.0));
+		assert_eq!(placed(PopupSide::Right, 10.0, 10.0, 30.0, 20.0), Vector2::new(30.0, 10.0));
+		assert_eq!(placed(PopupSide::Left, 50.0, 10.0, 70.0, 20.0), Vector2::new(30.0, 10.0));
+	}
+
+	#[test]
+	fn place_next_to_flips_to_opposite_side() {
+		assert_eq!(placed(PopupSide::Below, 10.0, 85.0, 30.0, 95.0), Vector2::new(10.0, 65.0));
+		assert_eq!(placed(PopupSide::Above, 10.0, 5.0, 30.0, 15.0), Vector2::new(10.0, 15.0));
+		assert_eq!(placed(PopupSide::Right, 70.0, 10.0, 90.0, 20.0), Vector2::new(50.0, 10.0));
+		assert_eq!(placed(PopupSide::Left, 5.0, 10.0, 25.0, 20.0), Vector2::new(25.0, 10.0));
+	}
+
+	#[test]
+	fn place_next_to_keeps_side_if_neither_fits() {
+		assert_eq!(placed(PopupSide::Below, 10.0, 10.0, 30.0, 90.0), Vector2::new(10.0, 90.0));
+		assert_eq!(placed(PopupSide::Left, 10.0, 10.0, 90.0, 20.0), Vector2::new(-10.0, 10.0));
+	}
+
+	#[test]
+	fn place_clamps_popup_into_window() {
+		let window = Vector2::new(100.0, 100.0);
+
+		assert_eq!(at(90.0, 95.0).place(window), (Vector2::new(80.0, 80.0), Vector2::new(20.0, 20.0)));
+		assert_eq!(at(-5.0, -5.0).place(window).0, Vector2::new(0.0, 0.0));
+		let center = Popup::new(block(20, 10), PopupPlacement::Center);
+		assert_eq!(center.place(window).0, Vector2::new(40.0, 45.0));
+		// an area near the bottom right corner that has no space on either side
+		let area = Popup::new(
+			block(20, 20),
+			PopupPlacement::Area {
+				area: Rect::new(10.0, 10.0, 30.0, 90.0),
+				side: PopupSide::Below,
+			},
+		);
+		assert_eq!(area.place(window).0, Vector2::new(10.0, 80.0));
+		let wide = Popup::new(block(150, 20), PopupPlacement::At(Vector2::new(30.0, 0.0)));
+		assert_eq!(wide.place(window), (Vector2::new(0.0, 0.0), Vector2::new(100.0, 20.0)));
+	}
+
+	#[test]
+	fn dismiss_outside_removes_popups_above_the_hit_one() {
+		let (bottom, middle, top) = (at(0.0, 0.0), at(40.0, 40.0), at(80.0, 80.0));
+		let mut popups = stack(&[&bottom, &middle, &top]);
+
+		let dismissed = popups.dismiss_outside(&Vector2::new(45.0, 45.0));
+
+		assert!(dismissed.len() == 1 && dismissed[0].widget() == top.widget());
+		assert!(popups.widgets() == vec![bottom.widget().clone(), middle.widget().clone()]);
+		assert_eq!(popups.dismiss_outside(&Vector2::new(5.0, 5.0)).len(), 1);
+		assert_eq!(popups.dismiss_outside(&Vector2::new(70.0, 5.0)).len(), 1);
+		assert!(popups.is_empty());
+	}
+
+	#[test]
+	fn dismiss_outside_stops_at_popup_without_light_dismiss() {
+		let (bottom, middle, top) = (at(0.0, 0.0), at(40.0, 40.0).light_dismiss(false), at(80.0, 80.0));
+		let mut popups = stack(&[&bottom, &middle, &top]);
+
+		let dismissed = popups.dismiss_outside(&Vector2::new(5.0, 5.0));
+
+		assert!(dismissed.len() == 1 && dismissed[0].widget() == top.widget());
+		assert!(popups.widgets() == vec![bottom.widget().clone(), middle.widget().clone()]);
+	}
+
+	#[test]
+	fn hit_test_finds_top_popup() {
+		let (bottom, top) = (at(0.0, 0.0), at(10.0, 10.0));
+		let popups = stack(&[&bottom, &top]);
+
+		let hit = |x, y| popups.hit_test(&Vector2::new(x, y)).map(|hit| hit.map(|arrangement| arrangement.widget));
+		assert!(hit(15.0, 15.0) == Some(Some(top.widget().clone())));
+		assert!(hit(5.0, 5.0) == Some(Some(bottom.widget().clone())));
+		assert!(hit(50.0, 50.0).is_none());
+	}
+
+	#[test]
+	fn hit_test_blocks_below_modal_popup() {
+		let (bottom, modal) = (at(0.0, 0.0), at(40.0, 40.0).modal());
+		let popups = stack(&[&bottom, &modal]);
+
+		let hit = |x, y| popups.hit_test(&Vector2::new(x, y)).map(|hit| hit.map(|arrangement| arrangement.widget));
+		assert!(hit(45.0, 45.0) == Some(Some(modal.widget().clone())));
+		assert!(hit(5.0, 5.0) == Some(None));
+		assert!(hit(90.0, 90.0) == Some(None));
+		assert!(popups.top_modal() == Some(modal.widget().clone()));
+	}
+}
@@ -0,0 +1,150 @@
+use std::cell::{Ref, RefMut};
+use crate::{
+	events::{input::MouseButton, Reply, WidgetEvent, WidgetFocusChange},
+	widgets::leaf_widget::LeafWidget,
+};
+use winit::event::VirtualKeyCode;
+
+/// Interaction state of widgets that get activated by clicking them
+/// or by pressing Space or Enter while they are focused.
+#[derive(Clone)]
+pub struct PressableState {
+	pub enabled: bool,
+	pub hovered: bool,
+	/// Pressed by the left mouse button or Space, the widget gets activated when released.
+	pub pressed: bool,
+	pub focused: bool,
+}
+
+impl Default for PressableState {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			hovered: false,
+			pressed: false,
+			focused: false,
+		}
+	}
+}
+
+pub trait PressableWidget: LeafWidget {
+	fn pressable_state(&self) -> Ref<PressableState>;
+	fn pressable_state_mut(&self) -> RefMut<PressableState>;
+
+	/// Called when the widget got clicked or activated with the keyboard.
+	fn activate(&self);
+
+	fn pressable_is_enabled(&self) -> bool {
+		self.pressable_state().enabled
+	}
+
+	/// Disabled widgets ignore all input and can not be focused.
+	fn pressable_set_enabled(&self, enabled: bool) {
+		let mut state = self.pressable_state_mut();
+		if state.enabled == enabled {
+			return;
+		}
+		state.enabled = enabled;
+		state.pressed = false;
+		drop(state);
+		self.widget_state_mut().focusable = enabled;
+		self.invalidate_paint();
+	}
+
+	/// Pressable implementation of [Widget::on_event()]
+	///
+	/// # Default Implementation
+	/// Tracks hover, press and focus and calls [PressableWidget::activate()]
+	/// when a press of the left mouse button gets released over the widget,
+	/// Space gets released or Enter gets pressed.
+	fn pressable_on_event(&self, event: &WidgetEvent) -> Reply {
+		let enabled = self.pressable_state().enabled;
+		let reply = match event {
+			WidgetEvent::OnCursorEnter { .. } => {
+				self.pressable_state_mut().hovered = true;
+				Reply::handled()
+			}
+			WidgetEvent::OnCursorLeave { .. } => {
+				self.pressable_state_mut().hovered = false;
+				Reply::handled()
+			}
+			// moves only reach the widget while hovered or while it captures the cursor
+			WidgetEvent::OnCursorMove { pos, .. } => {
+				let hovered = self.cached_geometry().contains_absolute_pos(pos);
+				let mut state = self.pressable_state_mut();
+				if state.hovered == hovered {
+					return Reply::unhandled();
+				}
+				state.hovered = hovered;
+				Reply::handled()
+			}
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Left,
+				..
+			} if enabled => {
+				self.pressable_state_mut().pressed = true;
+				Reply::handled()
+					.take_focus(WidgetFocusChange::KeyboardList(vec![*mouse]))
+					.capture_cursor(*mouse)
+			}
+			WidgetEvent::OnMouseButtonUp {
+				mouse,
+				button: MouseButton::Left,
+				pos,
+				..
+			} => {
+				let hovered = self.cached_geometry().contains_absolute_pos(pos);
+				let mut state = self.pressable_state_mut();
+				let pressed = std::mem::take(&mut state.pressed);
+				state.hovered = hovered;
+				drop(state);
+				if !pressed {
+					return Reply::unhandled();
+				}
+				if hovered && enabled {
+					self.activate();
+				}
+				Reply::handled().release_cursor(*mouse)
+			}
+			WidgetEvent::OnKeyDown {
+				key: Some(VirtualKeyCode::Space),
+				..
+			} if enabled => {
+				self.pressable_state_mut().pressed = true;
+				Reply::handled()
+			}
+			WidgetEvent::OnKeyUp {
+				key: Some(VirtualKeyCode::Space),
+				..
+			} => {
+				let pressed = std::mem::take(&mut self.pressable_state_mut().pressed);
+				if !pressed {
+					return Reply::unhandled();
+				}
+				self.activate();
+				Reply::handled()
+			}
+			WidgetEvent::OnKeyDown {
+				key: Some(VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter),
+				..
+			} if enabled => {
+				self.activate();
+				Reply::handled()
+			}
+			WidgetEvent::OnFocus { .. } => {
+				self.pressable_state_mut().focused = true;
+				Reply::handled()
+			}
+			WidgetEvent::OnUnfocus { .. } => {
+				let mut state = self.pressable_state_mut();
+				state.focused = false;
+				state.pressed = false;
+				Reply::handled()
+			}
+			_ => return Reply::unhandled(),
+		};
+		self.invalidate_paint();
+		reply
+	}
+}
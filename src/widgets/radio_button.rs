@@ -0,0 +1,275 @@
+use std::cell::{Ref, RefMut};
+use crate::{
+	events::{Reply, WidgetEvent},
+	paint::Painter,
+	util::{Geometry, SharedRef, WidgetRef, WidgetRefFromSelfSpecific, WidgetWeak},
+	widgets::{ButtonStyle, Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Point};
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+use crate::widgets::pressable::{PressableState, PressableWidget};
+
+/// Diameter of the circle.
+const CIRCLE_SIZE: scalar = 16.0;
+
+/// Radio buttons of which at most one is selected at a time.
+///
+/// Cloning is cheap, all clones refer to the same group.
+#[derive(Clone)]
+pub struct RadioGroup(SharedRef<Vec<WidgetWeak<RadioButtonWidget>>>);
+
+impl RadioGroup {
+	pub fn new() -> Self {
+		Self(SharedRef::new(Vec::new()))
+	}
+
+	/// Returns the buttons of the group in the order they got built.
+	pub fn buttons(&self) -> Vec<WidgetRef<RadioButtonWidget>> {
+		self.0.get().iter().filter_map(|button| button.upgrade()).collect()
+	}
+
+	/// Returns the index of the selected button within [RadioGroup::buttons()].
+	pub fn selected_index(&self) -> Option<usize> {
+		self.buttons().iter().position(|button| button.get().is_selected())
+	}
+
+	pub fn selected(&self) -> Option<WidgetRef<RadioButtonWidget>> {
+		self.buttons().into_iter().find(|button| button.get().is_selected())
+	}
+
+	fn add(&self, button: &WidgetRef<RadioButtonWidget>) {
+		let mut buttons = self.0.get_mut();
+		buttons.retain(|button| button.upgrade().is_some());
+		buttons.push(button.downgrade());
+	}
+}
+
+impl Default for RadioGroup {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+pub struct RadioButtonWidgetState {
+	leaf: LeafState,
+	pressable: PressableState,
+	selected: bool,
+	label: String,
+	group: Option<RadioGroup>,
+	style: ButtonStyle,
+	on_selected_changed: Option<Box<dyn Fn(bool)>>,
+}
+
+pub type RadioButtonWidget = WidgetImpl<RadioButtonWidgetState>;
+
+pub struct RadioButtonWidgetBuilder(RadioButtonWidget);
+
+/// A circle with an optional label next to it, that gets selected by activating it.
+///
+/// Selecting a button deselects the other buttons of its [RadioGroup].
+/// A selected button can not be deselected by activating it again.
+impl RadioButtonWidget {
+	pub fn new() -> RadioButtonWidgetBuilder {
+		let mut leaf = LeafState::default();
+		leaf.widget.focusable = true;
+		RadioButtonWidgetBuilder(RadioButtonWidgetState {
+			leaf,
+			pressable: Default::default(),
+			selected: false,
+			label: String::new(),
+			group: None,
+			style: Default::default(),
+			on_selected_changed: None,
+		}.into())
+	}
+
+	pub fn is_selected(&self) -> bool {
+		self.state().selected
+	}
+
+	/// Selects or deselects the button, selecting deselects the other buttons of the group.
+	pub fn set_selected(&self, selected: bool) {
+		if self.state().selected == selected {
+			return;
+		}
+		if selected {
+			let group = self.state().group.clone();
+			let this = self.self_ref();
+			for button in group.iter().flat_map(|group| group.buttons()) {
+				if button != this {
+					button.get().set_selected(false);
+				}
+			}
+		}
+		self.state_mut().selected = selected;
+		self.invalidate_paint();
+		let delegate = self.state_mut().on_selected_changed.take();
+		if let Some(delegate) = delegate {
+			delegate(selected);
+			self.state_mut().on_selected_changed = Some(delegate);
+		}
+	}
+
+	pub fn group(&self) -> Option<RadioGroup> {
+		self.state().group.clone()
+	}
+
+	pub fn set_label(&self, label: String) {
+		self.state_mut().label = label;
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+		self.invalidate_paint();
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.pressable_is_enabled()
+	}
+
+	pub fn set_enabled(&self, enabled: bool) {
+		self.pressable_set_enabled(enabled);
+	}
+}
+
+impl RadioButtonWidgetBuilder {
+	pub fn selected(self, selected: bool) -> Self {
+		self.0.state_mut().selected = selected;
+		self
+	}
+
+	pub fn label(self, label: impl Into<String>) -> Self {
+		self.0.state_mut().label = label.into();
+		self
+	}
+
+	pub fn group(self, group: RadioGroup) -> Self {
+		self.0.state_mut().group = Some(group);
+		self
+	}
+
+	/// Called with the new state whenever the button gets selected or deselected.
+	pub fn on_selected_changed<F>(self, func: F) -> Self where F: Fn(bool) + 'static {
+		self.0.state_mut().on_selected_changed = Some(Box::new(func));
+		self
+	}
+
+	pub fn enabled(self, enabled: bool) -> Self {
+		let mut state = self.0.state_mut();
+		state.pressable.enabled = enabled;
+		state.leaf.widget.focusable = enabled;
+		drop(state);
+		self
+	}
+
+	pub fn style(self, style: ButtonStyle) -> Self {
+		self.0.state_mut().style = style;
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.state_mut().leaf.widget.tab_index = tab_index;
+		self
+	}
+
+	/// Builds the button and adds it to its group.
+	/// If it is selected, the buttons of the group built before get deselected.
+	pub fn build(self) -> WidgetRef<RadioButtonWidget> {
+		let button = WidgetRef::new(self.0);
+		let (group, selected) = {
+			let widget = button.get();
+			let state = widget.state();
+			(state.group.clone(), state.selected)
+		};
+		if let Some(group) = group {
+			if selected {
+				for other in group.buttons() {
+					other.get().set_selected(false);
+				}
+			}
+			group.add(&button);
+		}
+		button
+	}
+}
+
+impl Widget for RadioButtonWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let state = self.state();
+		let style = &state.style;
+		let radius = CIRCLE_SIZE / 2.0;
+		let center = Point::new(radius, size.y / 2.0);
+		if state.selected && state.pressable.enabled {
+			painter.draw_circle(center, radius, &style.accent);
+		} else {
+			painter.draw_circle(center, radius, style.background(&state.pressable));
+		}
+		if state.selected {
+			painter.draw_circle(center, radius / 2.5, style.label_paint(&state.pressable));
+		}
+		if state.pressable.focused {
+			painter.draw_circle(center, radius + 2.0, &style.focus);
+		}
+		if !state.label.is_empty() {
+			style.draw_label(painter, &state.label, CIRCLE_SIZE + style.padding, size.y, &state.pressable);
+		}
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		state.style.indicator_size(Vector2::new(CIRCLE_SIZE, CIRCLE_SIZE), &state.label)
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		self.pressable_on_event(event)
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for RadioButtonWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
+
+impl PressableWidget for RadioButtonWidget {
+	fn pressable_state(&self) -> Ref<PressableState> {
+		self.widget_state(|v| &v.pressable)
+	}
+
+	fn pressable_state_mut(&self) -> RefMut<PressableState> {
+		self.widget_state_mut(|v| &mut v.pressable)
+	}
+
+	fn activate(&self) {
+		self.set_selected(true);
+	}
+}
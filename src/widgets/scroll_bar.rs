@@ -1,7 +1,7 @@
 use std::cell::{Ref, RefMut};
 use std::ops::Range;
 use crate::{
-	events::{Reply, WidgetEvent},
+	events::{input::{CursorIcon, MouseButton}, Reply, WidgetEvent},
 	paint::Painter,
 	util::{Geometry, WidgetRef},
 	widgets::{Axis, Widget, WidgetState},
@@ -9,6 +9,7 @@ use crate::{
 use cgmath::Vector2;
 use skia_safe::{scalar, Color, Color4f};
 use crate::widgets::{Arrangements, Children, PanelState, WidgetArrangement, WidgetImpl};
+use crate::widgets::handle_drag::HandleDrag;
 use crate::widgets::leaf_widget::{LeafState, LeafWidget};
 
 pub enum ScrollBarHandleSize {
@@ -33,7 +34,7 @@ pub struct ScrollBarWidgetState {
 	handle_size: ScrollBarHandleSize,
 	handle: skia_safe::Paint,
 	tray: skia_safe::Paint,
-	drag_start: Option<(f64, Vector2<scalar>)>,
+	drag: HandleDrag,
 	pub on_value_changed: Option<Box<dyn Fn(f64, f64)>>,
 }
 
@@ -51,7 +52,7 @@ impl ScrollBarWidget {
 			handle_size: ScrollBarHandleSize::Fraction(0.1),
 			handle: skia_safe::Paint::new(Color4f::from(Color::BLUE), None),
 			tray: skia_safe::Paint::new(Color4f::from(Color::RED), None),
-			drag_start: None,
+			drag: HandleDrag::default(),
 			on_value_changed: None,
 		}.into())
 	}
@@ -63,7 +64,11 @@ impl ScrollBarWidget {
 	pub fn set_value(&self, value: f64) {
 		let old_value = self.state().value;
 		let new_value = value.clamp(0.0, 1.0);
+		if old_value == new_value {
+			return;
+		}
 		self.state_mut().value = new_value;
+		self.invalidate_paint();
 		let delegate = self.state_mut().on_value_changed.take();
 		if let Some(delegate) = delegate {
 			delegate(old_value, new_value);
@@ -71,8 +76,16 @@ impl ScrollBarWidget {
 		}
 	}
 
+	pub fn range(&self) -> Range<f64> {
+		self.state().range.clone()
+	}
+
 	pub fn set_range(&self, range: Range<f64>) {
+		if self.state().range == range {
+			return;
+		}
 		self.state_mut().range = range;
+		self.invalidate_paint();
 		let value = self.state().value;
 		self.set_value(value);
 	}
@@ -139,35 +152,43 @@ impl Widget for ScrollBarWidget {
 		match event {
 			WidgetEvent::OnCursorMove { pos, .. } => {
 				let state = self.state();
-				if let Some(start) = state.drag_start {
-					let local_size = self.cached_geometry().local_size();
-					let local_axis = state.direction.get_vec_axis(local_size).0 as f64;
-					let handle_size = state.handle_size.get_size(&state.range);
-					let length = state.range.end - state.range.start;
-					let value_per_local = (length + handle_size) / local_axis;
-					let diff = state.direction.get_vec_axis(pos - start.1).0 as f64;
-					drop(state);
-
-					self.set_value(diff * value_per_local / length + start.0);
-
-					Reply::handled()
-				} else {
-					Reply::unhandled()
+				let track = state.direction.get_vec_axis(self.cached_geometry().local_size()).0;
+				let handle_size = state.handle_size.get_size(&state.range);
+				let length = state.range.end - state.range.start;
+				// the value is the fraction of the range, the track also covers the handle
+				let value = state.drag.value_at(pos, state.direction, track, (length + handle_size) / length);
+				drop(state);
+				match value {
+					Some(value) => {
+						self.set_value(value);
+						Reply::handled()
+					}
+					None => Reply::unhandled(),
 				}
 			}
-			WidgetEvent::OnMouseButtonDown { mouse, pos, .. } => {
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Left,
+				pos,
+				..
+			} => {
 				let mut state = self.state_mut();
-				state.drag_start = Some((state.value, *pos));
-				Reply::handled().capture_cursor(*mouse)
-			}
-			WidgetEvent::OnMouseButtonUp { mouse, .. } => {
-				self.state_mut().drag_start = None;
-				Reply::handled().release_cursor(*mouse)
+				let value = state.value;
+				state.drag.start(*mouse, value, *pos)
 			}
+			WidgetEvent::OnMouseButtonUp {
+				mouse,
+				button: MouseButton::Left,
+				..
+			} => self.state_mut().drag.end(*mouse).unwrap_or_else(Reply::unhandled),
 			_ => Reply::unhandled(),
 		}
 	}
 
+	fn cursor_icon(&self) -> Option<CursorIcon> {
+		Some(self.state().drag.cursor_icon())
+	}
+
 	fn cached_geometry(&self) -> Geometry {
 		self.leaf_cached_geometry()
 	}
@@ -1,6 +1,11 @@
 use std::cell::{Ref, RefMut};
 use std::ops::Deref;
+use std::time::{Duration, Instant};
 use crate::{
+	events::{
+		input::{ScrollDelta, ScrollPhase},
+		Reply, WidgetEvent,
+	},
 	paint::Painter,
 	util::{scalar, Geometry, WidgetRef},
 	widgets::{
@@ -30,10 +35,24 @@ pub struct ScrollPanelState {
 	arranged_content: Option<WidgetArrangement>,
 	arranged_vertical: Option<WidgetArrangement>,
 	arranged_horizontal: Option<WidgetArrangement>,
+	/// Velocity in pixels per second the content keeps scrolling with after a fling.
+	velocity: Vector2<scalar>,
+	/// Time of the last pixel scroll or kinetic scroll step.
+	last_scroll: Option<Instant>,
 }
 
 pub type ScrollPanel = WidgetImpl<ScrollPanelState>;
 
+/// Distance in pixels scrolled per line, e.g. per notch of a mouse wheel.
+pub(crate) const LINE_SCROLL_DISTANCE: scalar = 40.0;
+/// Fraction of the kinetic scroll velocity that remains after one second.
+const KINETIC_FRICTION: scalar = 0.05;
+/// Kinetic scrolling stops below this velocity in pixels per second.
+const KINETIC_MIN_VELOCITY: scalar = 20.0;
+/// If the fingers rest longer than this before getting lifted, there is no fling.
+const KINETIC_MAX_REST: Duration = Duration::from_millis(100);
+const KINETIC_TICK: Duration = Duration::from_millis(16);
+
 impl ScrollPanel {
 	pub fn new() -> ScrollPanelBuilder {
 		ScrollPanelBuilder(ScrollPanelState {
@@ -46,8 +65,33 @@ impl ScrollPanel {
 			arranged_content: None,
 			arranged_vertical: None,
 			arranged_horizontal: None,
+			velocity: Vector2::new(0.0, 0.0),
+			last_scroll: None,
 		}.into())
 	}
+
+	/// Scrolls the content by the given amount of pixels, positive values scroll towards the start.
+	/// Returns true if anything scrolled.
+	pub fn scroll_by(&self, delta: Vector2<scalar>) -> bool {
+		let state = self.state();
+		let horizontal = state.horizontal.clone().filter(|_| state.arranged_horizontal.is_some());
+		let vertical = state.vertical.clone().filter(|_| state.arranged_vertical.is_some());
+		drop(state);
+		let mut scrolled = false;
+		if let Some(horizontal) = horizontal {
+			scrolled |= scroll_bar_by(&horizontal.get(), -delta.x);
+		}
+		if let Some(vertical) = vertical {
+			scrolled |= scroll_bar_by(&vertical.get(), -delta.y);
+		}
+		scrolled
+	}
+
+	fn stop_kinetic_scroll(&self) {
+		let mut state = self.state_mut();
+		state.velocity = Vector2::new(0.0, 0.0);
+		state.last_scroll = None;
+	}
 }
 
 pub struct ScrollPanelBuilder(ScrollPanel);
@@ -60,15 +104,15 @@ impl ScrollPanelBuilder {
 		if let Some(vertical) = &state.vertical {
 			vertical.get().set_parent(Some(widget.clone()));
 			let w = widget.clone();
-			vertical.get().state_mut().on_value_changed = Some(Box::new(move |_, v| {
-				w.get().arrange_children(w.get().cached_geometry());
+			vertical.get().state_mut().on_value_changed = Some(Box::new(move |_, _| {
+				w.get().invalidate_layout();
 			}));
 		}
 		if let Some(horizontal) = &state.horizontal {
 			horizontal.get().set_parent(Some(widget.clone()));
 			let w = widget.clone();
-			horizontal.get().state_mut().on_value_changed = Some(Box::new(move |_, v| {
-				w.get().arrange_children(w.get().cached_geometry());
+			horizontal.get().state_mut().on_value_changed = Some(Box::new(move |_, _| {
+				w.get().invalidate_layout();
 			}));
 		}
 		if let Some(content) = &state.content {
@@ -119,9 +163,8 @@ impl Widget for ScrollPanel {
 				content.geometry.local_pos().x,
 				content.geometry.local_pos().y,
 			));
-			content.widget.get().paint(content.geometry, layer, painter);
+			layer = content.widget.get().paint_cached(content.geometry, layer, painter);
 			painter.restore();
-			layer += 1;
 		}
 		let state = self.state();
 		let children = vec![state.arranged_horizontal.clone(), state.arranged_vertical.clone()];
@@ -132,17 +175,16 @@ impl Widget for ScrollPanel {
 				child.geometry.local_pos().x,
 				child.geometry.local_pos().y,
 			));
-			child.widget.get().paint(child.geometry, layer, painter);
+			layer = child.widget.get().paint_cached(child.geometry, layer, painter);
 			painter.restore();
-			layer += 1;
 		}
 		layer
 	}
 
 	fn get_desired_size(&self) -> Vector2<scalar> {
 		match self.state().content.as_ref().map(|content| (self.state().direction, content.get().get_desired_size())) {
-			Some((ScrollPanelDirection::Horizontal, size)) => Vector2::new(0.0, size.x),
-			Some((ScrollPanelDirection::Vertical, size)) => Vector2::new(0.0, size.x),
+			Some((ScrollPanelDirection::Horizontal, size)) => Vector2::new(0.0, size.y),
+			Some((ScrollPanelDirection::Vertical, size)) => Vector2::new(size.x, 0.0),
 			_ => Vector2::new(0.0, 0.0),
 		}
 	}
@@ -155,6 +197,7 @@ impl Widget for ScrollPanel {
 	fn arrange_children(&self, geometry: Geometry) {
 		let state = self.state().clone();
 		self.state_mut().cached_geometry = geometry;
+		self.damage_geometry_change(state.cached_geometry, geometry);
 		let mut horizontal = false;
 		let mut vertical = false;
 		let desired_size = match &state.content {
@@ -209,11 +252,11 @@ impl Widget for ScrollPanel {
 			);
 			let size = content.get().get_desired_size();
 			let size = Vector2::new(
-				match geometry.local_size().x {
+				match available_content_size.x {
 					x if x > size.x => x,
 					_ => size.x,
 				},
-				match geometry.local_size().y {
+				match available_content_size.y {
 					y if y > size.y => y,
 					_ => size.y,
 				},
@@ -255,4 +298,140 @@ impl Widget for ScrollPanel {
 	fn cached_geometry(&self) -> Geometry {
 		self.state().cached_geometry
 	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnScroll { delta, phase, .. } => {
+				let now = Instant::now();
+				if *phase == ScrollPhase::Started || *phase == ScrollPhase::Cancelled {
+					self.stop_kinetic_scroll();
+				}
+				let pixels = match delta {
+					ScrollDelta::Lines(lines) => *lines * LINE_SCROLL_DISTANCE,
+					ScrollDelta::Pixels(pixels) => *pixels,
+				};
+				let scrolled = self.scroll_by(pixels);
+
+				if let ScrollDelta::Pixels(_) = delta {
+					// track the velocity, so a fling can continue kinetically
+					let mut state = self.state_mut();
+					if let Some(last_scroll) = state.last_scroll {
+						let dt = now.saturating_duration_since(last_scroll).as_secs_f32();
+						if dt > 0.0 {
+							state.velocity = state.velocity * 0.2 + pixels / dt * 0.8;
+						}
+					}
+					state.last_scroll = Some(now);
+				}
+
+				if *phase == ScrollPhase::Ended {
+					let state = self.state();
+					let resting = state
+						.last_scroll
+						.is_none_or(|last_scroll| now.saturating_duration_since(last_scroll) > KINETIC_MAX_REST);
+					let velocity = state.velocity.x.abs().max(state.velocity.y.abs());
+					drop(state);
+					if !resting && velocity > KINETIC_MIN_VELOCITY {
+						self.state_mut().last_scroll = Some(now);
+						return Reply::handled().request_tick(KINETIC_TICK);
+					}
+					self.stop_kinetic_scroll();
+				}
+
+				if scrolled {
+					Reply::handled()
+				} else {
+					Reply::unhandled()
+				}
+			}
+			WidgetEvent::OnTick { time } => {
+				let state = self.state();
+				let (velocity, last_scroll) = match state.last_scroll {
+					Some(last_scroll) => (state.velocity, last_scroll),
+					None => return Reply::unhandled(),
+				};
+				drop(state);
+				let dt = time.saturating_duration_since(last_scroll).as_secs_f32();
+				let scrolled = self.scroll_by(velocity * dt);
+				let velocity = velocity * KINETIC_FRICTION.powf(dt);
+				if scrolled && velocity.x.abs().max(velocity.y.abs()) > KINETIC_MIN_VELOCITY {
+					let mut state = self.state_mut();
+					state.velocity = velocity;
+					state.last_scroll = Some(*time);
+					Reply::handled().request_tick(KINETIC_TICK)
+				} else {
+					self.stop_kinetic_scroll();
+					Reply::handled()
+				}
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn bring_into_view(&self, area: Rect) -> bool {
+		let geometry = self.cached_geometry();
+		let state = self.state();
+		let content_pos = match &state.arranged_content {
+			Some(content) => content.geometry.local_pos(),
+			None => Vector2::new(0.0, 0.0),
+		};
+		let viewport = Vector2::new(
+			geometry.local_size().x - state.arranged_vertical.as_ref().map_or(0.0, |v| v.geometry.local_size().x),
+			geometry.local_size().y - state.arranged_horizontal.as_ref().map_or(0.0, |v| v.geometry.local_size().y),
+		);
+		let horizontal = state.horizontal.clone();
+		let vertical = state.vertical.clone();
+		drop(state);
+
+		// area relative to the origin of the not scrolled content
+		let origin = geometry.absolute_pos() + content_pos;
+		let mut scroll = Vector2::new(0.0, 0.0);
+		if let Some(horizontal) = horizontal {
+			scroll.x = scroll_into_view(&horizontal.get(), -content_pos.x, area.left - origin.x, area.right - origin.x, viewport.x);
+		}
+		if let Some(vertical) = vertical {
+			scroll.y = scroll_into_view(&vertical.get(), -content_pos.y, area.top - origin.y, area.bottom - origin.y, viewport.y);
+		}
+
+		let scrolled = scroll.x != 0.0 || scroll.y != 0.0;
+		let area = area.with_offset((-scroll.x, -scroll.y));
+		let parent_scrolled = match self.get_parent() {
+			Some(parent) => parent.get().bring_into_view(area),
+			None => false,
+		};
+		scrolled || parent_scrolled
+	}
+}
+
+/// Moves the value of the scroll bar by the given distance in pixels.
+/// Returns true if the value changed.
+pub(crate) fn scroll_bar_by(scroll_bar: &ScrollBarWidget, distance: scalar) -> bool {
+	let range = scroll_bar.range();
+	let overflow = range.end - range.start;
+	if overflow <= 0.0 {
+		return false;
+	}
+	let old_value = scroll_bar.value();
+	scroll_bar.set_value(old_value + distance as f64 / overflow);
+	scroll_bar.value() != old_value
+}
+
+/// Changes the value of the scroll bar so that the range from `start` to `end` is within the viewport.
+/// Returns by how much the content got scrolled.
+fn scroll_into_view(scroll_bar: &ScrollBarWidget, offset: scalar, start: scalar, end: scalar, viewport: scalar) -> scalar {
+	let range = scroll_bar.range();
+	let overflow = (range.end - range.start) as scalar;
+	if overflow <= 0.0 {
+		return 0.0;
+	}
+	let new_offset = if start < offset {
+		start
+	} else if end > offset + viewport {
+		(end - viewport).min(start)
+	} else {
+		return 0.0;
+	};
+	let new_offset = new_offset.clamp(0.0, overflow);
+	scroll_bar.set_value((new_offset / overflow) as f64);
+	new_offset - offset
 }
@@ -0,0 +1,460 @@
+use std::cell::{Ref, RefMut};
+use std::ops::RangeInclusive;
+use crate::{
+	events::{input::{CursorIcon, MouseButton}, Reply, WidgetEvent, WidgetFocusChange},
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{Axis, ButtonStyle, Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Point, Rect};
+use winit::event::VirtualKeyCode;
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::handle_drag::{HandleDrag, ScrollSteps, PAGE_STEPS};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+
+const THUMB_RADIUS: scalar = 7.0;
+const TRACK_THICKNESS: scalar = 4.0;
+/// Length of the tick marks, they get drawn below (or right of) the track.
+const TICK_LENGTH: scalar = 4.0;
+/// Length along the axis the slider wants to get.
+const DEFAULT_LENGTH: scalar = 120.0;
+/// Step size as fraction of the range, if the slider has no step.
+const DEFAULT_STEP_FRACTION: f64 = 0.01;
+
+pub struct SliderWidgetState {
+	leaf: LeafState,
+	direction: Axis,
+	range: RangeInclusive<f64>,
+	value: f64,
+	step: Option<f64>,
+	tick_interval: Option<f64>,
+	enabled: bool,
+	hovered: bool,
+	focused: bool,
+	style: ButtonStyle,
+	drag: HandleDrag,
+	scroll_steps: ScrollSteps,
+	on_value_changed: Option<Box<dyn Fn(f64, f64)>>,
+}
+
+pub type SliderWidget = WidgetImpl<SliderWidgetState>;
+
+pub struct SliderWidgetBuilder(SliderWidget);
+
+/// A thumb that gets dragged along a track to pick a value of a range.
+///
+/// Works like the handle of a [crate::widgets::ScrollBarWidget], but pressing the track moves the thumb to the cursor.
+/// If the slider has a step, the value snaps to multiples of it starting at the start of the range.
+/// The arrow keys, PageUp, PageDown, Home, End and the mouse wheel move the value by steps.
+/// Vertical sliders have the start of the range at the bottom.
+impl SliderWidget {
+	pub fn new() -> SliderWidgetBuilder {
+		let mut leaf = LeafState::default();
+		leaf.widget.focusable = true;
+		SliderWidgetBuilder(SliderWidgetState {
+			leaf,
+			direction: Axis::Horizontal,
+			range: 0.0..=1.0,
+			value: 0.0,
+			step: None,
+			tick_interval: None,
+			enabled: true,
+			hovered: false,
+			focused: false,
+			style: Default::default(),
+			drag: HandleDrag::default(),
+			scroll_steps: Default::default(),
+			on_value_changed: None,
+		}.into())
+	}
+
+	pub fn value(&self) -> f64 {
+		self.state().value
+	}
+
+	/// Returns the value rounded to an integer, for sliders with an integer range.
+	pub fn value_i64(&self) -> i64 {
+		self.value().round() as i64
+	}
+
+	/// Sets the value snapped to the step and clamped to the range.
+	pub fn set_value(&self, value: f64) {
+		let old_value = self.state().value;
+		let new_value = self.snap(value);
+		if old_value == new_value {
+			return;
+		}
+		self.state_mut().value = new_value;
+		self.invalidate_paint();
+		let delegate = self.state_mut().on_value_changed.take();
+		if let Some(delegate) = delegate {
+			delegate(old_value, new_value);
+			self.state_mut().on_value_changed = Some(delegate);
+		}
+	}
+
+	pub fn range(&self) -> RangeInclusive<f64> {
+		self.state().range.clone()
+	}
+
+	pub fn set_range(&self, range: RangeInclusive<f64>) {
+		if self.state().range == range {
+			return;
+		}
+		self.state_mut().range = range;
+		self.invalidate_paint();
+		let value = self.state().value;
+		self.set_value(value);
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.state().enabled
+	}
+
+	/// Disabled sliders ignore all input and can not be focused.
+	pub fn set_enabled(&self, enabled: bool) {
+		let mut state = self.state_mut();
+		state.enabled = enabled;
+		state.leaf.widget.focusable = enabled;
+		state.drag = HandleDrag::default();
+		drop(state);
+		self.invalidate_paint();
+	}
+
+	/// Moves the value by the given number of steps, negative steps move towards the start.
+	pub fn step_by(&self, steps: f64) {
+		let state = self.state();
+		let step = state.step.unwrap_or((state.range.end() - state.range.start()) * DEFAULT_STEP_FRACTION);
+		let value = state.value + steps * step;
+		drop(state);
+		self.set_value(value);
+	}
+
+	fn snap(&self, value: f64) -> f64 {
+		let state = self.state();
+		let (start, end) = (*state.range.start(), *state.range.end());
+		let value = value.clamp(start, end);
+		match state.step {
+			Some(step) if step > 0.0 => (start + ((value - start) / step).round() * step).clamp(start, end),
+			_ => value,
+		}
+	}
+
+	/// Returns the position of the value within the range from 0 to 1.
+	fn fraction(&self) -> f64 {
+		let state = self.state();
+		let length = state.range.end() - state.range.start();
+		if length <= 0.0 {
+			0.0
+		} else {
+			(state.value - state.range.start()) / length
+		}
+	}
+
+	/// Returns the offset along the axis of the thumb center at the fraction of the range.
+	fn offset_at(&self, fraction: f64, length: scalar) -> scalar {
+		let track = (length - THUMB_RADIUS * 2.0).max(0.0);
+		let fraction = match self.state().direction {
+			Axis::Horizontal => fraction,
+			Axis::Vertical => 1.0 - fraction,
+		};
+		THUMB_RADIUS + track * fraction as scalar
+	}
+
+	/// Returns the value at the position in window coordinates.
+	fn value_at(&self, pos: &Vector2<scalar>) -> f64 {
+		let geometry = self.cached_geometry();
+		let state = self.state();
+		let length = state.direction.get_vec_axis(geometry.local_size()).0;
+		let offset = state.direction.get_vec_axis(pos - geometry.absolute_pos()).0;
+		let track = (length - THUMB_RADIUS * 2.0).max(1.0);
+		let fraction = ((offset - THUMB_RADIUS) / track).clamp(0.0, 1.0) as f64;
+		let fraction = match state.direction {
+			Axis::Horizontal => fraction,
+			Axis::Vertical => 1.0 - fraction,
+		};
+		state.range.start() + fraction * (state.range.end() - state.range.start())
+	}
+
+	/// Returns the offset across the axis of the track center.
+	fn track_center(&self, thickness: scalar) -> scalar {
+		let ticks = if self.state().tick_interval.is_some() { TICK_LENGTH + 2.0 } else { 0.0 };
+		(thickness - ticks) / 2.0
+	}
+
+	/// Returns the center of the thumb in local coordinates.
+	fn thumb_center(&self, size: Vector2<scalar>) -> Vector2<scalar> {
+		let fraction = self.fraction();
+		let state = self.state();
+		let (length, thickness) = state.direction.get_vec_axis(size);
+		drop(state);
+		let along = self.offset_at(fraction, length);
+		let across = self.track_center(thickness);
+		self.state().direction.create_vec(along, across)
+	}
+}
+
+impl SliderWidgetBuilder {
+	pub fn direction(self, direction: Axis) -> Self {
+		self.0.state_mut().direction = direction;
+		self
+	}
+
+	/// Sets the range of the value, the end is included.
+	pub fn range(self, range: RangeInclusive<f64>) -> Self {
+		self.0.state_mut().range = range;
+		self
+	}
+
+	/// Sets an integer range and a step of 1, read the value with [SliderWidget::value_i64()].
+	pub fn int_range(self, range: RangeInclusive<i64>) -> Self {
+		let mut state = self.0.state_mut();
+		state.range = *range.start() as f64..=*range.end() as f64;
+		state.step = Some(1.0);
+		drop(state);
+		self
+	}
+
+	/// The value snaps to multiples of the step, starting at the start of the range.
+	pub fn step(self, step: f64) -> Self {
+		self.0.state_mut().step = Some(step);
+		self
+	}
+
+	/// Draws a tick mark at every multiple of the interval, starting at the start of the range.
+	pub fn ticks(self, interval: f64) -> Self {
+		self.0.state_mut().tick_interval = Some(interval);
+		self
+	}
+
+	pub fn value(self, value: f64) -> Self {
+		self.0.state_mut().value = value;
+		self
+	}
+
+	pub fn on_value_changed<F>(self, func: F) -> Self where F: Fn(f64, f64) + 'static {
+		self.0.state_mut().on_value_changed = Some(Box::new(func));
+		self
+	}
+
+	pub fn enabled(self, enabled: bool) -> Self {
+		let mut state = self.0.state_mut();
+		state.enabled = enabled;
+		state.leaf.widget.focusable = enabled;
+		drop(state);
+		self
+	}
+
+	pub fn style(self, style: ButtonStyle) -> Self {
+		self.0.state_mut().style = style;
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.state_mut().leaf.widget.tab_index = tab_index;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<SliderWidget> {
+		let value = self.0.state().value;
+		let value = self.0.snap(value);
+		self.0.state_mut().value = value;
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for SliderWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let thumb = self.thumb_center(size);
+		let fraction = self.fraction();
+		let range = self.range();
+		let (length, thickness) = self.state().direction.get_vec_axis(size);
+		let center = self.track_center(thickness);
+		let start = self.offset_at(0.0, length);
+		let end = self.offset_at(1.0, length);
+		let value_offset = self.offset_at(fraction, length);
+		let range_length = range.end() - range.start();
+		let tick_offsets: Vec<scalar> = match self.state().tick_interval {
+			Some(interval) if interval > 0.0 && range_length > 0.0 => {
+				let count = (range_length / interval).floor() as usize;
+				(0..=count)
+					.map(|tick| self.offset_at(tick as f64 * interval / range_length, length))
+					.collect()
+			}
+			_ => Vec::new(),
+		};
+
+		let state = self.state();
+		let style = &state.style;
+		// creates a rect from offsets along and across the axis
+		let rect = |along: (scalar, scalar), across: (scalar, scalar)| {
+			let a = state.direction.create_vec(along.0, across.0);
+			let b = state.direction.create_vec(along.1, across.1);
+			Rect::new(a.x.min(b.x), a.y.min(b.y), a.x.max(b.x), a.y.max(b.y))
+		};
+		let foreground = if state.enabled { &style.foreground } else { &style.disabled_foreground };
+
+		let track_across = (center - TRACK_THICKNESS / 2.0, center + TRACK_THICKNESS / 2.0);
+		let radius = TRACK_THICKNESS / 2.0;
+		let track = if state.enabled { &style.background } else { &style.disabled };
+		painter.draw_round_rect(rect((start, end), track_across), radius, radius, track);
+		if state.enabled {
+			painter.draw_round_rect(rect((start, value_offset), track_across), radius, radius, &style.accent);
+		}
+
+		let tick_across = (center + THUMB_RADIUS + 2.0, center + THUMB_RADIUS + 2.0 + TICK_LENGTH);
+		for offset in tick_offsets {
+			painter.draw_rect(rect((offset - 0.5, offset + 0.5), tick_across), foreground);
+		}
+
+		let thumb = Point::new(thumb.x, thumb.y);
+		let grown = state.enabled && (state.hovered || state.drag.is_dragging());
+		let thumb_radius = if grown { THUMB_RADIUS } else { THUMB_RADIUS - 1.0 };
+		painter.draw_circle(thumb, thumb_radius, foreground);
+		if state.focused {
+			painter.draw_circle(thumb, THUMB_RADIUS + 2.0, &style.focus);
+		}
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let ticks = if state.tick_interval.is_some() { TICK_LENGTH + 2.0 } else { 0.0 };
+		state.direction.create_vec(DEFAULT_LENGTH, THUMB_RADIUS * 2.0 + ticks)
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		let enabled = self.state().enabled;
+		match event {
+			WidgetEvent::OnCursorEnter { .. } | WidgetEvent::OnCursorLeave { .. } => {
+				self.state_mut().hovered = matches!(event, WidgetEvent::OnCursorEnter { .. });
+				self.invalidate_paint();
+				Reply::handled()
+			}
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Left,
+				pos,
+				..
+			} if enabled => {
+				let geometry = self.cached_geometry();
+				let thumb = geometry.absolute_pos() + self.thumb_center(geometry.local_size());
+				let distance = ((pos.x - thumb.x).powi(2) + (pos.y - thumb.y).powi(2)).sqrt();
+				// pressing the track jumps to the cursor, pressing the thumb keeps the offset to it
+				if distance > THUMB_RADIUS {
+					let value = self.value_at(pos);
+					self.set_value(value);
+				}
+				let mut state = self.state_mut();
+				let value = state.value;
+				state
+					.drag
+					.start(*mouse, value, *pos)
+					.take_focus(WidgetFocusChange::KeyboardList(vec![*mouse]))
+			}
+			WidgetEvent::OnCursorMove { pos, .. } => {
+				let state = self.state();
+				let track = state.direction.get_vec_axis(self.cached_geometry().local_size()).0 - THUMB_RADIUS * 2.0;
+				let range = state.range.end() - state.range.start();
+				// vertical sliders have the start of the range at the bottom
+				let span = match state.direction {
+					Axis::Horizontal => range,
+					Axis::Vertical => -range,
+				};
+				let value = state.drag.value_at(pos, state.direction, track, span);
+				drop(state);
+				match value {
+					Some(value) => {
+						self.set_value(value);
+						Reply::handled()
+					}
+					None => Reply::unhandled(),
+				}
+			}
+			WidgetEvent::OnMouseButtonUp {
+				mouse,
+				button: MouseButton::Left,
+				..
+			} => {
+				let reply = self.state_mut().drag.end(*mouse);
+				match reply {
+					Some(reply) => {
+						self.invalidate_paint();
+						reply
+					}
+					None => Reply::unhandled(),
+				}
+			}
+			WidgetEvent::OnScroll { delta, .. } if enabled => {
+				let steps = self.state_mut().scroll_steps.scroll(delta, true);
+				if steps != 0.0 {
+					self.step_by(steps);
+				}
+				Reply::handled()
+			}
+			WidgetEvent::OnKeyDown { key: Some(key), .. } if enabled => {
+				match key {
+					VirtualKeyCode::Right | VirtualKeyCode::Up => self.step_by(1.0),
+					VirtualKeyCode::Left | VirtualKeyCode::Down => self.step_by(-1.0),
+					VirtualKeyCode::PageUp => self.step_by(PAGE_STEPS),
+					VirtualKeyCode::PageDown => self.step_by(-PAGE_STEPS),
+					VirtualKeyCode::Home => self.set_value(*self.range().start()),
+					VirtualKeyCode::End => self.set_value(*self.range().end()),
+					_ => return Reply::unhandled(),
+				}
+				Reply::handled()
+			}
+			WidgetEvent::OnFocus { .. } | WidgetEvent::OnUnfocus { .. } => {
+				self.state_mut().focused = matches!(event, WidgetEvent::OnFocus { .. });
+				self.invalidate_paint();
+				Reply::handled()
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn cursor_icon(&self) -> Option<CursorIcon> {
+		let state = self.state();
+		if state.enabled || state.drag.is_dragging() {
+			Some(state.drag.cursor_icon())
+		} else {
+			None
+		}
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for SliderWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
@@ -0,0 +1,399 @@
+use std::cell::{Ref, RefMut};
+use std::ops::RangeInclusive;
+use crate::{
+	events::{Reply, WidgetEvent},
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{Arrangements, ButtonWidget, Children, PanelState, PanelWidget, TextEditWidget, Widget, WidgetArrangement, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Color, Color4f, Paint, PaintStyle, Rect};
+use winit::event::VirtualKeyCode;
+use crate::widgets::handle_drag::{ScrollSteps, PAGE_STEPS};
+
+/// Width of the column holding the up and down buttons.
+const BUTTON_WIDTH: scalar = 20.0;
+/// Minimal width of the text edit, so short numbers still get some space to be typed in.
+const MIN_EDIT_WIDTH: scalar = 40.0;
+/// Space between the border and the text edit.
+const EDIT_PADDING: scalar = 3.0;
+
+pub struct SpinBoxWidgetState {
+	panel: PanelState,
+	edit: WidgetRef<TextEditWidget>,
+	up: WidgetRef<ButtonWidget>,
+	down: WidgetRef<ButtonWidget>,
+	value: f64,
+	range: RangeInclusive<f64>,
+	step: f64,
+	decimals: usize,
+	/// False while the text of the edit is no valid value, it gets reverted on commit.
+	valid: bool,
+	scroll_steps: ScrollSteps,
+	background: Paint,
+	border: Paint,
+	invalid: Paint,
+	on_value_changed: Option<Box<dyn Fn(f64, f64)>>,
+}
+
+pub type SpinBoxWidget = crate::widgets::WidgetImpl<SpinBoxWidgetState>;
+
+pub struct SpinBoxWidgetBuilder(WidgetRef<SpinBoxWidget>);
+
+/// A text edit for numbers with buttons to step the value up and down.
+///
+/// Typed text gets parsed while typing, the value only changes if the text is a plain decimal number within the range
+/// with at most the configured decimals. Invalid text gets outlined and reverted on [SpinBoxWidget::commit()].
+/// Up, Down, PageUp, PageDown and the mouse wheel step the value, Enter commits the text.
+impl SpinBoxWidget {
+	pub fn new() -> SpinBoxWidgetBuilder {
+		let mut border = Paint::new(Color4f::from(Color::from_rgb(0x80, 0x80, 0x80)), None);
+		border.set_style(PaintStyle::Stroke);
+		let mut invalid = Paint::new(Color4f::from(Color::from_rgb(0xE0, 0x40, 0x40)), None);
+		invalid.set_style(PaintStyle::Stroke);
+		invalid.set_stroke_width(2.0);
+		let spin_box = WidgetRef::new(SpinBoxWidgetState {
+			panel: Default::default(),
+			edit: TextEditWidget::new().build(),
+			up: ButtonWidget::new("+").build(),
+			down: ButtonWidget::new("-").build(),
+			value: 0.0,
+			range: 0.0..=100.0,
+			step: 1.0,
+			decimals: 0,
+			valid: true,
+			scroll_steps: Default::default(),
+			background: Paint::new(Color4f::from(Color::from_rgb(0x30, 0x30, 0x30)), None),
+			border,
+			invalid,
+			on_value_changed: None,
+		}.into());
+
+		let (edit, up, down) = {
+			let widget = spin_box.get();
+			let state = widget.state();
+			(state.edit.clone(), state.up.clone(), state.down.clone())
+		};
+		let weak = spin_box.downgrade();
+		edit.get().set_on_text_changed(move |text, _| {
+			weak.upgrade().inspect(|spin_box| spin_box.get().text_changed(text));
+		});
+		let weak = spin_box.downgrade();
+		up.get().set_on_click(move || {
+			weak.upgrade().inspect(|spin_box| spin_box.get().step_by(1.0));
+		});
+		let weak = spin_box.downgrade();
+		down.get().set_on_click(move || {
+			weak.upgrade().inspect(|spin_box| spin_box.get().step_by(-1.0));
+		});
+		SpinBoxWidgetBuilder(spin_box)
+	}
+
+	pub fn value(&self) -> f64 {
+		self.state().value
+	}
+
+	/// Returns the value rounded to an integer, for spin boxes without decimals.
+	pub fn value_i64(&self) -> i64 {
+		self.value().round() as i64
+	}
+
+	/// Sets the value clamped to the range and rounded to the decimals, and replaces the text with it.
+	pub fn set_value(&self, value: f64) {
+		let value = self.round(value);
+		let old_value = self.state().value;
+		self.state_mut().value = value;
+		self.reset_text();
+		if old_value != value {
+			self.value_changed(old_value, value);
+		}
+	}
+
+	pub fn range(&self) -> RangeInclusive<f64> {
+		self.state().range.clone()
+	}
+
+	pub fn set_range(&self, range: RangeInclusive<f64>) {
+		self.state_mut().range = range;
+		let value = self.state().value;
+		self.set_value(value);
+	}
+
+	/// Moves the value by the given number of steps, negative steps move towards the start of the range.
+	/// The value snaps to multiples of the step starting at the start of the range,
+	/// so a typed value in between moves to the neighbouring multiple.
+	pub fn step_by(&self, steps: f64) {
+		let state = self.state();
+		let start = *state.range.start();
+		let mut value = state.value + steps * state.step;
+		if state.step > 0.0 {
+			value = start + ((value - start) / state.step).round() * state.step;
+		}
+		drop(state);
+		self.set_value(value);
+	}
+
+	/// Returns false while the text is no valid value.
+	pub fn is_valid(&self) -> bool {
+		self.state().valid
+	}
+
+	/// Replaces the text with the formatted value, this reverts invalid text.
+	pub fn commit(&self) {
+		self.reset_text();
+	}
+
+	pub fn edit(&self) -> WidgetRef<TextEditWidget> {
+		self.state().edit.clone()
+	}
+
+	/// Clamps the value to the range and rounds it to the decimals.
+	fn round(&self, value: f64) -> f64 {
+		let state = self.state();
+		let factor = 10f64.powi(state.decimals as i32);
+		let value = value.clamp(*state.range.start(), *state.range.end());
+		(value * factor).round() / factor
+	}
+
+	fn format(&self, value: f64) -> String {
+		format!("{:.*}", self.state().decimals, value)
+	}
+
+	fn reset_text(&self) {
+		let text = self.format(self.state().value);
+		let edit = self.state().edit.clone();
+		edit.get().set_text(text);
+		self.state_mut().valid = true;
+		self.invalidate_paint();
+	}
+
+	/// Parses the text typed into the edit, the value only gets updated if the text is valid.
+	///
+	/// Gets called by the edit while it is borrowed, so the edit must not be touched here.
+	fn text_changed(&self, text: &str) {
+		let text = text.trim();
+		// only plain decimals, the parser also accepts exponents and names like "inf"
+		let digits = text.strip_prefix('-').unwrap_or(text);
+		let plain = digits.chars().all(|c| c.is_ascii_digit() || c == '.');
+		let decimals = text.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+		let value = text.parse::<f64>().ok().filter(|value| {
+			let state = self.state();
+			plain && value.is_finite() && state.range.contains(value) && decimals <= state.decimals
+		});
+		self.state_mut().valid = value.is_some();
+		self.invalidate_paint();
+		if let Some(value) = value {
+			let old_value = self.state().value;
+			if old_value != value {
+				self.state_mut().value = value;
+				self.value_changed(old_value, value);
+			}
+		}
+	}
+
+	fn value_changed(&self, old_value: f64, new_value: f64) {
+		let delegate = self.state_mut().on_value_changed.take();
+		if let Some(delegate) = delegate {
+			delegate(old_value, new_value);
+			self.state_mut().on_value_changed = Some(delegate);
+		}
+	}
+}
+
+impl SpinBoxWidgetBuilder {
+	pub fn range(self, range: RangeInclusive<f64>) -> Self {
+		self.0.get().state_mut().range = range;
+		self
+	}
+
+	/// Sets an integer range, a step of 1 and no decimals, read the value with [SpinBoxWidget::value_i64()].
+	pub fn int_range(self, range: RangeInclusive<i64>) -> Self {
+		let widget = self.0.get();
+		let mut state = widget.state_mut();
+		state.range = *range.start() as f64..=*range.end() as f64;
+		state.step = 1.0;
+		state.decimals = 0;
+		drop(state);
+		drop(widget);
+		self
+	}
+
+	/// Amount the buttons, the arrow keys and the mouse wheel change the value by.
+	pub fn step(self, step: f64) -> Self {
+		self.0.get().state_mut().step = step;
+		self
+	}
+
+	/// Number of decimals shown and accepted when typing.
+	pub fn decimals(self, decimals: usize) -> Self {
+		self.0.get().state_mut().decimals = decimals;
+		self
+	}
+
+	pub fn value(self, value: f64) -> Self {
+		self.0.get().state_mut().value = value;
+		self
+	}
+
+	/// Called with the old and the new value whenever the value changed.
+	/// While typing, it gets called when the edit is borrowed, so it must not set the value.
+	pub fn on_value_changed<F>(self, func: F) -> Self where F: Fn(f64, f64) + 'static {
+		self.0.get().state_mut().on_value_changed = Some(Box::new(func));
+		self
+	}
+
+	pub fn background(self, background: Paint) -> Self {
+		self.0.get().state_mut().background = background;
+		self
+	}
+
+	pub fn border(self, border: Paint) -> Self {
+		self.0.get().state_mut().border = border;
+		self
+	}
+
+	/// Outline drawn while the text is no valid value.
+	pub fn invalid(self, invalid: Paint) -> Self {
+		self.0.get().state_mut().invalid = invalid;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<SpinBoxWidget> {
+		let widget = self.0.get();
+		for child in widget.get_children() {
+			child.get().set_parent(Some(self.0.clone()));
+		}
+		let value = widget.round(widget.state().value);
+		widget.state_mut().value = value;
+		widget.reset_text();
+		drop(widget);
+		self.0
+	}
+}
+
+impl Widget for SpinBoxWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.panel.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.panel.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let rect = Rect::from_wh(size.x, size.y);
+		{
+			let state = self.state();
+			painter.draw_rect(rect, &state.background);
+		}
+		let layer = self.panel_paint(geometry, layer + 1, painter);
+		let state = self.state();
+		if state.valid {
+			painter.draw_rect(rect, &state.border);
+		} else {
+			painter.draw_rect(rect.with_inset((1.0, 1.0)), &state.invalid);
+		}
+		layer
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let edit = state.edit.get().get_desired_size();
+		let buttons = state.up.get().get_desired_size().y.min(state.down.get().get_desired_size().y);
+		Vector2::new(
+			edit.x.max(MIN_EDIT_WIDTH) + EDIT_PADDING * 2.0 + BUTTON_WIDTH,
+			(edit.y + EDIT_PADDING * 2.0).max(buttons),
+		)
+	}
+
+	fn get_children(&self) -> Children {
+		let state = self.state();
+		vec![
+			state.edit.clone() as WidgetRef<dyn Widget>,
+			state.up.clone() as WidgetRef<dyn Widget>,
+			state.down.clone() as WidgetRef<dyn Widget>,
+		]
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.panel_arrange_children(geometry);
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.panel_get_arranged_children()
+	}
+
+	fn on_preview_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnKeyDown { key: Some(key), .. } => {
+				match key {
+					VirtualKeyCode::Up => self.step_by(1.0),
+					VirtualKeyCode::Down => self.step_by(-1.0),
+					VirtualKeyCode::PageUp => self.step_by(PAGE_STEPS),
+					VirtualKeyCode::PageDown => self.step_by(-PAGE_STEPS),
+					VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+						self.commit();
+						// the key still reaches the edit and its ancestors, e.g. to confirm a dialog
+						return Reply::unhandled();
+					}
+					_ => return Reply::unhandled(),
+				}
+				Reply::handled()
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnScroll { delta, .. } => {
+				let steps = self.state_mut().scroll_steps.scroll(delta, false);
+				if steps != 0.0 {
+					self.step_by(steps);
+				}
+				Reply::handled()
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.panel_cached_geometry()
+	}
+}
+
+impl PanelWidget for SpinBoxWidget {
+	fn panel_state(&self) -> Ref<PanelState> {
+		self.widget_state(|v| &v.panel)
+	}
+
+	fn panel_state_mut(&self) -> RefMut<PanelState> {
+		self.widget_state_mut(|v| &mut v.panel)
+	}
+
+	fn rearrange_children(&self, geometry: Geometry) -> Vec<WidgetArrangement> {
+		let state = self.state();
+		let size = geometry.local_size();
+		let edit_width = (size.x - BUTTON_WIDTH).max(0.0);
+		let button_height = size.y / 2.0;
+		let padding = Vector2::new(EDIT_PADDING, EDIT_PADDING);
+		vec![
+			geometry.child_widget(
+				state.edit.clone(),
+				padding,
+				Vector2::new(edit_width, size.y) - padding * 2.0,
+			),
+			geometry.child_widget(
+				state.up.clone(),
+				Vector2::new(edit_width, 0.0),
+				Vector2::new(BUTTON_WIDTH, button_height),
+			),
+			geometry.child_widget(
+				state.down.clone(),
+				Vector2::new(edit_width, button_height),
+				Vector2::new(BUTTON_WIDTH, button_height),
+			),
+		]
+	}
+}
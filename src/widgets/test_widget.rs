@@ -7,7 +7,7 @@ use crate::{
 };
 use cgmath::Vector2;
 use rand::Rng;
-use skia_safe::{scalar, Paint, Rect};
+use skia_safe::{scalar, Color, Paint, Rect};
 use crate::widgets::{Arrangements, Children, WidgetImpl};
 use crate::widgets::leaf_widget::{LeafState, LeafWidget};
 
@@ -59,6 +59,8 @@ impl TestWidget {
 			let val = TEST_WIDGET_RAND.as_mut().unwrap().gen::<f32>() * 360.0;
 			skia_safe::HSV::from((val, 1.0, 1.0)).to_color(alpha)
 		});
+		drop(state);
+		self.invalidate_paint();
 	}
 }
 
@@ -68,6 +70,11 @@ impl TestWidgetBuilder {
 		self
 	}
 
+	pub fn color(mut self, color: Color) -> Self {
+		self.0.state_mut().paint.set_color(color);
+		self
+	}
+
 	pub fn name(mut self, name: &str) -> Self {
 		self.0.state_mut().name = name.into();
 		self
@@ -119,6 +126,7 @@ impl Widget for TestWidget {
 			WidgetEvent::OnCursorEnter { cursor } => {
 				println!("Mouse {} Enter for {}", cursor, self.state().name);
 				self.state_mut().paint.set_alpha(150);
+				self.invalidate_paint();
 			}
 			WidgetEvent::OnCursorMove { .. } => {
 				//println!("Mouse Move for {} {}!!!", self.name, self.counter);
@@ -127,8 +135,9 @@ impl Widget for TestWidget {
 			WidgetEvent::OnCursorLeave { cursor } => {
 				println!("Mouse {} Leave for {}", cursor, self.state().name);
 				self.state_mut().paint.set_alpha(255);
+				self.invalidate_paint();
 			}
-			WidgetEvent::OnClick { mouse, pos, button } => {
+			WidgetEvent::OnClick { mouse, pos, button, .. } => {
 				println!(
 					"Mouse {} Click {:?} for {} '{}' at {:?}!!!",
 					mouse, button, self.state().name, self.state().counter, pos
@@ -143,7 +152,7 @@ impl Widget for TestWidget {
 			WidgetEvent::OnKeyDown {
 				keyboard,
 				key_physical,
-				key: _,
+				..
 			} => {
 				println!(
 					"Key '{}' down for {} from {}!",
@@ -153,7 +162,7 @@ impl Widget for TestWidget {
 			WidgetEvent::OnKeyUp {
 				keyboard,
 				key_physical,
-				key: _,
+				..
 			} => {
 				println!(
 					"Key '{}' up for {} from {}!",
@@ -163,6 +172,7 @@ impl Widget for TestWidget {
 			WidgetEvent::OnText {
 				keyboard,
 				character,
+				..
 			} => {
 				println!("Text '{}' for {} from {}!", character, self.state().name, keyboard);
 			}
@@ -172,6 +182,7 @@ impl Widget for TestWidget {
 			WidgetEvent::OnUnfocus { keyboard } => {
 				println!("Unfocused {} from {}!", self.state().name, keyboard);
 			}
+			_ => return Reply::unhandled(),
 		}
 		Reply::handled()
 	}
@@ -0,0 +1,632 @@
+use std::cell::{Ref, RefMut};
+use std::ops::Range;
+use crate::{
+	events::{Reply, WidgetEvent, WidgetFocusChange},
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Color, Font, Paint, Point, Rect};
+use winit::event::VirtualKeyCode;
+use crate::events::input::{CursorIcon, MouseButton};
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+
+pub struct TextAreaWidgetState {
+	leaf: LeafState,
+	text: String,
+	/// Byte index of the caret, always at a character boundary.
+	cursor: usize,
+	/// Horizontal offset the caret tries to keep when moving up and down between lines.
+	preferred_x: Option<scalar>,
+	/// Byte ranges of the visual lines, excluding the line breaks.
+	lines: Vec<Range<usize>>,
+	/// Width the lines got wrapped with.
+	wrap_width: scalar,
+	word_wrap: bool,
+	read_only: bool,
+	/// The caret should be brought into view with the next arrangement.
+	scroll_to_cursor: bool,
+	foreground_font: Font,
+	foreground: Paint,
+	on_text_changed: Option<Box<dyn Fn(&str, &str)>>,
+}
+
+pub type TextAreaWidget = WidgetImpl<TextAreaWidgetState>;
+
+pub struct TextAreaWidgetBuilder(TextAreaWidget);
+
+/// A Widget showing and editing multiple lines of text.
+///
+/// Lines get soft wrapped at word boundaries to the width the widget got arranged with,
+/// so if placed in a vertical [crate::widgets::ScrollPanel], the desired height grows with the
+/// number of lines and the panel scrolls to keep the caret visible.
+impl TextAreaWidget {
+	pub fn new() -> TextAreaWidgetBuilder {
+		let mut paint = Paint::default();
+		paint.set_color(Color::WHITE);
+		let font = Font::default();
+		let mut leaf = LeafState::default();
+		leaf.widget.focusable = true;
+		leaf.widget.cursor_icon = Some(CursorIcon::Text);
+		TextAreaWidgetBuilder(TextAreaWidgetState {
+			leaf,
+			text: String::new(),
+			cursor: 0,
+			preferred_x: None,
+			lines: vec![0..0],
+			wrap_width: 0.0,
+			word_wrap: true,
+			read_only: false,
+			scroll_to_cursor: false,
+			foreground_font: font,
+			foreground: paint,
+			on_text_changed: None,
+		}.into())
+	}
+
+	pub fn text(&self) -> String {
+		self.state().text.clone()
+	}
+
+	pub fn set_text(&self, text: String) {
+		if self.state().text != text {
+			let len = self.state().text.len();
+			self.replace_range(0..len, &text);
+			let cursor = self.state().cursor;
+			self.set_cursor(cursor);
+		}
+	}
+
+	/// Appends the given text at the end.
+	/// If the caret was at the end before, it stays at the end, which is useful for logs.
+	///
+	/// Only the last paragraph gets wrapped again, so appending stays cheap for long texts.
+	pub fn append(&self, text: &str) {
+		let len = self.state().text.len();
+		let follow = self.state().cursor == len;
+		self.replace_range(len..len, text);
+		if follow {
+			self.set_cursor(len + text.len());
+		}
+	}
+
+	/// Returns the byte index of the caret within the text.
+	pub fn cursor(&self) -> usize {
+		self.state().cursor
+	}
+
+	/// Moves the caret to the given byte index and makes sure it becomes visible.
+	/// Indices within a character move the caret to the start of that character.
+	pub fn set_cursor(&self, cursor: usize) {
+		let mut state = self.state_mut();
+		let mut cursor = cursor.min(state.text.len());
+		while !state.text.is_char_boundary(cursor) {
+			cursor -= 1;
+		}
+		state.cursor = cursor;
+		state.preferred_x = None;
+		state.scroll_to_cursor = true;
+		drop(state);
+		self.invalidate_layout();
+	}
+
+	/// Inserts the given text at the caret.
+	pub fn insert(&self, text: &str) {
+		let cursor = self.state().cursor;
+		self.edit(cursor..cursor, text);
+	}
+
+	/// Returns the index of the visual line containing the given byte index.
+	pub fn line_of(&self, cursor: usize) -> usize {
+		let state = self.state();
+		state
+			.lines
+			.partition_point(|line| line.start <= cursor)
+			.saturating_sub(1)
+	}
+
+	pub fn line_count(&self) -> usize {
+		self.state().lines.len()
+	}
+
+	fn line_height(&self) -> scalar {
+		let metrics = self.state().foreground_font.metrics().1;
+		metrics.bottom - metrics.top
+	}
+
+	fn edit(&self, range: Range<usize>, insert: &str) {
+		self.replace_range(range.clone(), insert);
+		self.set_cursor(range.start + insert.len());
+	}
+
+	/// Replaces the given byte range of the text.
+	/// Only the paragraphs touched by the range get wrapped again,
+	/// the lines of all other paragraphs just get moved.
+	fn replace_range(&self, range: Range<usize>, insert: &str) {
+		let mut state = self.state_mut();
+		// copying the whole text is only worth it if someone wants to know
+		let old_text = state.on_text_changed.as_ref().map(|_| state.text.clone());
+
+		let start = state.text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+		let old_end = state.text[range.end..].find('\n').map_or(state.text.len(), |i| range.end + i);
+		let removed = range.end - range.start;
+		state.text.replace_range(range, insert);
+
+		// visual lines never start at the end of their paragraph, except for empty paragraphs
+		let first = state.lines.partition_point(|line| line.start < start);
+		let last = state.lines.partition_point(|line| line.start <= old_end);
+		let mut lines = Vec::new();
+		let mut paragraph_start = start;
+		for paragraph in state.text[start..old_end + insert.len() - removed].split('\n') {
+			lines.extend(wrap_paragraph(&state, paragraph_start..paragraph_start + paragraph.len()));
+			paragraph_start += paragraph.len() + 1;
+		}
+		let moved = first + lines.len();
+		state.lines.splice(first..last, lines);
+		for line in &mut state.lines[moved..] {
+			*line = line.start + insert.len() - removed..line.end + insert.len() - removed;
+		}
+		drop(state);
+
+		if let Some(old_text) = old_text {
+			self.state().on_text_changed.as_ref().inspect(|d| d(&self.state().text, &old_text));
+		}
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+		self.invalidate_layout();
+	}
+
+	/// Splits the text into visual lines at line breaks and,
+	/// if word wrap is enabled, wherever a line gets wider than the wrap width.
+	fn wrap_lines(&self) {
+		let state = self.state();
+		let mut lines = Vec::new();
+		let mut start = 0;
+		for paragraph in state.text.split('\n') {
+			lines.extend(wrap_paragraph(&state, start..start + paragraph.len()));
+			start += paragraph.len() + 1;
+		}
+		drop(state);
+		self.state_mut().lines = lines;
+	}
+
+	/// Returns the horizontal offset of the given byte index within its line.
+	fn offset_in_line(&self, line: usize, cursor: usize) -> scalar {
+		let state = self.state();
+		let line = &state.lines[line];
+		let text = &state.text[line.start..cursor.clamp(line.start, line.end)];
+		if text.is_empty() {
+			return 0.0;
+		}
+		state.foreground_font.measure_str(text, Some(&state.foreground)).0
+	}
+
+	/// Returns the byte index of the given line closest to the given horizontal offset.
+	fn cursor_in_line(&self, line: usize, x: scalar) -> usize {
+		let state = self.state();
+		let range = state.lines[line].clone();
+		let text = &state.text[range.clone()];
+		let mut offset = 0.0;
+		for ((index, _), width) in text.char_indices().zip(char_widths(&state, text)) {
+			if x < offset + width / 2.0 {
+				return range.start + index;
+			}
+			offset += width;
+		}
+		range.end
+	}
+
+	/// Returns the byte index closest to the given absolute position.
+	fn cursor_at_pos(&self, pos: Vector2<scalar>) -> usize {
+		let local = pos - self.cached_geometry().absolute_pos();
+		let line_count = self.state().lines.len();
+		let line = ((local.y / self.line_height()).max(0.0) as usize).min(line_count - 1);
+		self.cursor_in_line(line, local.x)
+	}
+
+	/// Moves the caret the given amount of lines up (negative) or down (positive),
+	/// keeping its horizontal position.
+	fn move_lines(&self, lines: isize) {
+		let cursor = self.state().cursor;
+		let line = self.line_of(cursor);
+		let x = match self.state().preferred_x {
+			Some(x) => x,
+			None => self.offset_in_line(line, cursor),
+		};
+		let line_count = self.state().lines.len();
+		let target = (line as isize + lines).clamp(0, line_count as isize - 1) as usize;
+		let new_cursor = if target == line && lines < 0 {
+			0
+		} else if target == line && lines > 0 {
+			self.state().text.len()
+		} else {
+			self.cursor_in_line(target, x)
+		};
+		self.set_cursor(new_cursor);
+		self.state_mut().preferred_x = Some(x);
+	}
+
+	/// Area of the caret in window coordinates.
+	fn cursor_area(&self) -> Rect {
+		let cursor = self.state().cursor;
+		let line = self.line_of(cursor);
+		let x = self.offset_in_line(line, cursor);
+		let line_height = self.line_height();
+		let pos = self.cached_geometry().absolute_pos();
+		Rect::from_xywh(pos.x + x, pos.y + line as scalar * line_height, 1.0, line_height)
+	}
+}
+
+impl TextAreaWidgetBuilder {
+	pub fn text(self, text: String) -> Self {
+		self.0.state_mut().text = text;
+		self.0.wrap_lines();
+		self
+	}
+
+	/// Wrap lines that are wider than the widget, enabled by default.
+	pub fn word_wrap(self, word_wrap: bool) -> Self {
+		self.0.state_mut().word_wrap = word_wrap;
+		self
+	}
+
+	/// Disallows editing the text, e.g. for log viewers.
+	pub fn read_only(self, read_only: bool) -> Self {
+		self.0.state_mut().read_only = read_only;
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.state_mut().leaf.widget.tab_index = tab_index;
+		self
+	}
+
+	pub fn on_text_changed<F>(self, event: F) -> Self where F: Fn(&str, &str) + 'static {
+		self.0.state_mut().on_text_changed = Some(Box::new(event));
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<TextAreaWidget> {
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for TextAreaWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let height = self.state().lines.len() as scalar * self.line_height();
+		let state = self.state();
+		// wrapped text takes whatever width it gets
+		let width = if state.word_wrap {
+			0.0
+		} else {
+			state
+				.lines
+				.iter()
+				.map(|line| state.foreground_font.measure_str(&state.text[line.clone()], Some(&state.foreground)).0)
+				.fold(0.0, scalar::max)
+		};
+		Vector2::new(width, height)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let line_height = self.line_height();
+		let cursor = self.state().cursor;
+		let cursor_line = self.line_of(cursor);
+		let cursor_x = self.offset_in_line(cursor_line, cursor);
+		let state = self.state();
+		let font_metric = state.foreground_font.metrics().1;
+
+		// only draw the lines that are not clipped, e.g. by a scroll panel
+		let clip = painter
+			.local_clip_bounds()
+			.unwrap_or_else(|| Rect::from_wh(geometry.local_size().x, geometry.local_size().y));
+		let first = (clip.top / line_height).max(0.0) as usize;
+		let last = ((clip.bottom / line_height).max(0.0) as usize + 1).min(state.lines.len());
+		for (index, line) in state.lines.iter().enumerate().take(last).skip(first) {
+			painter.draw_str(
+				&state.text[line.clone()],
+				Point::new(0.0, index as scalar * line_height - font_metric.top),
+				&state.foreground_font,
+				&state.foreground,
+			);
+		}
+
+		if !state.read_only {
+			let top = cursor_line as scalar * line_height;
+			painter.draw_line(
+				Point::new(cursor_x, top),
+				Point::new(cursor_x, top + line_height),
+				&state.foreground,
+			);
+		}
+		layer + 1
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		let cursor = self.state().cursor;
+		let prev = prev_char_boundary(&self.state().text, cursor);
+		let next = next_char_boundary(&self.state().text, cursor);
+		let read_only = self.state().read_only;
+		match event {
+			WidgetEvent::OnText { character, .. } => {
+				if read_only {
+					return Reply::unhandled();
+				}
+				match *character {
+					'\u{8}' => {
+						if prev < cursor {
+							self.edit(prev..cursor, "");
+						}
+					}
+					'\u{7F}' => {
+						if next > cursor {
+							self.edit(cursor..next, "");
+						}
+					}
+					'\r' | '\n' => self.insert("\n"),
+					c if c.is_control() => return Reply::unhandled(),
+					c => self.insert(c.encode_utf8(&mut [0; 4])),
+				}
+				Reply::handled()
+			}
+			WidgetEvent::OnKeyDown { key: Some(key), .. } => match key {
+				VirtualKeyCode::Left => {
+					self.set_cursor(prev);
+					Reply::handled()
+				}
+				VirtualKeyCode::Right => {
+					self.set_cursor(next);
+					Reply::handled()
+				}
+				VirtualKeyCode::Up => {
+					self.move_lines(-1);
+					Reply::handled()
+				}
+				VirtualKeyCode::Down => {
+					self.move_lines(1);
+					Reply::handled()
+				}
+				VirtualKeyCode::PageUp | VirtualKeyCode::PageDown => {
+					let page = (self.cached_geometry().local_size().y / self.line_height()).max(1.0) as isize;
+					self.move_lines(if *key == VirtualKeyCode::PageUp { -page } else { page });
+					Reply::handled()
+				}
+				VirtualKeyCode::Home => {
+					let line = self.line_of(cursor);
+					let start = self.state().lines[line].start;
+					self.set_cursor(start);
+					Reply::handled()
+				}
+				VirtualKeyCode::End => {
+					let line = self.line_of(cursor);
+					let end = self.state().lines[line].end;
+					self.set_cursor(end);
+					Reply::handled()
+				}
+				_ => Reply::unhandled(),
+			},
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Left,
+				pos,
+				..
+			} => {
+				let target = self.cursor_at_pos(*pos);
+				self.set_cursor(target);
+				Reply::handled().take_focus(WidgetFocusChange::KeyboardList(vec![*mouse]))
+			}
+			WidgetEvent::OnFocus { .. } => Reply::handled(),
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry);
+
+		let width = geometry.local_size().x;
+		if self.state().wrap_width != width {
+			self.state_mut().wrap_width = width;
+			let line_count = self.state().lines.len();
+			self.wrap_lines();
+			// the desired height changed, so the parent has to give us a new size
+			if self.state().lines.len() != line_count {
+				self.get_parent().inspect(|p| p.get().invalidate_layout());
+			}
+		}
+
+		if self.state().scroll_to_cursor {
+			// keep requesting until nothing has to scroll anymore,
+			// as the scroll range may change with the following layout pass
+			let scrolled = self.bring_into_view(self.cursor_area());
+			self.state_mut().scroll_to_cursor = scrolled;
+		}
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for TextAreaWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
+
+/// Splits the given paragraph of the text into visual lines,
+/// if word wrap is enabled wherever a line gets wider than the wrap width.
+///
+/// The paragraph is a byte range of the text without any line breaks,
+/// so the returned lines are byte ranges of the text as well.
+fn wrap_paragraph(state: &TextAreaWidgetState, paragraph: Range<usize>) -> Vec<Range<usize>> {
+	let width = if state.word_wrap { state.wrap_width } else { 0.0 };
+	let text = &state.text[paragraph.clone()];
+	if width <= 0.0 || text.is_empty() {
+		return vec![paragraph];
+	}
+
+	let mut lines = Vec::new();
+	let mut line_start = 0;
+	let mut line_width = 0.0;
+	// byte index after the last whitespace of the line and the width of the line up to it
+	let mut break_at: Option<(usize, scalar)> = None;
+	for ((i, c), advance) in text.char_indices().zip(char_widths(state, text)) {
+		while i > line_start && line_width + advance > width {
+			// prefer to break after the last whitespace, otherwise break within the word
+			let (end, end_width) = break_at.take().unwrap_or((i, line_width));
+			lines.push(paragraph.start + line_start..paragraph.start + end);
+			line_start = end;
+			line_width -= end_width;
+		}
+		line_width += advance;
+		if c.is_whitespace() {
+			break_at = Some((i + c.len_utf8(), line_width));
+		}
+	}
+	lines.push(paragraph.start + line_start..paragraph.end);
+	lines
+}
+
+/// Returns the advance width of each character of the given text.
+fn char_widths(state: &TextAreaWidgetState, text: &str) -> Vec<scalar> {
+	let glyphs = state.foreground_font.str_to_glyphs_vec(text);
+	let mut widths = vec![0.0; glyphs.len()];
+	state
+		.foreground_font
+		.get_widths_bounds(&glyphs, Some(&mut widths), None, Some(&state.foreground));
+	widths
+}
+
+/// Returns the byte index of the character before the given byte index.
+fn prev_char_boundary(text: &str, cursor: usize) -> usize {
+	text[..cursor].chars().next_back().map_or(0, |c| cursor - c.len_utf8())
+}
+
+/// Returns the byte index of the character after the given byte index.
+fn next_char_boundary(text: &str, cursor: usize) -> usize {
+	text[cursor..].chars().next().map_or(cursor, |c| cursor + c.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::util::WidgetRef;
+	use super::{char_widths, wrap_paragraph, TextAreaWidget};
+	use skia_safe::scalar;
+
+	/// Creates a text area wrapping its text at the given width.
+	fn text_area(text: &str, wrap_width: scalar) -> WidgetRef<TextAreaWidget> {
+		let area = TextAreaWidget::new().build();
+		area.get().state_mut().wrap_width = wrap_width;
+		area.get().state_mut().text = text.to_string();
+		area.get().wrap_lines();
+		area
+	}
+
+	fn text_width(area: &WidgetRef<TextAreaWidget>, text: &str) -> scalar {
+		char_widths(&area.get().state(), text).iter().sum()
+	}
+
+	/// Wraps the whole text again and compares the result with the incrementally updated lines.
+	fn assert_lines_match_full_wrap(area: &WidgetRef<TextAreaWidget>) {
+		let lines = area.get().state().lines.clone();
+		area.get().wrap_lines();
+		assert_eq!(lines, area.get().state().lines, "text: {:?}", area.get().text());
+	}
+
+	/// A text area wrapping after every word with 4 letters.
+	fn word_per_line(text: &str) -> WidgetRef<TextAreaWidget> {
+		let area = text_area(text, 0.0);
+		let width = text_width(&area, "aaaa ") + 1.0;
+		area.get().state_mut().wrap_width = width;
+		area.get().wrap_lines();
+		area
+	}
+
+	#[test]
+	fn wrap_paragraph_breaks_after_whitespace() {
+		let area = word_per_line("xx\naaaa aaaa aaaa");
+
+		assert_eq!(wrap_paragraph(&area.get().state(), 3..17), vec![3..8, 8..13, 13..17]);
+		assert_eq!(area.get().state().lines, vec![0..2, 3..8, 8..13, 13..17]);
+	}
+
+	#[test]
+	fn wrap_paragraph_breaks_long_words() {
+		let area = text_area("aaaaaaaaaa", 0.0);
+		let width = text_width(&area, "aaaa") + 1.0;
+		area.get().state_mut().wrap_width = width;
+
+		assert_eq!(wrap_paragraph(&area.get().state(), 0..10), vec![0..4, 4..8, 8..10]);
+	}
+
+	#[test]
+	fn wrap_paragraph_keeps_empty_and_unwrapped_paragraphs() {
+		let area = word_per_line("aaaa aaaa\n\naaaa");
+
+		assert_eq!(wrap_paragraph(&area.get().state(), 10..10), vec![10..10]);
+		area.get().state_mut().word_wrap = false;
+		assert_eq!(wrap_paragraph(&area.get().state(), 0..9), vec![0..9]);
+	}
+
+	#[test]
+	fn replace_range_matches_full_wrap() {
+		let area = word_per_line("aaaa aaaa aaaa\nbbbb bbbb\n\ncccc cccc cccc");
+
+		// within a paragraph
+		area.get().replace_range(5..5, "dddd ");
+		assert_lines_match_full_wrap(&area);
+		// splitting a paragraph
+		area.get().replace_range(9..10, "\n");
+		assert_lines_match_full_wrap(&area);
+		// joining paragraphs
+		area.get().replace_range(9..10, " ");
+		assert_lines_match_full_wrap(&area);
+		// deleting across line breaks
+		area.get().replace_range(12..30, "");
+		assert_lines_match_full_wrap(&area);
+		// inserting several paragraphs
+		area.get().replace_range(0..0, "eeee\n\neeee eeee\n");
+		assert_lines_match_full_wrap(&area);
+		// deleting everything
+		let len = area.get().text().len();
+		area.get().replace_range(0..len, "");
+		assert_lines_match_full_wrap(&area);
+		assert_eq!(area.get().state().lines, vec![0..0]);
+	}
+
+	#[test]
+	fn append_matches_full_wrap() {
+		let area = word_per_line("aaaa aaaa");
+
+		area.get().append(" aaaa");
+		assert_lines_match_full_wrap(&area);
+		area.get().append("\n");
+		assert_lines_match_full_wrap(&area);
+		area.get().append("bbbb bbbb\ncccc");
+		assert_lines_match_full_wrap(&area);
+		area.get().append("");
+		assert_lines_match_full_wrap(&area);
+		assert_eq!(area.get().text(), "aaaa aaaa aaaa\nbbbb bbbb\ncccc");
+	}
+}
@@ -1,40 +1,127 @@
 use std::cell::{Ref, RefMut};
 use crate::{
-	paint::{Painter, TextStyle},
+	paint::{font_collection, Painter, TextSpan, TextStyle},
 	util::{Geometry, WidgetRef},
 	widgets::{Widget, WidgetState},
 };
 use cgmath::Vector2;
-use skia_safe::{scalar, Point};
+use skia_safe::{
+	scalar,
+	textlayout::{Paragraph, ParagraphBuilder, ParagraphStyle, TextDecoration},
+	FontStyle, Point,
+};
 use crate::widgets::{Arrangements, Children, WidgetImpl};
 use crate::widgets::leaf_widget::{LeafState, LeafWidget};
 
 pub struct TextBlockWidgetState {
 	leaf: LeafState,
-	text: String,
+	spans: Vec<TextSpan>,
 	text_style: TextStyle,
+	/// Laid out text, gets rebuilt if the spans change.
+	paragraph: Option<Paragraph>,
+	/// Width the paragraph got laid out with.
+	layout_width: scalar,
 }
 
 pub type TextBlockWidget = WidgetImpl<TextBlockWidgetState>;
 
 pub struct TextBlockWidgetBuilder(TextBlockWidget);
 
-/// A Widget showing some simple text.
+/// A Widget showing rich text made of styled spans.
 ///
-/// The desired size is the size the text would consume if the text were in one line.
+/// The text gets shaped with Skia's paragraph layout,
+/// so it supports bidirectional text and font fallback.
+/// It wraps to the width it gets arranged with.
+/// The desired size is the width the text would consume if the text were in one line
+/// and the height of the text wrapped to the width it got arranged with last.
 impl TextBlockWidget {
 	pub fn new() -> TextBlockWidgetBuilder {
 		TextBlockWidgetBuilder(TextBlockWidgetState {
 			leaf: Default::default(),
-			text: String::default(),
+			spans: Vec::new(),
 			text_style: TextStyle::default(),
+			paragraph: None,
+			layout_width: scalar::INFINITY,
 		}.into())
 	}
+
+	/// Returns the plain text of all spans.
+	pub fn text(&self) -> String {
+		self.state().spans.iter().map(|span| span.text.as_str()).collect()
+	}
+
+	/// Replaces the content with a single span using the default style.
+	pub fn set_text(&self, text: String) {
+		self.set_spans(vec![TextSpan::new(text)]);
+	}
+
+	pub fn set_spans(&self, spans: Vec<TextSpan>) {
+		let mut state = self.state_mut();
+		state.spans = spans;
+		state.paragraph = None;
+		drop(state);
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+		self.invalidate_paint();
+	}
+
+	/// Builds the paragraph if needed and lays it out with the current layout width.
+	fn ensure_paragraph(&self) {
+		let mut state = self.state_mut();
+		if state.paragraph.is_some() {
+			return;
+		}
+
+		let default_color = state.text_style.color.color();
+		let default_size = state.text_style.font.size();
+		let family = state.text_style.font.typeface().map(|typeface| typeface.family_name());
+
+		let mut builder = ParagraphBuilder::new(&ParagraphStyle::new(), font_collection());
+		for span in &state.spans {
+			let mut style = skia_safe::textlayout::TextStyle::new();
+			style.set_color(span.color.unwrap_or(default_color));
+			style.set_font_size(span.size.unwrap_or(default_size));
+			if let Some(family) = &family {
+				style.set_font_families(&[family]);
+			}
+			style.set_font_style(match (span.bold, span.italic) {
+				(true, true) => FontStyle::bold_italic(),
+				(true, false) => FontStyle::bold(),
+				(false, true) => FontStyle::italic(),
+				(false, false) => FontStyle::normal(),
+			});
+			if span.underline {
+				style.decoration_mut().ty = TextDecoration::UNDERLINE;
+			}
+			builder.push_style(&style);
+			builder.add_text(&span.text);
+			builder.pop();
+		}
+
+		let mut paragraph = builder.build();
+		paragraph.layout(state.layout_width);
+		state.paragraph = Some(paragraph);
+	}
 }
 
 impl TextBlockWidgetBuilder {
-	pub fn text(mut self, text: String) -> Self {
-		self.0.state_mut().text = text;
+	pub fn text(self, text: String) -> Self {
+		self.0.state_mut().spans = vec![TextSpan::new(text)];
+		self
+	}
+
+	/// Appends a styled span to the text.
+	pub fn span(self, span: TextSpan) -> Self {
+		self.0.state_mut().spans.push(span);
+		self
+	}
+
+	pub fn spans(self, spans: Vec<TextSpan>) -> Self {
+		self.0.state_mut().spans = spans;
+		self
+	}
+
+	pub fn text_style(self, text_style: TextStyle) -> Self {
+		self.0.state_mut().text_style = text_style;
 		self
 	}
 
@@ -53,23 +140,19 @@ impl Widget for TextBlockWidget {
 	}
 
 	fn paint(&self, _geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		self.ensure_paragraph();
 		let state = self.state();
-		painter.draw_str(
-			&state.text,
-			Point::new(0.0, 0.0),
-			&state.text_style.font,
-			&state.text_style.color,
-		);
+		if let Some(paragraph) = &state.paragraph {
+			paragraph.paint(painter, Point::new(0.0, 0.0));
+		}
 		layer
 	}
 
 	fn get_desired_size(&self) -> Vector2<scalar> {
+		self.ensure_paragraph();
 		let state = self.state();
-		let (_, rect) = state
-			.text_style
-			.font
-			.measure_str(&state.text, Some(&state.text_style.color));
-		Vector2::new(rect.height(), rect.width())
+		let paragraph = state.paragraph.as_ref().unwrap();
+		Vector2::new(paragraph.max_intrinsic_width().ceil(), paragraph.height().ceil())
 	}
 
 	fn get_children(&self) -> Children {
@@ -77,7 +160,24 @@ impl Widget for TextBlockWidget {
 	}
 
 	fn arrange_children(&self, geometry: Geometry) {
-		self.leaf_arrange_children(geometry)
+		self.leaf_arrange_children(geometry);
+
+		let width = geometry.local_size().x;
+		if self.state().layout_width == width {
+			return;
+		}
+		self.ensure_paragraph();
+		let mut state = self.state_mut();
+		state.layout_width = width;
+		let paragraph = state.paragraph.as_mut().unwrap();
+		let old_height = paragraph.height();
+		paragraph.layout(width);
+		let height_changed = paragraph.height() != old_height;
+		drop(state);
+		// the wrapped height changed, so the parent has to give us a new size
+		if height_changed {
+			self.get_parent().inspect(|p| p.get().invalidate_layout());
+		}
 	}
 
 	fn get_arranged_children(&self) -> Arrangements {
@@ -1,6 +1,8 @@
 use std::cell::{Ref, RefMut};
+use std::ops::Range;
 use crate::{
-	events::{Reply, WidgetEvent, WidgetFocusChange},
+	application::GUIApplication,
+	events::{standard, Reply, WidgetEvent, WidgetFocusChange},
 	paint::Painter,
 	util::{Geometry, WidgetRef},
 	widgets::{Widget, WidgetState},
@@ -9,16 +11,33 @@ use cgmath::Vector2;
 use skia_safe::{scalar, Color, Font, Paint, Point, Rect};
 use skia_safe::wrapper::ValueWrapper;
 use winit::event::VirtualKeyCode;
+use crate::events::input::{CursorIcon, Modifiers, MouseButton};
 use crate::widgets::{Arrangements, Children, WidgetImpl};
 use crate::widgets::leaf_widget::{LeafState, LeafWidget};
 
+/// State of the text edit that can be restored by undo and redo.
+#[derive(Clone)]
+struct TextEditHistoryEntry {
+	text: String,
+	cursor: usize,
+	selection_anchor: Option<usize>,
+}
+
 pub struct TextEditWidgetState {
 	leaf: LeafState,
 	text: String,
 	cursor: usize,
 	cursor_byte: usize,
+	/// Character index where the selection started, the selection spans from here to the cursor.
+	selection_anchor: Option<usize>,
 	foreground_font: Font,
 	foreground: Paint,
+	selection: Paint,
+	undo_stack: Vec<TextEditHistoryEntry>,
+	redo_stack: Vec<TextEditHistoryEntry>,
+	is_selecting: bool,
+	/// Text currently composed with the input method editor and the byte range of its cursor.
+	preedit: Option<(String, Option<(usize, usize)>)>,
 	on_text_changed: Option<Box<dyn Fn(&str, &str)>>,
 }
 
@@ -30,42 +49,266 @@ impl TextEditWidget {
 	pub fn new() -> TextEditWidgetBuilder {
 		let mut paint = Paint::default();
 		paint.set_color(Color::WHITE);
+		let mut selection = Paint::default();
+		selection.set_color(Color::from_argb(0x80, 0x33, 0x99, 0xFF));
 		let font = Font::default();
+		let mut leaf = LeafState::default();
+		leaf.widget.focusable = true;
+		leaf.widget.cursor_icon = Some(CursorIcon::Text);
 		TextEditWidgetBuilder(TextEditWidgetState {
-			leaf: Default::default(),
+			leaf,
 			text: "".to_string(),
 			cursor: 0,
 			cursor_byte: 0,
+			selection_anchor: None,
 			foreground_font: font,
 			foreground: paint,
+			selection,
+			undo_stack: Vec::new(),
+			redo_stack: Vec::new(),
+			is_selecting: false,
+			preedit: None,
 			on_text_changed: None,
 		}.into())
 	}
 
+	pub fn text(&self) -> String {
+		self.state().text.clone()
+	}
+
+	pub fn cursor(&self) -> usize {
+		self.state().cursor
+	}
+
 	pub fn set_cursor(&self, cursor: usize) {
 		let mut state = self.state_mut();
-		state.cursor = cursor.clamp(0, state.text.len());
-		state.cursor_byte = state
-			.text
-			.char_indices()
-			.map(|(i, _)| i)
-			.nth(state.cursor)
-			.unwrap_or(state.text.len());
+		state.cursor = cursor.clamp(0, state.text.chars().count());
+		state.cursor_byte = byte_index(&state.text, state.cursor);
+		drop(state);
+		self.invalidate_paint();
 	}
 
+	/// Replaces the whole text.
+	/// Clears the selection and the undo history.
 	pub fn set_text(&self, text: String) {
 		let old_text = self.state().text.clone();
 		if old_text != text {
-			self.state().on_text_changed.as_ref().inspect(|d| d(&text, &old_text));
-			self.state_mut().text = text;
-			self.get_parent().inspect(|p| p.get().arrange_children(p.get().cached_geometry()));
+			self.replace_text(text);
+			let mut state = self.state_mut();
+			state.selection_anchor = None;
+			state.undo_stack.clear();
+			state.redo_stack.clear();
+			let cursor = state.cursor;
+			drop(state);
+			self.set_cursor(cursor);
+		}
+	}
+
+	/// Returns the selected character range, if anything is selected.
+	pub fn selection(&self) -> Option<Range<usize>> {
+		let state = self.state();
+		let anchor = state.selection_anchor?;
+		if anchor == state.cursor {
+			None
+		} else {
+			Some(anchor.min(state.cursor)..anchor.max(state.cursor))
+		}
+	}
+
+	/// Selects the given character range, leaving the cursor at its end.
+	pub fn select(&self, range: Range<usize>) {
+		let len = self.state().text.chars().count();
+		self.state_mut().selection_anchor = Some(range.start.min(len));
+		self.set_cursor(range.end);
+	}
+
+	pub fn select_all(&self) {
+		let len = self.state().text.chars().count();
+		self.select(0..len);
+	}
+
+	pub fn selected_text(&self) -> String {
+		match self.selection() {
+			Some(range) => self.state().text.chars().skip(range.start).take(range.len()).collect(),
+			None => String::new(),
+		}
+	}
+
+	/// Replaces the selection (or inserts at the cursor if nothing is selected) with the given text
+	/// and records the change in the undo history.
+	pub fn insert(&self, text: &str) {
+		let range = self.selection().unwrap_or_else(|| {
+			let cursor = self.state().cursor;
+			cursor..cursor
+		});
+		self.edit(range, text);
+	}
+
+	/// Reverts the last change of the text.
+	/// Returns false if there is nothing to undo.
+	pub fn undo(&self) -> bool {
+		let entry = self.state_mut().undo_stack.pop();
+		match entry {
+			Some(entry) => {
+				let current = self.history_entry();
+				self.state_mut().redo_stack.push(current);
+				self.restore_history_entry(entry);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Reapplies the last change reverted by [TextEditWidget::undo()].
+	/// Returns false if there is nothing to redo.
+	pub fn redo(&self) -> bool {
+		let entry = self.state_mut().redo_stack.pop();
+		match entry {
+			Some(entry) => {
+				let current = self.history_entry();
+				self.state_mut().undo_stack.push(current);
+				self.restore_history_entry(entry);
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn copy(&self) {
+		if self.selection().is_some() {
+			GUIApplication::get().platform_context_mut().set_clipboard(self.selected_text());
+		}
+	}
+
+	pub fn cut(&self) {
+		if let Some(range) = self.selection() {
+			self.copy();
+			self.edit(range, "");
+		}
+	}
+
+	pub fn paste(&self) {
+		let text = GUIApplication::get().platform_context().get_clipboard();
+		if let Some(text) = text {
+			// single line edit, so drop line breaks and other control characters
+			let text: String = text.chars().filter(|c| !c.is_control()).collect();
+			self.insert(&text);
+		}
+	}
+
+	/// Replaces the delegate called with the new and the old text whenever the text changed.
+	/// It gets called while the edit is borrowed, so it must not access the edit.
+	pub fn set_on_text_changed<F>(&self, event: F) where F: Fn(&str, &str) + 'static {
+		self.state_mut().on_text_changed = Some(Box::new(event));
+	}
+
+	/// Moves the cursor to the given character index.
+	/// If `select` is true, the selection gets extended to the new position,
+	/// otherwise the selection gets cleared.
+	fn move_cursor(&self, cursor: usize, select: bool) {
+		let mut state = self.state_mut();
+		if select {
+			if state.selection_anchor.is_none() {
+				state.selection_anchor = Some(state.cursor);
+			}
+		} else {
+			state.selection_anchor = None;
+		}
+		drop(state);
+		self.set_cursor(cursor);
+	}
+
+	/// Replaces the given character range with the given text, puts the cursor after
+	/// the inserted text and records the change in the undo history.
+	fn edit(&self, range: Range<usize>, insert: &str) {
+		if range.is_empty() && insert.is_empty() {
+			return;
+		}
+		let entry = self.history_entry();
+		let mut state = self.state_mut();
+		state.undo_stack.push(entry);
+		state.redo_stack.clear();
+		let mut text = state.text.clone();
+		let start = byte_index(&text, range.start);
+		let end = byte_index(&text, range.end);
+		text.replace_range(start..end, insert);
+		state.selection_anchor = None;
+		drop(state);
+		self.replace_text(text);
+		self.set_cursor(range.start + insert.chars().count());
+	}
+
+	fn replace_text(&self, text: String) {
+		let old_text = std::mem::replace(&mut self.state_mut().text, text);
+		self.state().on_text_changed.as_ref().inspect(|d| d(&self.state().text, &old_text));
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+	}
+
+	fn history_entry(&self) -> TextEditHistoryEntry {
+		let state = self.state();
+		TextEditHistoryEntry {
+			text: state.text.clone(),
+			cursor: state.cursor,
+			selection_anchor: state.selection_anchor,
+		}
+	}
+
+	fn restore_history_entry(&self, entry: TextEditHistoryEntry) {
+		self.replace_text(entry.text);
+		self.state_mut().selection_anchor = entry.selection_anchor;
+		self.set_cursor(entry.cursor);
+	}
+
+	/// Returns the x offset of the given character index relative to the start of the text.
+	fn offset_of(&self, cursor: usize) -> scalar {
+		let state = self.state();
+		let end = byte_index(&state.text, cursor);
+		if end == 0 {
+			return 0.0;
+		}
+		state.foreground_font.measure_str(&state.text[0..end], Some(&state.foreground)).0
+	}
+
+	/// Area of the caret in window coordinates, including the cursor within the preedit text.
+	fn cursor_area(&self) -> Rect {
+		let mut x = self.offset_of(self.state().cursor);
+		let state = self.state();
+		if let Some((preedit, Some((cursor, _)))) = &state.preedit {
+			x += state.foreground_font.measure_str(&preedit[0..*cursor], Some(&state.foreground)).0;
+		}
+		let geometry = self.cached_geometry();
+		let font_metric = state.foreground_font.metrics();
+		let line_height = font_metric.1.bottom - font_metric.1.top;
+		let top = (geometry.local_size().y - line_height) / 2.0;
+		let pos = geometry.absolute_pos();
+		Rect::from_xywh(pos.x + x, pos.y + top, 1.0, line_height)
+	}
+
+	/// Returns the character index closest to the given absolute position.
+	fn cursor_at_pos(&self, pos: Vector2<scalar>) -> usize {
+		let x = pos.x - self.cached_geometry().absolute_pos().x;
+		let len = self.state().text.chars().count();
+		let mut prev_offset = 0.0;
+		for cursor in 1..=len {
+			let offset = self.offset_of(cursor);
+			if x < offset {
+				return if x - prev_offset < offset - x { cursor - 1 } else { cursor };
+			}
+			prev_offset = offset;
 		}
+		len
 	}
 }
 
 impl TextEditWidgetBuilder {
-	pub fn on_text_changed<F>(mut self, event: F) where F: Fn(&str, &str) + 'static {
+	pub fn on_text_changed<F>(self, event: F) -> Self where F: Fn(&str, &str) + 'static {
 		self.0.state_mut().on_text_changed = Some(Box::new(event));
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.state_mut().leaf.widget.tab_index = tab_index;
+		self
 	}
 
 	pub fn build(self) -> WidgetRef<TextEditWidget> {
@@ -97,82 +340,236 @@ impl Widget for TextEditWidget {
 	}
 
 	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let selection = self
+			.selection()
+			.map(|selection| (self.offset_of(selection.start), self.offset_of(selection.end)));
+		let cursor_x = self.offset_of(self.state().cursor);
 		let state = self.state();
-		let width = if state.text.len() >= state.cursor && state.cursor > 0 {
-			state.foreground_font
-				.measure_str(&state.text[0..state.cursor_byte], Some(&state.foreground))
-		} else {
-			(0.0, Rect::default())
-		};
 		let center = geometry.local_size() / 2.0;
 		let font_metric = state.foreground_font.metrics();
 		let line_height = font_metric.1.bottom - font_metric.1.top;
 		let base_line = center.y - line_height / 2.0 - font_metric.1.top;
-		painter.draw_str(
-			&state.text,
-			Point::new(0.0, base_line),
-			&state.foreground_font,
-			&state.foreground,
-		);
-		painter.draw_line(
-			Point::new(width.0, base_line + font_metric.1.top),
-			Point::new(width.0, base_line + font_metric.1.bottom),
-			&state.foreground,
-		);
+		let measure = |text: &str| state.foreground_font.measure_str(text, Some(&state.foreground)).0;
+
+		let width = match &state.preedit {
+			Some((preedit, preedit_cursor)) => {
+				// the preedit text gets shown at the cursor, underlined
+				let preedit_width = measure(preedit);
+				let text = format!(
+					"{}{}{}",
+					&state.text[0..state.cursor_byte],
+					preedit,
+					&state.text[state.cursor_byte..]
+				);
+				painter.draw_str(&text, Point::new(0.0, base_line), &state.foreground_font, &state.foreground);
+				let underline = base_line + font_metric.1.underline_position().unwrap_or(1.0);
+				painter.draw_line(
+					Point::new(cursor_x, underline),
+					Point::new(cursor_x + preedit_width, underline),
+					&state.foreground,
+				);
+				preedit_cursor.map(|(cursor, _)| cursor_x + measure(&preedit[0..cursor]))
+			}
+			None => {
+				if let Some((left, right)) = selection {
+					painter.draw_rect(
+						Rect::new(left, base_line + font_metric.1.top, right, base_line + font_metric.1.bottom),
+						&state.selection,
+					);
+				}
+				painter.draw_str(
+					&state.text,
+					Point::new(0.0, base_line),
+					&state.foreground_font,
+					&state.foreground,
+				);
+				Some(cursor_x)
+			}
+		};
+		if let Some(width) = width {
+			painter.draw_line(
+				Point::new(width, base_line + font_metric.1.top),
+				Point::new(width, base_line + font_metric.1.bottom),
+				&state.foreground,
+			);
+		}
 		layer + 1
 	}
 
 	fn on_event(&self, event: &WidgetEvent) -> Reply {
-		let cursor_byte = self.state().cursor_byte;
 		let cursor = self.state().cursor;
-		let text = self.state().text.clone();
-		match event {
-			WidgetEvent::OnText {
-				keyboard: _,
-				character,
-			} => {
+		let len = self.state().text.chars().count();
+		let modifiers = match event {
+			WidgetEvent::OnText { modifiers, .. }
+			| WidgetEvent::OnKeyDown { modifiers, .. }
+			| WidgetEvent::OnMouseButtonDown { modifiers, .. } => *modifiers,
+			_ => Modifiers::empty(),
+		};
+		let ctrl = modifiers.ctrl();
+		let shift = modifiers.shift();
+		let reply = match event {
+			WidgetEvent::OnText { character, .. } => {
 				match *character {
 					'\u{8}' => {
-						if cursor > 0 {
-							self.set_cursor(cursor - 1);
-							self.state_mut().text.remove(cursor_byte);
+						if let Some(selection) = self.selection() {
+							self.edit(selection, "");
+						} else if cursor > 0 {
+							let start = if ctrl { prev_word_boundary(&self.state().text, cursor) } else { cursor - 1 };
+							self.edit(start..cursor, "");
 						}
 					}
 					'\u{7F}' => {
-						if cursor_byte < text.len() {
-							self.state_mut().text.remove(cursor_byte);
+						if let Some(selection) = self.selection() {
+							self.edit(selection, "");
+						} else if cursor < len {
+							let end = if ctrl { next_word_boundary(&self.state().text, cursor) } else { cursor + 1 };
+							self.edit(cursor..end, "");
 						}
 					}
-					_ => {
-						self.state_mut().text.insert(cursor_byte, *character);
-						self.set_cursor(cursor + 1);
-					}
+					// control characters like tab or the ones sent together with ctrl shortcuts
+					c if c.is_control() => return Reply::unhandled(),
+					c => self.insert(c.encode_utf8(&mut [0; 4])),
 				}
-				self.get_parent().unwrap().get().arrange_children(self.get_parent().unwrap().get().cached_geometry());
 				Reply::handled()
 			}
-			WidgetEvent::OnKeyDown {
-				keyboard: _,
-				key_physical: _,
-				key: Some(key),
-			} => match key {
+			WidgetEvent::OnKeyDown { key: Some(key), .. } => match key {
 				VirtualKeyCode::Left => {
-					self.set_cursor(cursor.saturating_sub(1));
+					let target = match self.selection() {
+						Some(selection) if !shift && !ctrl => selection.start,
+						_ if ctrl => prev_word_boundary(&self.state().text, cursor),
+						_ => cursor.saturating_sub(1),
+					};
+					self.move_cursor(target, shift);
 					Reply::handled()
 				}
 				VirtualKeyCode::Right => {
-					self.set_cursor(cursor + 1);
+					let target = match self.selection() {
+						Some(selection) if !shift && !ctrl => selection.end,
+						_ if ctrl => next_word_boundary(&self.state().text, cursor),
+						_ => cursor + 1,
+					};
+					self.move_cursor(target, shift);
+					Reply::handled()
+				}
+				VirtualKeyCode::Home => {
+					self.move_cursor(0, shift);
+					Reply::handled()
+				}
+				VirtualKeyCode::End => {
+					self.move_cursor(len, shift);
+					Reply::handled()
+				}
+				_ => Reply::unhandled(),
+			},
+			WidgetEvent::OnCommand { command, .. } => match command.as_str() {
+				standard::SELECT_ALL => {
+					self.select_all();
+					Reply::handled()
+				}
+				standard::COPY => {
+					self.copy();
+					Reply::handled()
+				}
+				standard::CUT => {
+					self.cut();
+					Reply::handled()
+				}
+				standard::PASTE => {
+					self.paste();
+					Reply::handled()
+				}
+				standard::UNDO => {
+					self.undo();
+					Reply::handled()
+				}
+				standard::REDO => {
+					self.redo();
 					Reply::handled()
 				}
 				_ => Reply::unhandled(),
 			},
-			WidgetEvent::OnClick {
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Left,
+				pos,
+				..
+			} => {
+				let target = self.cursor_at_pos(*pos);
+				self.move_cursor(target, shift);
+				self.state_mut().is_selecting = true;
+				Reply::handled()
+					.take_focus(WidgetFocusChange::KeyboardList(vec![*mouse]))
+					.capture_cursor(*mouse)
+			}
+			WidgetEvent::OnDoubleClick {
+				button: MouseButton::Left,
+				pos,
+				..
+			} => {
+				let cursor = self.cursor_at_pos(*pos);
+				let range = word_at(&self.state().text, cursor);
+				self.select(range);
+				Reply::handled()
+			}
+			// the text edit has a single line only, so selecting the line selects everything
+			WidgetEvent::OnTripleClick {
+				button: MouseButton::Left,
+				..
+			} => {
+				self.select_all();
+				Reply::handled()
+			}
+			WidgetEvent::OnCursorMove { pos, .. } => {
+				if self.state().is_selecting {
+					let target = self.cursor_at_pos(*pos);
+					self.move_cursor(target, true);
+					Reply::handled()
+				} else {
+					Reply::unhandled()
+				}
+			}
+			WidgetEvent::OnMouseButtonUp {
 				mouse,
-				button: _,
-				pos: _,
-			} => Reply::handled().take_focus(WidgetFocusChange::KeyboardList(vec![*mouse])),
+				button: MouseButton::Left,
+				..
+			} => {
+				self.state_mut().is_selecting = false;
+				Reply::handled().release_cursor(*mouse)
+			}
+			WidgetEvent::OnClick { mouse, .. } => Reply::handled().take_focus(WidgetFocusChange::KeyboardList(vec![*mouse])),
+			WidgetEvent::OnImePreedit { text, cursor, .. } => {
+				self.state_mut().preedit = if text.is_empty() {
+					None
+				} else {
+					Some((text.clone(), *cursor))
+				};
+				self.invalidate_paint();
+				Reply::handled()
+			}
+			WidgetEvent::OnImeCommit { text, .. } => {
+				self.state_mut().preedit = None;
+				self.insert(text);
+				Reply::handled()
+			}
+			WidgetEvent::OnImeEnabled { .. } => Reply::handled(),
+			WidgetEvent::OnImeDisabled { .. } => {
+				self.state_mut().preedit = None;
+				self.invalidate_paint();
+				Reply::handled()
+			}
 			WidgetEvent::OnFocus { .. } => Reply::handled(),
+			WidgetEvent::OnUnfocus { .. } => {
+				self.state_mut().preedit = None;
+				self.invalidate_paint();
+				Reply::handled()
+			}
 			_ => Reply::unhandled(),
+		};
+		// keep the candidate window of the input method editor next to the caret
+		if reply.is_handled() {
+			reply.ime_cursor_area(self.cursor_area())
+		} else {
+			reply
 		}
 	}
 
@@ -189,7 +586,7 @@ impl Widget for TextEditWidget {
 	}
 
 	fn cached_geometry(&self) -> Geometry {
-		self.cached_geometry()
+		self.leaf_cached_geometry()
 	}
 }
 
@@ -202,3 +599,214 @@ impl LeafWidget for TextEditWidget {
 		self.widget_state_mut(|v| &mut v.leaf)
 	}
 }
+
+/// Converts a character index of the text into a byte index.
+fn byte_index(text: &str, cursor: usize) -> usize {
+	text.char_indices()
+		.map(|(i, _)| i)
+		.nth(cursor)
+		.unwrap_or(text.len())
+}
+
+/// Returns the character index of the start of the word before the given cursor.
+fn prev_word_boundary(text: &str, cursor: usize) -> usize {
+	let chars: Vec<char> = text.chars().take(cursor).collect();
+	let mut index = chars.len();
+	while index > 0 && chars[index - 1].is_whitespace() {
+		index -= 1;
+	}
+	while index > 0 && !chars[index - 1].is_whitespace() {
+		index -= 1;
+	}
+	index
+}
+
+/// Returns the character range of the word at the given cursor,
+/// or of the whitespace at the cursor if it is not within a word.
+fn word_at(text: &str, cursor: usize) -> Range<usize> {
+	let chars: Vec<char> = text.chars().collect();
+	let cursor = cursor.min(chars.len());
+	// prefer the character after the cursor, unless the cursor is at the end
+	let whitespace = match chars.get(cursor).or_else(|| cursor.checked_sub(1).and_then(|i| chars.get(i))) {
+		Some(c) => c.is_whitespace(),
+		None => return cursor..cursor,
+	};
+	let mut start = cursor;
+	while start > 0 && chars[start - 1].is_whitespace() == whitespace {
+		start -= 1;
+	}
+	let mut end = cursor;
+	while end < chars.len() && chars[end].is_whitespace() == whitespace {
+		end += 1;
+	}
+	start..end
+}
+
+/// Returns the character index of the end of the word after the given cursor.
+fn next_word_boundary(text: &str, cursor: usize) -> usize {
+	let chars: Vec<char> = text.chars().collect();
+	let mut index = cursor.min(chars.len());
+	while index < chars.len() && chars[index].is_whitespace() {
+		index += 1;
+	}
+	while index < chars.len() && !chars[index].is_whitespace() {
+		index += 1;
+	}
+	index
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		events::{input::{Modifiers, MouseButton}, WidgetEvent},
+		util::WidgetRef,
+		widgets::Widget,
+	};
+	use super::{next_word_boundary, prev_word_boundary, word_at, TextEditWidget};
+	use cgmath::Vector2;
+
+	fn text_edit(text: &str) -> WidgetRef<TextEditWidget> {
+		let edit = TextEditWidget::new().build();
+		edit.get().set_text(text.to_string());
+		edit
+	}
+
+	fn type_char(edit: &WidgetRef<TextEditWidget>, character: char, modifiers: Modifiers) {
+		let event = WidgetEvent::OnText {
+			keyboard: 0,
+			character,
+			modifiers,
+		};
+		assert!(edit.get().on_event(&event).is_handled());
+	}
+
+	#[test]
+	fn undo_and_redo_restore_changes_in_order() {
+		let edit = text_edit("ab");
+		edit.get().set_cursor(2);
+		edit.get().insert("c");
+		edit.get().insert("d");
+
+		assert!(edit.get().undo());
+		assert_eq!((edit.get().text(), edit.get().cursor()), ("abc".to_string(), 3));
+		assert!(edit.get().undo());
+		assert_eq!((edit.get().text(), edit.get().cursor()), ("ab".to_string(), 2));
+		assert!(!edit.get().undo());
+
+		assert!(edit.get().redo());
+		assert_eq!(edit.get().text(), "abc");
+		assert!(edit.get().redo());
+		assert_eq!(edit.get().text(), "abcd");
+		assert!(!edit.get().redo());
+	}
+
+	#[test]
+	fn editing_after_undo_clears_redo() {
+		let edit = text_edit("");
+		edit.get().insert("a");
+		edit.get().insert("b");
+		edit.get().undo();
+
+		edit.get().insert("c");
+
+		assert!(!edit.get().redo());
+		assert_eq!(edit.get().text(), "ac");
+		assert!(edit.get().undo());
+		assert_eq!(edit.get().text(), "a");
+	}
+
+	#[test]
+	fn undo_restores_replaced_selection() {
+		let edit = text_edit("hello wörld");
+		edit.get().select(6..11);
+		assert_eq!(edit.get().selected_text(), "wörld");
+
+		edit.get().insert("you");
+
+		assert_eq!(edit.get().text(), "hello you");
+		assert_eq!(edit.get().selection(), None);
+		assert_eq!(edit.get().cursor(), 9);
+		edit.get().undo();
+		assert_eq!(edit.get().text(), "hello wörld");
+		assert_eq!(edit.get().selection(), Some(6..11));
+	}
+
+	#[test]
+	fn typing_replaces_selection() {
+		let edit = text_edit("one two");
+		edit.get().select(4..7);
+
+		type_char(&edit, 'x', Modifiers::empty());
+
+		assert_eq!(edit.get().text(), "one x");
+		assert_eq!(edit.get().cursor(), 5);
+	}
+
+	#[test]
+	fn ctrl_backspace_and_delete_remove_words() {
+		let edit = text_edit("one two  three");
+		edit.get().set_cursor(9);
+
+		type_char(&edit, '\u{8}', Modifiers::CTRL);
+		assert_eq!((edit.get().text(), edit.get().cursor()), ("one three".to_string(), 4));
+
+		type_char(&edit, '\u{7F}', Modifiers::CTRL);
+		assert_eq!((edit.get().text(), edit.get().cursor()), ("one ".to_string(), 4));
+
+		type_char(&edit, '\u{8}', Modifiers::empty());
+		assert_eq!(edit.get().text(), "one");
+	}
+
+	#[test]
+	fn backspace_and_delete_remove_selection() {
+		let edit = text_edit("abcdef");
+		edit.get().select(1..3);
+		type_char(&edit, '\u{8}', Modifiers::CTRL);
+		assert_eq!(edit.get().text(), "adef");
+
+		edit.get().select(3..1);
+		type_char(&edit, '\u{7F}', Modifiers::empty());
+		assert_eq!(edit.get().text(), "af");
+	}
+
+	#[test]
+	fn word_boundaries_skip_whitespace_first() {
+		let text = "one  two three";
+		assert_eq!(prev_word_boundary(text, 14), 9);
+		assert_eq!(prev_word_boundary(text, 9), 5);
+		assert_eq!(prev_word_boundary(text, 5), 0);
+		assert_eq!(prev_word_boundary(text, 0), 0);
+
+		assert_eq!(next_word_boundary(text, 0), 3);
+		assert_eq!(next_word_boundary(text, 3), 8);
+		assert_eq!(next_word_boundary(text, 8), 14);
+		assert_eq!(next_word_boundary(text, 14), 14);
+		assert_eq!(next_word_boundary(text, 20), 14);
+	}
+
+	#[test]
+	fn word_at_selects_word_or_whitespace() {
+		let text = "one  twö three";
+		assert_eq!(word_at(text, 0), 0..3);
+		assert_eq!(word_at(text, 6), 5..8);
+		// the character after the cursor counts, so the end of a word selects the following whitespace
+		assert_eq!(word_at(text, 3), 3..5);
+		assert_eq!(word_at(text, 14), 9..14);
+		assert_eq!(word_at("", 0), 0..0);
+	}
+
+	#[test]
+	fn double_click_selects_word() {
+		let edit = text_edit("one two");
+		let event = WidgetEvent::OnDoubleClick {
+			mouse: 0,
+			button: MouseButton::Left,
+			pos: Vector2::new(0.0, 0.0),
+			modifiers: Modifiers::empty(),
+		};
+
+		assert!(edit.get().on_event(&event).is_handled());
+
+		assert_eq!(edit.get().selected_text(), "one");
+	}
+}
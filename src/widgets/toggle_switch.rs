@@ -0,0 +1,202 @@
+use std::cell::{Ref, RefMut};
+use crate::{
+	events::{Reply, WidgetEvent},
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{ButtonStyle, Widget, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Point, Rect};
+use crate::widgets::{Arrangements, Children, WidgetImpl};
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+use crate::widgets::pressable::{PressableState, PressableWidget};
+
+const TRACK_SIZE: Vector2<scalar> = Vector2::new(32.0, 16.0);
+/// Space between the knob and the border of the track.
+const KNOB_INSET: scalar = 3.0;
+
+pub struct ToggleSwitchWidgetState {
+	leaf: LeafState,
+	pressable: PressableState,
+	on: bool,
+	label: String,
+	style: ButtonStyle,
+	on_toggled: Option<Box<dyn Fn(bool)>>,
+}
+
+pub type ToggleSwitchWidget = WidgetImpl<ToggleSwitchWidgetState>;
+
+pub struct ToggleSwitchWidgetBuilder(ToggleSwitchWidget);
+
+/// A switch with an optional label next to it, that gets switched on and off by activating it.
+///
+/// The knob sits on the right while switched on.
+impl ToggleSwitchWidget {
+	pub fn new() -> ToggleSwitchWidgetBuilder {
+		let mut leaf = LeafState::default();
+		leaf.widget.focusable = true;
+		ToggleSwitchWidgetBuilder(ToggleSwitchWidgetState {
+			leaf,
+			pressable: Default::default(),
+			on: false,
+			label: String::new(),
+			style: Default::default(),
+			on_toggled: None,
+		}.into())
+	}
+
+	pub fn is_on(&self) -> bool {
+		self.state().on
+	}
+
+	pub fn set_on(&self, on: bool) {
+		if self.state().on == on {
+			return;
+		}
+		self.state_mut().on = on;
+		self.invalidate_paint();
+		let delegate = self.state_mut().on_toggled.take();
+		if let Some(delegate) = delegate {
+			delegate(on);
+			self.state_mut().on_toggled = Some(delegate);
+		}
+	}
+
+	pub fn set_label(&self, label: String) {
+		self.state_mut().label = label;
+		self.get_parent().inspect(|p| p.get().invalidate_layout());
+		self.invalidate_paint();
+	}
+
+	pub fn is_enabled(&self) -> bool {
+		self.pressable_is_enabled()
+	}
+
+	pub fn set_enabled(&self, enabled: bool) {
+		self.pressable_set_enabled(enabled);
+	}
+}
+
+impl ToggleSwitchWidgetBuilder {
+	pub fn on(self, on: bool) -> Self {
+		self.0.state_mut().on = on;
+		self
+	}
+
+	pub fn label(self, label: impl Into<String>) -> Self {
+		self.0.state_mut().label = label.into();
+		self
+	}
+
+	/// Called with the new state whenever the switch gets switched on or off.
+	pub fn on_toggled<F>(self, func: F) -> Self where F: Fn(bool) + 'static {
+		self.0.state_mut().on_toggled = Some(Box::new(func));
+		self
+	}
+
+	pub fn enabled(self, enabled: bool) -> Self {
+		let mut state = self.0.state_mut();
+		state.pressable.enabled = enabled;
+		state.leaf.widget.focusable = enabled;
+		drop(state);
+		self
+	}
+
+	pub fn style(self, style: ButtonStyle) -> Self {
+		self.0.state_mut().style = style;
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.state_mut().leaf.widget.tab_index = tab_index;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<ToggleSwitchWidget> {
+		WidgetRef::new(self.0)
+	}
+}
+
+impl Widget for ToggleSwitchWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let state = self.state();
+		let style = &state.style;
+		let track = Rect::from_xywh(0.0, (size.y - TRACK_SIZE.y) / 2.0, TRACK_SIZE.x, TRACK_SIZE.y);
+		let radius = TRACK_SIZE.y / 2.0;
+		if state.on && state.pressable.enabled {
+			painter.draw_round_rect(track, radius, radius, &style.accent);
+		} else {
+			painter.draw_round_rect(track, radius, radius, style.background(&state.pressable));
+		}
+		let knob_x = if state.on { track.right - radius } else { track.left + radius };
+		painter.draw_circle(
+			Point::new(knob_x, track.center_y()),
+			radius - KNOB_INSET,
+			style.label_paint(&state.pressable),
+		);
+		style.draw_focus(painter, track.with_outset((2.0, 2.0)), radius + 2.0, &state.pressable);
+		if !state.label.is_empty() {
+			style.draw_label(painter, &state.label, TRACK_SIZE.x + style.padding, size.y, &state.pressable);
+		}
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		state.style.indicator_size(TRACK_SIZE, &state.label)
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		self.pressable_on_event(event)
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for ToggleSwitchWidget {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
+
+impl PressableWidget for ToggleSwitchWidget {
+	fn pressable_state(&self) -> Ref<PressableState> {
+		self.widget_state(|v| &v.pressable)
+	}
+
+	fn pressable_state_mut(&self) -> RefMut<PressableState> {
+		self.widget_state_mut(|v| &mut v.pressable)
+	}
+
+	fn activate(&self) {
+		let on = self.state().on;
+		self.set_on(!on);
+	}
+}
@@ -0,0 +1,120 @@
+use std::cell::{Ref, RefMut};
+use crate::{
+	paint::Painter,
+	util::{Geometry, WidgetRef},
+	widgets::{Arrangements, Children, PanelState, PanelWidget, Widget, WidgetArrangement, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{scalar, Color, Color4f, Paint, PaintStyle, Rect};
+
+pub struct TooltipWidgetState {
+	panel: PanelState,
+	content: WidgetRef<dyn Widget>,
+	padding: scalar,
+	background: Paint,
+	border: Paint,
+}
+
+pub type TooltipWidget = crate::widgets::WidgetImpl<TooltipWidgetState>;
+
+pub struct TooltipWidgetBuilder(WidgetRef<TooltipWidget>);
+
+/// The frame a tooltip gets shown in, draws a background and border around the content.
+///
+/// Gets created by the event context when a tooltip gets shown, see [Widget::tooltip()].
+impl TooltipWidget {
+	pub fn new(content: WidgetRef<dyn Widget>) -> TooltipWidgetBuilder {
+		let background = Paint::new(Color4f::from(Color::from_rgb(0x30, 0x30, 0x30)), None);
+		let mut border = Paint::new(Color4f::from(Color::from_rgb(0x80, 0x80, 0x80)), None);
+		border.set_style(PaintStyle::Stroke);
+		TooltipWidgetBuilder(WidgetRef::new(TooltipWidgetState {
+			panel: Default::default(),
+			content,
+			padding: 4.0,
+			background,
+			border,
+		}.into()))
+	}
+}
+
+impl TooltipWidgetBuilder {
+	pub fn padding(self, padding: scalar) -> Self {
+		self.0.get().state_mut().padding = padding;
+		self
+	}
+
+	pub fn background(self, background: Paint) -> Self {
+		self.0.get().state_mut().background = background;
+		self
+	}
+
+	pub fn border(self, border: Paint) -> Self {
+		self.0.get().state_mut().border = border;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<TooltipWidget> {
+		self.0.get().state().content.get().set_parent(Some(self.0.clone()));
+		self.0
+	}
+}
+
+impl Widget for TooltipWidget {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.panel.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.panel.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let rect = Rect::from_wh(size.x, size.y);
+		{
+			let state = self.state();
+			painter.draw_rect(rect, &state.background);
+			painter.draw_rect(rect, &state.border);
+		}
+		self.panel_paint(geometry, layer + 1, painter)
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let padding = Vector2::new(state.padding, state.padding) * 2.0;
+		state.content.get().get_desired_size() + padding
+	}
+
+	fn get_children(&self) -> Children {
+		vec![self.state().content.clone()]
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.panel_arrange_children(geometry);
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.panel_get_arranged_children()
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.panel_cached_geometry()
+	}
+}
+
+impl PanelWidget for TooltipWidget {
+	fn panel_state(&self) -> Ref<PanelState> {
+		self.widget_state(|v| &v.panel)
+	}
+
+	fn panel_state_mut(&self) -> RefMut<PanelState> {
+		self.widget_state_mut(|v| &mut v.panel)
+	}
+
+	fn rearrange_children(&self, geometry: Geometry) -> Vec<WidgetArrangement> {
+		let state = self.state();
+		let padding = Vector2::new(state.padding, state.padding);
+		let size = geometry.local_size() - padding * 2.0;
+		vec![geometry.child_widget(state.content.clone(), padding, size)]
+	}
+}
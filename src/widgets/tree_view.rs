@@ -0,0 +1,730 @@
+use std::cell::{Ref, RefMut};
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use crate::{
+	events::{input::{Modifiers, MouseButton}, standard, Reply, WidgetEvent, WidgetFocusChange},
+	paint::Painter,
+	util::{scalar, Geometry, WidgetRef},
+	widgets::{Arrangements, Children, ListView, PanelState, PanelWidget, Widget, WidgetArrangement, WidgetState},
+};
+use cgmath::Vector2;
+use skia_safe::{Color, Font, Paint, PaintStyle, Point, Rect};
+use winit::event::VirtualKeyCode;
+use crate::widgets::WidgetImpl;
+use crate::widgets::leaf_widget::{LeafState, LeafWidget};
+
+/// Space above and below the label of a row.
+const ROW_PADDING: scalar = 3.0;
+/// Width of the column left of the label, showing the arrow of expandable nodes.
+const EXPANDER_WIDTH: scalar = 16.0;
+
+/// Look of tree views, shared by all rows of a tree view.
+pub struct TreeViewStyle {
+	pub font: Font,
+	pub foreground: Paint,
+	pub background: Paint,
+	/// Background of selected rows.
+	pub selection: Paint,
+	/// Outline of the current row while the tree view is focused.
+	pub focus: Paint,
+	/// Distance each level of the tree gets indented by.
+	pub indent: scalar,
+}
+
+impl Default for TreeViewStyle {
+	fn default() -> Self {
+		let mut foreground = Paint::default();
+		foreground.set_anti_alias(true);
+		foreground.set_color(Color::WHITE);
+		let mut background = Paint::default();
+		background.set_color(Color::from_rgb(0x30, 0x30, 0x30));
+		let mut selection = Paint::default();
+		selection.set_color(Color::from_rgb(0x33, 0x66, 0xCC));
+		let mut focus = Paint::default();
+		focus.set_color(Color::from_rgb(0x33, 0x99, 0xFF));
+		focus.set_style(PaintStyle::Stroke);
+		Self {
+			font: Font::default(),
+			foreground,
+			background,
+			selection,
+			focus,
+			indent: 16.0,
+		}
+	}
+}
+
+impl TreeViewStyle {
+	fn row_height(&self) -> scalar {
+		let metrics = self.font.metrics().1;
+		metrics.bottom - metrics.top + ROW_PADDING * 2.0
+	}
+}
+
+/// How many nodes of a tree view can be selected at a time.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SelectionMode {
+	Single,
+	/// Ctrl toggles single nodes and Shift selects ranges of rows.
+	Multi,
+}
+
+/// A node of the tree shown by a [TreeView].
+pub struct TreeItem {
+	label: String,
+	children: Vec<TreeItem>,
+	expanded: bool,
+}
+
+impl TreeItem {
+	pub fn new(label: impl Into<String>) -> Self {
+		Self {
+			label: label.into(),
+			children: Vec::new(),
+			expanded: false,
+		}
+	}
+
+	pub fn child(mut self, child: TreeItem) -> Self {
+		self.children.push(child);
+		self
+	}
+
+	pub fn children(mut self, children: Vec<TreeItem>) -> Self {
+		self.children.extend(children);
+		self
+	}
+
+	pub fn expanded(mut self, expanded: bool) -> Self {
+		self.expanded = expanded;
+		self
+	}
+}
+
+struct TreeNode {
+	label: String,
+	depth: usize,
+	parent: Option<usize>,
+	children: Vec<usize>,
+	/// End of the range of nodes in the subtree of this node, the subtree starts at the node itself.
+	end: usize,
+	expanded: bool,
+}
+
+/// Adds the items and their descendants in depth first order to the nodes.
+/// Returns the nodes of the items.
+fn add_nodes(items: Vec<TreeItem>, parent: Option<usize>, depth: usize, nodes: &mut Vec<TreeNode>) -> Vec<usize> {
+	let mut ids = Vec::with_capacity(items.len());
+	for item in items {
+		let id = nodes.len();
+		nodes.push(TreeNode {
+			label: item.label,
+			depth,
+			parent,
+			children: Vec::new(),
+			end: id + 1,
+			expanded: item.expanded,
+		});
+		let children = add_nodes(item.children, Some(id), depth + 1, nodes);
+		nodes[id].children = children;
+		nodes[id].end = nodes.len();
+		ids.push(id);
+	}
+	ids
+}
+
+/// Returns the nodes within the range that are not hidden by a collapsed node of the range.
+fn shown_nodes(nodes: &[TreeNode], start: usize, end: usize) -> Vec<usize> {
+	let mut shown = Vec::new();
+	let mut node = start;
+	while node < end {
+		shown.push(node);
+		// the subtree directly follows the node, so collapsed subtrees get skipped as a whole
+		node = match nodes[node].expanded {
+			true => node + 1,
+			false => nodes[node].end,
+		};
+	}
+	shown
+}
+
+pub struct TreeViewState {
+	panel: PanelState,
+	list: WidgetRef<ListView>,
+	nodes: Vec<TreeNode>,
+	/// Nodes shown as rows, all nodes whose ancestors are expanded in depth first order.
+	rows: Vec<usize>,
+	selection_mode: SelectionMode,
+	selected: BTreeSet<usize>,
+	/// Node the keyboard navigates from, outlined while the tree view is focused.
+	current: Option<usize>,
+	/// Node ranges selected with Shift start at.
+	anchor: Option<usize>,
+	focused: bool,
+	style: Rc<TreeViewStyle>,
+	on_selection_changed: Option<Box<dyn Fn(&[usize])>>,
+	on_expanded_changed: Option<Box<dyn Fn(usize, bool)>>,
+}
+
+pub type TreeView = WidgetImpl<TreeViewState>;
+
+pub struct TreeViewBuilder(WidgetRef<TreeView>);
+
+/// Shows a tree of labeled nodes as rows of a [ListView], so only the visible rows get widgets.
+///
+/// Nodes are identified by their index in depth first order of the items the tree view got created with.
+/// Clicking the arrow or double clicking a row expands or collapses its node, as do Left and Right.
+/// Up, Down, Home, End, PageUp and PageDown move the current node and select it.
+/// With [SelectionMode::Multi], Ctrl+click and Space toggle nodes, Shift extends the selection
+/// and the [standard::SELECT_ALL] command, Ctrl+A by default, selects all rows.
+impl TreeView {
+	pub fn new(items: Vec<TreeItem>) -> TreeViewBuilder {
+		let style = Rc::new(TreeViewStyle::default());
+		let mut panel = PanelState::default();
+		panel.widget.focusable = true;
+		let tree_view = WidgetRef::new(TreeViewState {
+			panel,
+			list: ListView::new().item_height(style.row_height()).build(),
+			nodes: Vec::new(),
+			rows: Vec::new(),
+			selection_mode: SelectionMode::Single,
+			selected: BTreeSet::new(),
+			current: None,
+			anchor: None,
+			focused: false,
+			style,
+			on_selection_changed: None,
+			on_expanded_changed: None,
+		}.into());
+
+		let list = tree_view.get().state().list.clone();
+		let weak = tree_view.downgrade();
+		list.get().set_items(
+			TreeRow::new,
+			move |row, index| {
+				weak.upgrade().inspect(|tree_view| tree_view.get().bind_row(row, index));
+			},
+		);
+		tree_view.get().set_items(items);
+		TreeViewBuilder(tree_view)
+	}
+
+	/// Replaces all nodes, clearing the selection.
+	pub fn set_items(&self, items: Vec<TreeItem>) {
+		let mut nodes = Vec::new();
+		add_nodes(items, None, 0, &mut nodes);
+		let rows = shown_nodes(&nodes, 0, nodes.len());
+		let mut state = self.state_mut();
+		state.nodes = nodes;
+		state.rows = rows;
+		state.current = None;
+		state.anchor = None;
+		drop(state);
+		self.update_rows();
+		self.set_selection(BTreeSet::new());
+	}
+
+	pub fn node_count(&self) -> usize {
+		self.state().nodes.len()
+	}
+
+	pub fn label(&self, node: usize) -> String {
+		self.state().nodes[node].label.clone()
+	}
+
+	pub fn parent(&self, node: usize) -> Option<usize> {
+		self.state().nodes[node].parent
+	}
+
+	pub fn children(&self, node: usize) -> Vec<usize> {
+		self.state().nodes[node].children.clone()
+	}
+
+	pub fn is_expanded(&self, node: usize) -> bool {
+		self.state().nodes[node].expanded
+	}
+
+	/// Expands or collapses the node, the current node moves to it if it gets hidden.
+	pub fn set_expanded(&self, node: usize, expanded: bool) {
+		let mut state = self.state_mut();
+		if state.nodes[node].expanded == expanded {
+			return;
+		}
+		state.nodes[node].expanded = expanded;
+		let end = state.nodes[node].end;
+		// only shown nodes have rows for their descendants
+		if let Ok(row) = state.rows.binary_search(&node) {
+			let descendants = match expanded {
+				true => shown_nodes(&state.nodes, node + 1, end),
+				false => Vec::new(),
+			};
+			let hidden = state.rows[row + 1..].partition_point(|descendant| *descendant < end);
+			let tail = state.rows.split_off(row + 1 + hidden);
+			state.rows.truncate(row + 1);
+			state.rows.extend(descendants);
+			state.rows.extend(tail);
+		}
+		if !expanded && state.current.is_some_and(|current| current > node && current < end) {
+			state.current = Some(node);
+		}
+		drop(state);
+		self.update_rows();
+
+		let delegate = self.state_mut().on_expanded_changed.take();
+		if let Some(delegate) = delegate {
+			delegate(node, expanded);
+			self.state_mut().on_expanded_changed = Some(delegate);
+		}
+	}
+
+	/// Returns the selected nodes in ascending order.
+	pub fn selected(&self) -> Vec<usize> {
+		self.state().selected.iter().copied().collect()
+	}
+
+	pub fn is_selected(&self, node: usize) -> bool {
+		self.state().selected.contains(&node)
+	}
+
+	/// Selects only the node and makes it the current node.
+	/// It gets scrolled into view, if it is shown.
+	pub fn select(&self, node: usize) {
+		self.set_current(node, Modifiers::empty());
+	}
+
+	pub fn clear_selection(&self) {
+		self.set_selection(BTreeSet::new());
+	}
+
+	/// Returns the node the keyboard navigates from.
+	pub fn current(&self) -> Option<usize> {
+		self.state().current
+	}
+
+	/// Copies the data of the node shown in the row into the row widget.
+	fn bind_row(&self, row: &TreeRow, index: usize) {
+		let state = self.state();
+		let node_id = state.rows[index];
+		let node = &state.nodes[node_id];
+		let mut row_state = row.state_mut();
+		row_state.style = state.style.clone();
+		row_state.label = node.label.clone();
+		row_state.depth = node.depth;
+		row_state.expander = match node.children.is_empty() {
+			true => None,
+			false => Some(node.expanded),
+		};
+		row_state.selected = state.selected.contains(&node_id);
+		row_state.current = state.focused && state.current == Some(node_id);
+		drop(row_state);
+		drop(state);
+		row.invalidate_paint();
+	}
+
+	fn update_rows(&self) {
+		let state = self.state();
+		let row_count = state.rows.len();
+		let list = state.list.clone();
+		drop(state);
+		list.get().set_item_count(row_count);
+	}
+
+	fn set_selection(&self, selected: BTreeSet<usize>) {
+		if self.state().selected == selected {
+			return;
+		}
+		self.state_mut().selected = selected;
+		let list = self.state().list.clone();
+		list.get().refresh();
+
+		let selected = self.selected();
+		let delegate = self.state_mut().on_selection_changed.take();
+		if let Some(delegate) = delegate {
+			delegate(&selected);
+			self.state_mut().on_selection_changed = Some(delegate);
+		}
+	}
+
+	/// Makes the node the current node and updates the selection like clicking it with the modifiers.
+	fn set_current(&self, node: usize, modifiers: Modifiers) {
+		let state = self.state();
+		let multi = state.selection_mode == SelectionMode::Multi;
+		let anchor = state.anchor.filter(|_| multi && modifiers.shift());
+		let row = state.rows.binary_search(&node).ok();
+		let anchor_row = anchor.and_then(|anchor| state.rows.binary_search(&anchor).ok());
+		let mut selected = state.selected.clone();
+		let list = state.list.clone();
+		drop(state);
+
+		match (anchor_row, row) {
+			(Some(anchor_row), Some(row)) => {
+				// ranges replace the selection, unless Ctrl adds them
+				if !modifiers.ctrl() {
+					selected.clear();
+				}
+				let rows = anchor_row.min(row)..=anchor_row.max(row);
+				selected.extend(self.state().rows[rows].iter().copied());
+			}
+			_ if multi && modifiers.ctrl() => {
+				if !selected.remove(&node) {
+					selected.insert(node);
+				}
+				self.state_mut().anchor = Some(node);
+			}
+			_ => {
+				selected = BTreeSet::from([node]);
+				self.state_mut().anchor = Some(node);
+			}
+		}
+		self.state_mut().current = Some(node);
+		if let Some(row) = row {
+			list.get().scroll_into_view(row);
+		}
+		list.get().refresh();
+		self.set_selection(selected);
+	}
+
+	/// Moves the current node to the row, the selection follows unless only Ctrl is held in multi selection.
+	fn move_to_row(&self, row: usize, modifiers: Modifiers) {
+		let state = self.state();
+		let node = match state.rows.get(row) {
+			Some(node) => *node,
+			None => return,
+		};
+		let list = state.list.clone();
+		let only_move = state.selection_mode == SelectionMode::Multi && modifiers.ctrl() && !modifiers.shift();
+		drop(state);
+		if only_move {
+			self.state_mut().current = Some(node);
+			list.get().scroll_into_view(row);
+			list.get().refresh();
+		} else {
+			self.set_current(node, modifiers - Modifiers::CTRL);
+		}
+	}
+
+	/// Returns the row and the node at the position in window coordinates
+	/// and whether the position is on the arrow of the node.
+	fn row_at(&self, pos: &Vector2<scalar>) -> Option<(usize, usize, bool)> {
+		let list = self.state().list.clone();
+		let row = list.get().index_at(pos)?;
+		let state = self.state();
+		let node = state.rows[row];
+		let indent = state.nodes[node].depth as scalar * state.style.indent;
+		let x = pos.x - self.cached_geometry().absolute_pos().x;
+		let on_expander = !state.nodes[node].children.is_empty() && x >= indent && x < indent + EXPANDER_WIDTH;
+		Some((row, node, on_expander))
+	}
+
+	fn handle_key(&self, key: VirtualKeyCode, modifiers: Modifiers) -> bool {
+		let state = self.state();
+		let row_count = state.rows.len();
+		if row_count == 0 {
+			return false;
+		}
+		let current = state.current;
+		let current_row = current.and_then(|current| state.rows.binary_search(&current).ok());
+		let multi = state.selection_mode == SelectionMode::Multi;
+		let list = state.list.clone();
+		drop(state);
+		let page = (list.get().cached_geometry().local_size().y / list.get().item_height()).max(1.0) as usize;
+
+		match key {
+			VirtualKeyCode::Up => self.move_to_row(current_row.map_or(0, |row| row.saturating_sub(1)), modifiers),
+			VirtualKeyCode::Down => self.move_to_row(current_row.map_or(0, |row| (row + 1).min(row_count - 1)), modifiers),
+			VirtualKeyCode::PageUp => self.move_to_row(current_row.map_or(0, |row| row.saturating_sub(page)), modifiers),
+			VirtualKeyCode::PageDown => self.move_to_row(current_row.map_or(0, |row| (row + page).min(row_count - 1)), modifiers),
+			VirtualKeyCode::Home => self.move_to_row(0, modifiers),
+			VirtualKeyCode::End => self.move_to_row(row_count - 1, modifiers),
+			VirtualKeyCode::Left => {
+				let node = match current {
+					Some(node) => node,
+					None => return false,
+				};
+				if self.is_expanded(node) && !self.children(node).is_empty() {
+					self.set_expanded(node, false);
+				} else if let Some(parent) = self.parent(node) {
+					self.set_current(parent, Modifiers::empty());
+				}
+			}
+			VirtualKeyCode::Right => {
+				let node = match current {
+					Some(node) => node,
+					None => return false,
+				};
+				let first_child = self.children(node).first().copied();
+				match first_child {
+					Some(_) if !self.is_expanded(node) => self.set_expanded(node, true),
+					Some(child) => self.set_current(child, Modifiers::empty()),
+					None => {}
+				}
+			}
+			VirtualKeyCode::Space if multi => {
+				let node = match current {
+					Some(node) => node,
+					None => return false,
+				};
+				self.set_current(node, Modifiers::CTRL);
+			}
+			_ => return false,
+		}
+		true
+	}
+}
+
+impl TreeViewBuilder {
+	pub fn selection_mode(self, selection_mode: SelectionMode) -> Self {
+		self.0.get().state_mut().selection_mode = selection_mode;
+		self
+	}
+
+	pub fn style(self, style: TreeViewStyle) -> Self {
+		let widget = self.0.get();
+		let list = widget.state().list.clone();
+		list.get().set_item_height(style.row_height());
+		widget.state_mut().style = Rc::new(style);
+		drop(widget);
+		self
+	}
+
+	/// Called with the selected nodes in ascending order whenever the selection changed.
+	pub fn on_selection_changed<F>(self, func: F) -> Self where F: Fn(&[usize]) + 'static {
+		self.0.get().state_mut().on_selection_changed = Some(Box::new(func));
+		self
+	}
+
+	/// Called with the node and its new state whenever a node got expanded or collapsed.
+	pub fn on_expanded_changed<F>(self, func: F) -> Self where F: Fn(usize, bool) + 'static {
+		self.0.get().state_mut().on_expanded_changed = Some(Box::new(func));
+		self
+	}
+
+	pub fn tab_index(self, tab_index: i32) -> Self {
+		self.0.get().state_mut().panel.widget.tab_index = tab_index;
+		self
+	}
+
+	pub fn build(self) -> WidgetRef<TreeView> {
+		self.0.get().state().list.get().set_parent(Some(self.0.clone()));
+		self.0
+	}
+}
+
+impl Widget for TreeView {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.panel.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.panel.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		{
+			let state = self.state();
+			painter.draw_rect(Rect::from_wh(size.x, size.y), &state.style.background);
+		}
+		self.panel_paint(geometry, layer + 1, painter)
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		self.state().list.get().get_desired_size()
+	}
+
+	fn get_children(&self) -> Children {
+		vec![self.state().list.clone() as WidgetRef<dyn Widget>]
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.panel_arrange_children(geometry);
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.panel_get_arranged_children()
+	}
+
+	fn on_event(&self, event: &WidgetEvent) -> Reply {
+		match event {
+			WidgetEvent::OnMouseButtonDown {
+				mouse,
+				button: MouseButton::Left,
+				pos,
+				modifiers,
+			} => {
+				match self.row_at(pos) {
+					Some((_, node, true)) => {
+						let expanded = self.is_expanded(node);
+						self.set_expanded(node, !expanded);
+					}
+					Some((_, node, false)) => self.set_current(node, *modifiers),
+					None => {}
+				}
+				Reply::handled().take_focus(WidgetFocusChange::KeyboardList(vec![*mouse]))
+			}
+			// the press already acted, handling the click keeps the focus taken by the press
+			WidgetEvent::OnClick {
+				button: MouseButton::Left,
+				..
+			} => Reply::handled(),
+			WidgetEvent::OnDoubleClick {
+				button: MouseButton::Left,
+				pos,
+				..
+			} => {
+				// presses on the arrow already toggled the node
+				if let Some((_, node, false)) = self.row_at(pos) {
+					if !self.children(node).is_empty() {
+						let expanded = self.is_expanded(node);
+						self.set_expanded(node, !expanded);
+					}
+				}
+				Reply::handled()
+			}
+			WidgetEvent::OnKeyDown { key: Some(key), modifiers, .. } => {
+				match self.handle_key(*key, *modifiers) {
+					true => Reply::handled(),
+					false => Reply::unhandled(),
+				}
+			}
+			WidgetEvent::OnCommand { command, .. } if command == standard::SELECT_ALL => {
+				if self.state().selection_mode != SelectionMode::Multi {
+					return Reply::unhandled();
+				}
+				let rows = self.state().rows.iter().copied().collect();
+				self.set_selection(rows);
+				Reply::handled()
+			}
+			WidgetEvent::OnFocus { .. } | WidgetEvent::OnUnfocus { .. } => {
+				self.state_mut().focused = matches!(event, WidgetEvent::OnFocus { .. });
+				let list = self.state().list.clone();
+				list.get().refresh();
+				Reply::handled()
+			}
+			_ => Reply::unhandled(),
+		}
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.panel_cached_geometry()
+	}
+}
+
+impl PanelWidget for TreeView {
+	fn panel_state(&self) -> Ref<PanelState> {
+		self.widget_state(|v| &v.panel)
+	}
+
+	fn panel_state_mut(&self) -> RefMut<PanelState> {
+		self.widget_state_mut(|v| &mut v.panel)
+	}
+
+	fn rearrange_children(&self, geometry: Geometry) -> Vec<WidgetArrangement> {
+		let list = self.state().list.clone();
+		vec![geometry.child_widget(list, Vector2::new(0.0, 0.0), geometry.local_size())]
+	}
+}
+
+struct TreeRowState {
+	leaf: LeafState,
+	style: Rc<TreeViewStyle>,
+	label: String,
+	depth: usize,
+	/// Whether the node is expanded, if it has children.
+	expander: Option<bool>,
+	selected: bool,
+	current: bool,
+}
+
+type TreeRow = WidgetImpl<TreeRowState>;
+
+impl TreeRow {
+	fn new() -> WidgetRef<TreeRow> {
+		WidgetRef::new(TreeRowState {
+			leaf: Default::default(),
+			style: Default::default(),
+			label: String::new(),
+			depth: 0,
+			expander: None,
+			selected: false,
+			current: false,
+		}.into())
+	}
+}
+
+impl Widget for TreeRow {
+	fn widget_state(&self) -> Ref<WidgetState> {
+		self.widget_state(|v| &v.leaf.widget)
+	}
+
+	fn widget_state_mut(&self) -> RefMut<WidgetState> {
+		self.widget_state_mut(|v| &mut v.leaf.widget)
+	}
+
+	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let size = geometry.local_size();
+		let state = self.state();
+		let style = &state.style;
+		let rect = Rect::from_wh(size.x, size.y);
+		if state.selected {
+			painter.draw_rect(rect, &style.selection);
+		}
+		let x = state.depth as scalar * style.indent;
+		if let Some(expanded) = state.expander {
+			let mut arrow = style.foreground.clone();
+			arrow.set_style(PaintStyle::Stroke);
+			arrow.set_stroke_width(1.5);
+			let center = Point::new(x + EXPANDER_WIDTH / 2.0, size.y / 2.0);
+			if expanded {
+				let tip = Point::new(center.x, center.y + 2.0);
+				painter.draw_line(Point::new(tip.x - 4.0, tip.y - 4.0), tip, &arrow);
+				painter.draw_line(tip, Point::new(tip.x + 4.0, tip.y - 4.0), &arrow);
+			} else {
+				let tip = Point::new(center.x + 2.0, center.y);
+				painter.draw_line(Point::new(tip.x - 4.0, tip.y - 4.0), tip, &arrow);
+				painter.draw_line(tip, Point::new(tip.x - 4.0, tip.y + 4.0), &arrow);
+			}
+		}
+		let metrics = style.font.metrics().1;
+		let base_line = (size.y - (metrics.bottom - metrics.top)) / 2.0 - metrics.top;
+		painter.draw_str(&state.label, Point::new(x + EXPANDER_WIDTH, base_line), &style.font, &style.foreground);
+		if state.current {
+			painter.draw_rect(rect.with_inset((0.5, 0.5)), &style.focus);
+		}
+		layer + 1
+	}
+
+	fn get_desired_size(&self) -> Vector2<scalar> {
+		let state = self.state();
+		let style = &state.style;
+		let width = style.font.measure_str(&state.label, Some(&style.foreground)).0;
+		let x = state.depth as scalar * style.indent + EXPANDER_WIDTH;
+		Vector2::new(x + width + ROW_PADDING, style.row_height())
+	}
+
+	fn get_children(&self) -> Children {
+		self.leaf_get_children()
+	}
+
+	fn arrange_children(&self, geometry: Geometry) {
+		self.leaf_arrange_children(geometry)
+	}
+
+	fn get_arranged_children(&self) -> Arrangements {
+		self.leaf_get_arranged_children()
+	}
+
+	fn cached_geometry(&self) -> Geometry {
+		self.leaf_cached_geometry()
+	}
+}
+
+impl LeafWidget for TreeRow {
+	fn leaf_state(&self) -> Ref<LeafState> {
+		self.widget_state(|v| &v.leaf)
+	}
+
+	fn leaf_state_mut(&self) -> RefMut<LeafState> {
+		self.widget_state_mut(|v| &mut v.leaf)
+	}
+}
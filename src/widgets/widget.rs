@@ -1,13 +1,15 @@
 use std::cell::{Ref, RefMut};
+use std::collections::HashSet;
 use std::rc::Weak;
 use crate::{
-	events::{Reply, WidgetEvent},
+	events::{input::CursorIcon, GestureKind, Reply, Tooltip, WidgetEvent},
 	paint::Painter,
 	util::{Geometry, WidgetRef},
+	widgets::Window,
 };
 use cgmath::Vector2;
 
-use skia_safe::scalar;
+use skia_safe::{scalar, Contains, Picture, PictureRecorder, Rect};
 use crate::util::{WidgetRefFromSelf, WidgetRefFromSelfSpecific, SharedRef, WidgetWeak};
 
 /// Holds a widget and its respective geometry it got when last arranged.
@@ -36,6 +38,74 @@ pub type Arrangements = Vec<WidgetArrangement>;
 pub struct WidgetState {
 	pub parent: Option<WidgetRef<dyn Widget>>,
 	pub cached_geometry: Geometry,
+	/// The children of this widget have to be rearranged before the next paint.
+	pub layout_dirty: bool,
+	/// At least one widget further down the tree has a dirty layout.
+	pub children_layout_dirty: bool,
+	/// This widget or at least one widget further down the tree has to be painted again.
+	pub paint_dirty: bool,
+	/// If set, the painted output of this widget gets recorded and replayed until it gets invalidated.
+	pub cache_paint: bool,
+	pub paint_cache: Option<PaintCache>,
+	/// The widget can receive keyboard focus through focus navigation.
+	pub focusable: bool,
+	/// Position of the widget in the focus navigation order, lower indices get focused first.
+	pub tab_index: i32,
+	/// Gestures the widget wants to receive as [WidgetEvent::OnGesture] when touched.
+	pub gestures: HashSet<GestureKind>,
+	/// The cursor shown while the widget is hovered or captures the cursor, None leaves it to the parent.
+	pub cursor_icon: Option<CursorIcon>,
+	/// Shown next to the cursor after it rested on the widget for a moment.
+	pub tooltip: Option<Tooltip>,
+	/// Areas in window coordinates that have to be repainted.
+	/// Only collected in the root widget of a tree, normally the window.
+	pub damage: Vec<Rect>,
+}
+
+/// Maximum number of separate damaged areas a widget state collects,
+/// once exceeded they get merged into their bounds.
+const MAX_DAMAGE_AREAS: usize = 16;
+
+impl WidgetState {
+	/// Adds the given area in window coordinates to the damage.
+	///
+	/// Areas already covered by the damage are skipped.
+	/// As roots that never get painted (like detached widget trees) never take their damage,
+	/// the areas get merged into their bounds once there are more than [MAX_DAMAGE_AREAS].
+	pub fn add_damage(&mut self, area: Rect) {
+		if area.is_empty() || self.damage.iter().any(|damage| damage.contains(area)) {
+			return;
+		}
+		self.damage.retain(|damage| !area.contains(*damage));
+		self.damage.push(area);
+		if self.damage.len() > MAX_DAMAGE_AREAS {
+			let mut bounds = self.damage[0];
+			for damage in &self.damage[1..] {
+				bounds.join(damage);
+			}
+			self.damage = vec![bounds];
+		}
+	}
+}
+
+/// Returns the area the given geometry covers in window coordinates.
+fn geometry_area(geometry: &Geometry) -> Rect {
+	Rect::from_xywh(
+		geometry.absolute_pos().x,
+		geometry.absolute_pos().y,
+		geometry.local_size().x,
+		geometry.local_size().y,
+	)
+}
+
+/// The recorded painted output of a widget that has paint caching enabled.
+#[derive(Clone)]
+pub struct PaintCache {
+	pub picture: Picture,
+	/// The size the widget was painted with, if it changes the cache is invalid.
+	pub size: Vector2<scalar>,
+	/// The number of layers the widget used while painting.
+	pub layers: i32,
 }
 
 /// A widget is the basic trait needed for any GUI "Element" to correctly interface
@@ -104,14 +174,207 @@ pub trait Widget: WidgetRefFromSelf {
 		self.widget_state().parent.clone()
 	}
 
+	/// Returns the root of the widget tree this widget is part of, normally the window.
+	/// If the widget has no parent, the widget itself is the root.
+	fn get_root(&self) -> WidgetRef<dyn Widget> {
+		let mut root = self.widget_ref();
+		let mut parent = self.get_parent();
+		while let Some(widget) = parent {
+			parent = widget.get().get_parent();
+			root = widget;
+		}
+		root
+	}
+
+	/// Returns true if the widget can receive the keyboard focus through focus navigation
+	/// like Tab and Shift+Tab.
+	///
+	/// # Default Implementation
+	/// Returns the focusable flag of the widget state.
+	fn is_focusable(&self) -> bool {
+		self.widget_state().focusable
+	}
+
+	/// Returns the position of the widget in the focus navigation order.
+	/// Focusable widgets with a lower tab index get focused first,
+	/// widgets with the same tab index in the order they appear in the widget tree.
+	///
+	/// # Default Implementation
+	/// Returns the tab index of the widget state.
+	fn tab_index(&self) -> i32 {
+		self.widget_state().tab_index
+	}
+
+	/// Returns the gestures the widget wants to receive.
+	/// The first widget under a touch that subscribed to any gesture recognizes the gestures of all fingers touching it.
+	///
+	/// # Default Implementation
+	/// Returns the gestures of the widget state.
+	fn gestures(&self) -> HashSet<GestureKind> {
+		self.widget_state().gestures.clone()
+	}
+
+	/// Returns the cursor icon the widget wants while it is hovered or captures the cursor.
+	/// If it returns None, the icon of the parent is used.
+	///
+	/// # Default Implementation
+	/// Returns the cursor icon of the widget state.
+	fn cursor_icon(&self) -> Option<CursorIcon> {
+		self.widget_state().cursor_icon.clone()
+	}
+
+	/// Returns the tooltip shown when the cursor rests on the widget.
+	/// The deepest widget under the cursor with a tooltip shows it.
+	///
+	/// # Default Implementation
+	/// Returns the tooltip of the widget state.
+	fn tooltip(&self) -> Option<Tooltip> {
+		self.widget_state().tooltip.clone()
+	}
+
+	/// Changes the tooltip stored in the widget state.
+	/// A tooltip already shown stays until it gets dismissed.
+	fn set_tooltip(&self, tooltip: Option<Tooltip>) {
+		self.widget_state_mut().tooltip = tooltip;
+	}
+
+	/// Requests that the given area in window coordinates becomes visible,
+	/// e.g. by scrolling a [crate::widgets::ScrollPanel] containing this widget.
+	/// Returns true if anything got scrolled.
+	///
+	/// # Default Implementation
+	/// Forwards the request to the parent widget.
+	fn bring_into_view(&self, area: Rect) -> bool {
+		match self.get_parent() {
+			Some(parent) => parent.get().bring_into_view(area),
+			None => false,
+		}
+	}
+
 	/// Allows to change the parent of this widget.
 	/// This should only be called by a widget that is about to add it to its children.
 	/// The caller has to ensure the widget does not have a parent already.
 	///
 	/// # Default Implementation
 	/// Stores the new parent in the widget state and invalidates the widget fully.
+	/// The new parent is not notified, as it is about to arrange the widget anyway.
 	fn set_parent(&self, parent: Option<WidgetRef<dyn Widget>>) {
-		self.widget_state_mut().parent = parent;
+		let mut state = self.widget_state_mut();
+		state.parent = parent;
+		state.layout_dirty = true;
+		state.paint_dirty = true;
+	}
+
+	/// Marks the layout of this widget as dirty, so the system rearranges its children
+	/// before the widget gets painted the next time.
+	/// All ancestors get marked as having a child with a dirty layout, so the system can find
+	/// the dirty subtrees without rearranging the whole tree.
+	///
+	/// Invalidating the layout also invalidates the paint.
+	/// If the desired size of the widget changed, invalidate the layout of the parent instead.
+	fn invalidate_layout(&self) {
+		self.widget_state_mut().layout_dirty = true;
+		let mut parent = self.get_parent();
+		while let Some(widget) = parent {
+			widget.get().widget_state_mut().children_layout_dirty = true;
+			parent = widget.get().get_parent();
+		}
+		self.invalidate_paint();
+	}
+
+	/// Marks this widget and all its ancestors as dirty paint,
+	/// causing the window containing this widget to be painted again.
+	/// The area the widget got arranged to last gets added to the damage of the root widget.
+	fn invalidate_paint(&self) {
+		let damage = geometry_area(&self.cached_geometry());
+
+		self.widget_state_mut().paint_dirty = true;
+		let mut root = None;
+		let mut parent = self.get_parent();
+		while let Some(widget) = parent {
+			widget.get().widget_state_mut().paint_dirty = true;
+			parent = widget.get().get_parent();
+			root = Some(widget);
+		}
+		match root {
+			Some(root) => root.get().widget_state_mut().add_damage(damage),
+			None => self.widget_state_mut().add_damage(damage),
+		}
+	}
+
+	/// Adds the area the widget got arranged to before as well as the new area
+	/// to the damage of the root widget, if the widget moved or got resized.
+	/// Otherwise the area the widget left would keep showing what it painted before.
+	///
+	/// Implementations of [arrange_children()] that cache the geometry should call this
+	/// with the previously cached geometry.
+	/// Unlike [invalidate_paint()] no ancestor gets marked as dirty paint,
+	/// as arranging a widget is always caused by a parent that already got invalidated.
+	fn damage_geometry_change(&self, old: Geometry, new: Geometry) {
+		if old.absolute_pos() == new.absolute_pos() && old.local_size() == new.local_size() {
+			return;
+		}
+		let root = self.get_root();
+		let root = root.get();
+		let mut state = root.widget_state_mut();
+		state.add_damage(geometry_area(&old));
+		state.add_damage(geometry_area(&new));
+	}
+
+	/// Enables or disables caching of the painted output of this widget.
+	///
+	/// If enabled, the output of [paint()] gets recorded as picture and replayed by [paint_cached()]
+	/// until the paint of this widget (or any widget further down the tree) gets invalidated.
+	/// Useful for widgets with a large amount of children that rarely change.
+	fn set_cache_paint(&self, cache_paint: bool) {
+		let mut state = self.widget_state_mut();
+		state.cache_paint = cache_paint;
+		state.paint_cache = None;
+	}
+
+	/// Paints the widget using [paint()] and marks the widget as painted.
+	/// If paint caching is enabled for this widget, the recorded output of the last paint gets
+	/// replayed instead as long as the widget did not get invalidated and the size did not change.
+	///
+	/// Parents should use this function instead of [paint()] to paint their children.
+	fn paint_cached(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
+		let (cache_paint, paint_dirty) = {
+			let state = self.widget_state();
+			(state.cache_paint, state.paint_dirty)
+		};
+		if !cache_paint {
+			let layer = self.paint(geometry, layer, painter);
+			self.widget_state_mut().paint_dirty = false;
+			return layer;
+		}
+
+		let size = geometry.local_size();
+		let cache = match &self.widget_state().paint_cache {
+			Some(cache) if !paint_dirty && cache.size == size => Some(cache.clone()),
+			_ => None,
+		};
+		let cache = match cache {
+			Some(cache) => cache,
+			None => {
+				let mut recorder = PictureRecorder::new();
+				let canvas = recorder.begin_recording(Rect::from_wh(size.x, size.y), None);
+				let layers = self.paint(geometry, layer, canvas) - layer;
+				let picture = recorder
+					.finish_recording_as_picture(None)
+					.expect("Unable to record widget paint cache");
+				let cache = PaintCache {
+					picture,
+					size,
+					layers,
+				};
+				let mut state = self.widget_state_mut();
+				state.paint_cache = Some(cache.clone());
+				state.paint_dirty = false;
+				cache
+			}
+		};
+		painter.draw_picture(&cache.picture, None, None);
+		layer + cache.layers
 	}
 
 	/// Provides the geometry, layer and needed painter to start drawing the widget it self.
@@ -141,6 +404,25 @@ pub trait Widget: WidgetRefFromSelf {
 	/// To rearrange the child widgets, invalidate the layout of this widget.
 	fn get_arranged_children(&self) -> Arrangements;
 
+	/// Returns the arranged children that can be hit by the cursor at the given position in window coordinates.
+	/// Used to find the widgets under the cursor, so widgets can hide children covered by others.
+	///
+	/// # Default Implementation
+	/// Returns all arranged children.
+	fn get_hit_test_children(&self, pos: &Vector2<scalar>) -> Arrangements {
+		let _ = pos;
+		self.get_arranged_children()
+	}
+
+	/// Returns the widget as window if it is one, e.g. to open popups in the window of a widget
+	/// using [get_root()].
+	///
+	/// # Default Implementation
+	/// Returns None.
+	fn as_window(&self) -> Option<&dyn Window> {
+		None
+	}
+
 	/// Called by the system when an event (mostly user input) occurs.
 	///
 	/// # Default Implementation
@@ -150,6 +432,17 @@ pub trait Widget: WidgetRefFromSelf {
 		Reply::unhandled()
 	}
 
+	/// Called by the system before [Widget::on_event()] with events routed along a widget path,
+	/// going from the root down to the target, so parents can intercept events before their children see them.
+	/// Replying with handled stops the routing, neither further previews nor the bubbling get sent.
+	///
+	/// # Default Implementation
+	/// Replies to the event as unhandled.
+	fn on_preview_event(&self, event: &WidgetEvent) -> Reply {
+		let _ = event;
+		Reply::unhandled()
+	}
+
 	/// Returns the geometry to which the children were arranged to last and that i5s used for painting.
 	///
 	/// # Default Implementation
@@ -157,6 +450,61 @@ pub trait Widget: WidgetRefFromSelf {
 	fn cached_geometry(&self) -> Geometry;
 }
 
+/// Maximum number of layout passes [validate_layout()] runs until the layout settles.
+const MAX_LAYOUT_PASSES: usize = 8;
+
+/// Rearranges all widgets of the given widget tree whose layout got invalidated
+/// using the geometry they got arranged with last time.
+///
+/// Subtrees without any dirty layout are skipped.
+/// Widgets may invalidate the layout again while getting arranged
+/// (e.g. if their desired size depends on the size they got arranged with),
+/// so passes get repeated until nothing is dirty anymore or [MAX_LAYOUT_PASSES] is reached.
+pub fn validate_layout<W: Widget + ?Sized>(widget: &W) {
+	for _ in 0..MAX_LAYOUT_PASSES {
+		{
+			let state = widget.widget_state();
+			if !state.layout_dirty && !state.children_layout_dirty {
+				return;
+			}
+		}
+		validate_layout_pass(widget);
+	}
+}
+
+fn validate_layout_pass<W: Widget + ?Sized>(widget: &W) {
+	let (layout_dirty, children_layout_dirty) = {
+		let mut state = widget.widget_state_mut();
+		let flags = (state.layout_dirty, state.children_layout_dirty);
+		state.layout_dirty = false;
+		state.children_layout_dirty = false;
+		flags
+	};
+	if layout_dirty {
+		// the whole subtree gets arranged, so nothing in it stays dirty,
+		// except if it gets invalidated again while arranging
+		for child in widget.get_arranged_children() {
+			clear_layout_dirty(&*child.widget.get());
+		}
+		widget.arrange_children(widget.cached_geometry());
+	} else if children_layout_dirty {
+		for child in widget.get_arranged_children() {
+			validate_layout_pass(&*child.widget.get());
+		}
+	}
+}
+
+fn clear_layout_dirty<W: Widget + ?Sized>(widget: &W) {
+	{
+		let mut state = widget.widget_state_mut();
+		state.layout_dirty = false;
+		state.children_layout_dirty = false;
+	}
+	for child in widget.get_arranged_children() {
+		clear_layout_dirty(&*child.widget.get());
+	}
+}
+
 pub struct WidgetImpl<T> where T: 'static, Self: Widget {
 	state: SharedRef<T>,
 	self_ref: WidgetWeak<Self>,
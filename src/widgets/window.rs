@@ -2,15 +2,20 @@ use std::cell::{Ref, RefMut};
 use crate::{
 	paint::Painter,
 	util::{Geometry, WidgetRef, WindowId},
-	widgets::{Widget, WidgetArrangement, WidgetState},
+	widgets::{popup::popups_closed, Popup, PopupStack, Widget, WidgetArrangement, WidgetState},
 };
 use cgmath::Vector2;
 
-use skia_safe::scalar;
+use skia_safe::{scalar, IRect, Region, RegionOp, RoundOut};
 use crate::widgets::{Arrangements, Children, WidgetImpl};
 
 pub trait Window: Widget {
-	fn draw(&self, canvas: &mut skia_safe::Canvas, size: (scalar, scalar)) {
+	/// Paints the window into the canvas.
+	///
+	/// Only the areas damaged since the last draw get cleared and painted again,
+	/// the rest of the canvas is expected to still hold the previous output.
+	/// Returns the damaged areas, so the platform only has to present those.
+	fn draw(&self, canvas: &mut skia_safe::Canvas, size: (scalar, scalar)) -> Vec<IRect> {
 		let geometry = Geometry::new(
 			Vector2::new(0.0, 0.0),
 			Vector2::new(size.0, size.1),
@@ -18,14 +23,104 @@ pub trait Window: Widget {
 			Vector2::new(1.0, 1.0),
 		);
 
-		canvas.clear(skia_safe::Color::DARK_GRAY);
+		let bounds = IRect::from_wh(size.0.ceil() as i32, size.1.ceil() as i32);
+		let damage: Vec<IRect> = std::mem::take(&mut self.widget_state_mut().damage)
+			.iter()
+			.filter_map(|rect| IRect::intersect(&rect.round_out(), &bounds))
+			.collect();
+		if damage.is_empty() {
+			return damage;
+		}
+		let mut region = Region::new();
+		for rect in &damage {
+			region.op_rect(rect, RegionOp::Union);
+		}
+
 		canvas.save();
-		self.paint(geometry, 0, canvas);
+		canvas.clip_region(&region, None);
+		canvas.clear(skia_safe::Color::DARK_GRAY);
+		self.paint_cached(geometry, 0, canvas);
 		canvas.restore();
+		damage
 	}
 
 	fn id(&self) -> Option<WindowId>;
 	fn set_id(&mut self, id: Option<WindowId>);
+
+	/// Returns the popups of the window, they get painted above the content and hit first.
+	fn popups(&self) -> Ref<PopupStack>;
+	fn popups_mut(&self) -> RefMut<PopupStack>;
+
+	/// Opens the popup above the content and all other popups of the window.
+	fn open_popup(&self, popup: Popup) {
+		popup.widget().get().set_parent(Some(self.widget_ref()));
+		self.popups_mut().push(popup);
+		self.invalidate_layout();
+	}
+
+	/// Closes the popup of the given widget and all popups above it.
+	fn close_popup(&self, widget: &WidgetRef<dyn Widget>) {
+		let closed = self.popups_mut().remove(widget);
+		close_popups(self, closed);
+	}
+
+	fn close_all_popups(&self) {
+		let bottom = self.popups().widgets().into_iter().next();
+		if let Some(bottom) = bottom {
+			self.close_popup(&bottom);
+		}
+	}
+
+	/// Closes the light dismissable popups on top that do not contain the given position.
+	/// Returns true if any popup got closed.
+	fn dismiss_popups_at(&self, pos: &Vector2<scalar>) -> bool {
+		let closed = self.popups_mut().dismiss_outside(pos);
+		close_popups(self, closed)
+	}
+
+	/// Closes the popup on top if it is light dismissable, returns true if it got closed.
+	fn dismiss_top_popup(&self) -> bool {
+		let closed = self.popups_mut().dismiss_top();
+		close_popups(self, closed.into_iter().collect())
+	}
+
+	/// Returns the widget of the modal popup closest to the top, it blocks input to everything below.
+	fn modal_popup(&self) -> Option<WidgetRef<dyn Widget>> {
+		self.popups().top_modal()
+	}
+
+	/// Shows the widget at the given position in window coordinates above the content and all popups,
+	/// or moves it there if it already is shown.
+	/// Overlays like tooltips never receive any input and stay until they get hidden again.
+	fn show_overlay(&self, widget: WidgetRef<dyn Widget>, pos: Vector2<scalar>) {
+		if self.popups().overlays().iter().any(|overlay| overlay.widget == widget) {
+			widget.get().invalidate_paint();
+		} else {
+			widget.get().set_parent(Some(self.widget_ref()));
+		}
+		let size = widget.get().get_desired_size();
+		let overlay = self.cached_geometry().child_widget(widget.clone(), pos, size);
+		widget.get().arrange_children(overlay.geometry);
+		self.popups_mut().set_overlay(overlay);
+		widget.get().invalidate_paint();
+	}
+
+	fn hide_overlay(&self, widget: &WidgetRef<dyn Widget>) {
+		if self.popups_mut().remove_overlay(widget) {
+			widget.get().invalidate_paint();
+			widget.get().set_parent(None);
+		}
+	}
+}
+
+/// Notifies the closed popups and rearranges the window, returns true if any popup got closed.
+fn close_popups<W: Window + ?Sized>(window: &W, closed: Vec<Popup>) -> bool {
+	if closed.is_empty() {
+		return false;
+	}
+	popups_closed(closed);
+	window.invalidate_layout();
+	true
 }
 
 pub struct WindowWidgetState {
@@ -34,6 +129,7 @@ pub struct WindowWidgetState {
 	content: Option<WidgetRef<dyn Widget>>,
 	cached_content: Option<WidgetArrangement>,
 	cached_geometry: Geometry,
+	popups: PopupStack,
 }
 
 pub type WindowWidget = WidgetImpl<WindowWidgetState>;
@@ -48,6 +144,7 @@ impl WindowWidget {
 			content,
 			cached_content: None,
 			cached_geometry: Default::default(),
+			popups: PopupStack::default(),
 		}.into())
 	}
 }
@@ -66,6 +163,14 @@ impl Window for WindowWidget {
 	fn set_id(&mut self, id: Option<WindowId>) {
 		self.state_mut().window_id = id;
 	}
+
+	fn popups(&self) -> Ref<PopupStack> {
+		self.widget_state(|v| &v.popups)
+	}
+
+	fn popups_mut(&self) -> RefMut<PopupStack> {
+		self.widget_state_mut(|v| &mut v.popups)
+	}
 }
 
 impl Widget for WindowWidget {
@@ -78,11 +183,13 @@ impl Widget for WindowWidget {
 	}
 
 	fn paint(&self, geometry: Geometry, layer: i32, painter: &mut Painter) -> i32 {
-		if let Some(content) = self.state().content.clone() {
-			content.get().paint(geometry, 0, painter)
+		let layer = if let Some(content) = self.state().content.clone() {
+			content.get().paint_cached(geometry, layer, painter)
 		} else {
 			layer
-		}
+		};
+		let popups = self.state().popups.clone();
+		popups.paint(geometry, layer, painter)
 	}
 
 	fn get_desired_size(&self) -> Vector2<scalar> {
@@ -94,13 +201,18 @@ impl Widget for WindowWidget {
 	}
 
 	fn get_children(&self) -> Children {
-		match &self.state().content {
-			Some(content) => vec![content.clone()],
-			None => vec![]
-		}
+		let state = self.state();
+		state
+			.content
+			.iter()
+			.cloned()
+			.chain(state.popups.widgets())
+			.chain(state.popups.overlays().iter().map(|overlay| overlay.widget.clone()))
+			.collect()
 	}
 
 	fn arrange_children(&self, geometry: Geometry) {
+		self.state_mut().cached_geometry = geometry;
 		let content = self.state().content.clone();
 		self.state_mut().cached_content = match content {
 			Some(content) => {
@@ -108,11 +220,36 @@ impl Widget for WindowWidget {
 				Some(WidgetArrangement::new(content, geometry))
 			},
 			None => None,
+		};
+
+		let popups = self.state().popups.place(geometry);
+		for popup in &popups {
+			popup.widget.get().arrange_children(popup.geometry);
+		}
+		self.state_mut().popups.set_arrangements(popups);
+
+		// overlays keep their position, only their content has to be arranged again
+		let overlays = self.state().popups.overlays().to_vec();
+		for overlay in overlays {
+			overlay.widget.get().arrange_children(overlay.geometry);
 		}
 	}
 
 	fn get_arranged_children(&self) -> Arrangements {
-		vec![self.state().cached_content.clone()].into_iter().filter_map(|v| v).collect()
+		let state = self.state();
+		state.cached_content.iter().cloned().chain(state.popups.arrangements()).collect()
+	}
+
+	fn get_hit_test_children(&self, pos: &Vector2<scalar>) -> Arrangements {
+		let state = self.state();
+		match state.popups.hit_test(pos) {
+			Some(popup) => popup.into_iter().collect(),
+			None => state.cached_content.iter().cloned().collect(),
+		}
+	}
+
+	fn as_window(&self) -> Option<&dyn Window> {
+		Some(self)
 	}
 
 	fn cached_geometry(&self) -> Geometry {